@@ -0,0 +1,553 @@
+//! Command-line modes that run without opening the window.
+
+use crate::constants::SUPPORTED_EXTENSIONS;
+use crate::convert::{
+    convert_each_format, convert_image, convert_one, create_zip_sink, encoder_quality,
+    finish_zip_sink, run_benchmark, zip_output_path, OutputTarget,
+};
+use crate::settings;
+use crate::state::{
+    ConversionOptions, ExistingFilePolicy, FrameChoice, ImageFormat, OutputColorSpace, ResizeMode,
+};
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Handles CLI-only arguments, returning an exit code when the GUI should not start.
+/// Any `--` flag runs headless; bare paths only pre-populate the GUI queue.
+pub fn run(args: &[String]) -> Option<i32> {
+    match args.first().map(String::as_str) {
+        Some("--benchmark") => {
+            attach_console();
+            let Some(path) = args.get(1) else {
+                eprintln!("Usage: --benchmark <file>");
+                return Some(2);
+            };
+            Some(benchmark(Path::new(path)))
+        }
+        _ if args.iter().any(|a| a.starts_with("--")) => {
+            attach_console();
+            Some(convert_from_args(args))
+        }
+        _ => None,
+    }
+}
+
+/// Image paths passed when the OS opens files with the app, to pre-populate the GUI queue.
+/// Missing or unsupported arguments are skipped with a log note.
+pub fn gui_inputs(args: &[String]) -> Vec<PathBuf> {
+    args.iter()
+        .filter_map(|arg| {
+            let path = PathBuf::from(arg);
+            let supported = path
+                .extension()
+                .and_then(|e| e.to_str())
+                .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str()));
+            if !path.is_file() {
+                log::info!("Ignoring argument {}: no such file", arg);
+                None
+            } else if !supported {
+                log::info!("Ignoring argument {}: unsupported file type", arg);
+                None
+            } else {
+                Some(path)
+            }
+        })
+        .collect()
+}
+
+/// Where a headless conversion writes its output.
+enum CliOutput {
+    /// Named like GUI outputs, into `--out` or the saved output folder.
+    Folder,
+    /// The encoded bytes of a single input, for shell pipelines.
+    Stdout,
+    /// Exactly this file, for a single input.
+    File(PathBuf),
+}
+
+/// Parses the flags and runs the conversion they ask for.
+fn convert_from_args(args: &[String]) -> i32 {
+    // Piped and single-file output reproduce "Copy as CLI command" exactly, so they start
+    // from defaults; batches start from the saved settings.
+    let single = args.iter().any(|a| a == "--stdout" || a == "--out-file");
+    let base = if single {
+        ConversionOptions::default()
+    } else {
+        settings::load_settings()
+    };
+    let (options, inputs, output) = match parse_args(args, base) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("{:#}", e);
+            return 2;
+        }
+    };
+    match output {
+        CliOutput::Folder => convert_batch(&options, &inputs),
+        CliOutput::Stdout | CliOutput::File(_) => {
+            let [input] = inputs.as_slice() else {
+                eprintln!("Usage: --stdout|--out-file <path> [OPTIONS] <file> (exactly one input)");
+                return 2;
+            };
+            convert_single(input, &options, output)
+        }
+    }
+}
+
+/// Converts a single input and writes the encoded bytes to stdout or to an exact path.
+fn convert_single(input: &Path, options: &ConversionOptions, output: CliOutput) -> i32 {
+    let result = match output {
+        CliOutput::File(path) => convert_one(input, &path, options),
+        _ => {
+            let mut stdout = std::io::stdout().lock();
+            if stdout.is_terminal() {
+                eprintln!("Refusing to write image data to a terminal; pipe or redirect stdout");
+                return 2;
+            }
+            convert_image(input, options, 0, OutputTarget::Stream(&mut stdout))
+        }
+    };
+    match result {
+        Ok(output) => {
+            if let Some(note) = output.note {
+                eprintln!("{}", note);
+            }
+            0
+        }
+        Err(e) => {
+            eprintln!("Conversion failed: {:#}", e);
+            1
+        }
+    }
+}
+
+/// Converts every input like a GUI batch, printing one OK/ERR line per file and a summary.
+/// Exits with 1 if any file failed.
+fn convert_batch(options: &ConversionOptions, inputs: &[PathBuf]) -> i32 {
+    let (inputs, unmatched) = expand_inputs(inputs);
+    for arg in &unmatched {
+        println!("ERR  {}: no matching files", arg);
+    }
+    if inputs.is_empty() {
+        eprintln!("Usage: [OPTIONS] [--out <folder>] <file|folder|pattern>...");
+        return 2;
+    }
+    let sink = if options.zip_output {
+        match create_zip_sink(&zip_output_path(options, &inputs[0])) {
+            Ok(sink) => Some(sink),
+            Err(e) => {
+                eprintln!("{:#}", e);
+                return 1;
+            }
+        }
+    } else {
+        None
+    };
+    let failed = AtomicUsize::new(unmatched.len());
+    inputs.par_iter().enumerate().for_each(|(index, input)| {
+        match convert_each_format(input, options, index, sink.as_ref()) {
+            Ok(report) => {
+                let output = report
+                    .path
+                    .map(|p| p.display().to_string())
+                    .unwrap_or_default();
+                println!(
+                    "OK   {} -> {} ({} KB)",
+                    input.display(),
+                    output,
+                    report.size.div_ceil(1024)
+                );
+            }
+            Err(e) => {
+                failed.fetch_add(1, Ordering::Relaxed);
+                println!("ERR  {}: {:#}", input.display(), e);
+            }
+        }
+    });
+    let mut failed = failed.into_inner();
+    if let Some(sink) = &sink {
+        if let Err(e) = finish_zip_sink(sink) {
+            eprintln!("Could not finish ZIP archive: {:#}", e);
+            failed = inputs.len() + unmatched.len();
+        }
+    }
+    println!(
+        "{} converted, {} failed",
+        inputs.len() + unmatched.len() - failed,
+        failed
+    );
+    i32::from(failed > 0)
+}
+
+/// Expands folders to the supported images directly inside them and `*`/`?` patterns in the
+/// file name to the matching files, since shells like cmd.exe pass them through unexpanded.
+/// Returns the files in a stable order and the arguments that matched nothing.
+fn expand_inputs(inputs: &[PathBuf]) -> (Vec<PathBuf>, Vec<String>) {
+    let mut files = Vec::new();
+    let mut unmatched = Vec::new();
+    for input in inputs {
+        let name = input.file_name().unwrap_or_default().to_string_lossy();
+        let pattern = name
+            .contains(['*', '?'])
+            .then(|| name.chars().collect::<Vec<_>>());
+        let dir = match &pattern {
+            Some(_) => input
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new(".")),
+            None if input.is_dir() => input.as_path(),
+            None => {
+                files.push(input.clone());
+                continue;
+            }
+        };
+        let mut matched: Vec<PathBuf> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.is_file())
+            .filter(|path| match &pattern {
+                Some(pattern) => {
+                    let name: Vec<char> = path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .chars()
+                        .collect();
+                    wildcard_match(pattern, &name)
+                }
+                None => path
+                    .extension()
+                    .and_then(|e| e.to_str())
+                    .is_some_and(|e| SUPPORTED_EXTENSIONS.contains(&e.to_lowercase().as_str())),
+            })
+            .collect();
+        if matched.is_empty() {
+            unmatched.push(input.display().to_string());
+        }
+        matched.sort();
+        files.extend(matched);
+    }
+    (files, unmatched)
+}
+
+/// Matches a file name against a pattern where `*` is any run of characters and `?` one.
+fn wildcard_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            wildcard_match(&pattern[1..], name)
+                || (!name.is_empty() && wildcard_match(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => wildcard_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => wildcard_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// Reads every flag `command_line` emits on top of `options`, plus the output choice:
+/// `--stdout`, `--out-file <path>`, or `--out <folder>` and `--zip` for batches.
+fn parse_args(
+    args: &[String],
+    mut options: ConversionOptions,
+) -> Result<(ConversionOptions, Vec<PathBuf>, CliOutput)> {
+    let mut inputs = Vec::new();
+    let mut output = CliOutput::Folder;
+    let mut output_flags = 0;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let Some(name) = arg.strip_prefix("--") else {
+            inputs.push(PathBuf::from(arg));
+            continue;
+        };
+        let mut value = || {
+            args.next()
+                .map(String::as_str)
+                .with_context(|| format!("--{} needs a value", name))
+        };
+        match name {
+            "stdout" => {
+                output = CliOutput::Stdout;
+                output_flags += 1;
+            }
+            "out-file" => {
+                output = CliOutput::File(PathBuf::from(value()?));
+                output_flags += 1;
+            }
+            "out" => {
+                options.use_custom_output = true;
+                options.custom_output_path = Some(PathBuf::from(value()?));
+                output_flags += 1;
+            }
+            "format" => {
+                options.format = match value()?.to_lowercase().as_str() {
+                    "jpg" | "jpeg" => ImageFormat::Jpeg,
+                    "png" => ImageFormat::Png,
+                    "webp" => ImageFormat::WebP,
+                    other => anyhow::bail!("Unknown format {}", other),
+                }
+            }
+            "keep-format" => options.keep_source_format = true,
+            "quality" => {
+                options.quality = number::<u8>(name, value()?)?.clamp(1, 100);
+                options.unified_quality = false;
+            }
+            "lossless" => options.webp_lossless = true,
+            "no-png-optimize" => options.png_compressed = false,
+            "resize" => {
+                let size = value()?;
+                let (w, h) = size
+                    .split_once('x')
+                    .with_context(|| format!("--resize expects WxH, got {}", size))?;
+                options.resize = true;
+                options.resize_mode = ResizeMode::Exact;
+                options.target_width = number::<u32>(name, w)?.to_string();
+                options.target_height = number::<u32>(name, h)?.to_string();
+            }
+            "max-side" => {
+                options.resize = true;
+                options.resize_mode = ResizeMode::FitWithin;
+                options.longest_side = number::<u32>(name, value()?)?.to_string();
+            }
+            "color-space" => {
+                options.color_space = match value()? {
+                    "srgb" => OutputColorSpace::Srgb,
+                    "display-p3" => OutputColorSpace::DisplayP3,
+                    "adobe-rgb" => OutputColorSpace::AdobeRgb,
+                    other => anyhow::bail!("Unknown color space {}", other),
+                }
+            }
+            "dpi" => options.dpi = number(name, value()?)?,
+            "brightness" => options.adjustments.brightness = number(name, value()?)?,
+            "contrast" => options.adjustments.contrast = number(name, value()?)?,
+            "saturation" => options.adjustments.saturation = number(name, value()?)?,
+            "grayscale" => {
+                options.adjustments.grayscale = true;
+                options.adjustments.linear_grayscale = match value()? {
+                    "linear" => true,
+                    "gamma" => false,
+                    other => anyhow::bail!("--grayscale expects linear or gamma, got {}", other),
+                };
+            }
+            "frame" => match value()? {
+                "first" => options.frame_choice = FrameChoice::First,
+                "last" => options.frame_choice = FrameChoice::Last,
+                n => {
+                    options.frame_choice = FrameChoice::Number;
+                    options.frame_number = number(name, n)?;
+                }
+            },
+            "keep-metadata" => options.keep_metadata = true,
+            "flatten-alpha" => options.flatten_alpha = true,
+            "verify" => options.verify_output = true,
+            "prefix" => options.prefix = value()?.to_string(),
+            "find" => options.find_pattern = value()?.to_string(),
+            "replace" => options.replace_with = value()?.to_string(),
+            "template" => options.filename_template = value()?.to_string(),
+            "auto-suffix" => options.auto_suffix = true,
+            "if-exists" => {
+                options.existing_file_policy = match value()? {
+                    "overwrite" => ExistingFilePolicy::Overwrite,
+                    "skip" => ExistingFilePolicy::Skip,
+                    "rename" => ExistingFilePolicy::Rename,
+                    other => anyhow::bail!(
+                        "--if-exists expects overwrite, skip or rename, got {}",
+                        other
+                    ),
+                }
+            }
+            "zip" => options.zip_output = true,
+            "zip-file" => {
+                options.zip_output = true;
+                options.zip_path = Some(PathBuf::from(value()?));
+            }
+            _ => anyhow::bail!("--{} is not supported on the command line", name),
+        }
+    }
+    if output_flags > 1 {
+        anyhow::bail!("Use only one of --out, --out-file, and --stdout");
+    }
+    if options.zip_output && !matches!(output, CliOutput::Folder) {
+        anyhow::bail!("--zip cannot be combined with --out-file or --stdout");
+    }
+    Ok((options, inputs, output))
+}
+
+/// Parses a numeric flag value, naming the flag on failure.
+fn number<T: std::str::FromStr>(name: &str, value: &str) -> Result<T> {
+    value
+        .trim()
+        .parse()
+        .ok()
+        .with_context(|| format!("--{}: {} is not a valid number", name, value))
+}
+
+/// Runs every resize filter and encoder setting on one file and prints a table.
+fn benchmark(path: &Path) -> i32 {
+    let results = match run_benchmark(path) {
+        Ok(r) => r,
+        Err(e) => {
+            eprintln!("Benchmark failed: {:#}", e);
+            return 1;
+        }
+    };
+    println!(
+        "{:<8} {:<26} {:>10} {:>12}",
+        "stage", "setting", "time (ms)", "size (KB)"
+    );
+    for r in results {
+        let size = r
+            .bytes
+            .map(|b| format!("{:.1}", b as f64 / 1024.0))
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<8} {:<26} {:>10.1} {:>12}",
+            r.stage, r.setting, r.millis, size
+        );
+    }
+    0
+}
+
+/// Builds the command line that reproduces the given options, with `FILE...` for the inputs.
+pub fn command_line(options: &ConversionOptions) -> String {
+    let program = std::env::current_exe()
+        .ok()
+        .and_then(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+        .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string());
+    let mut args = vec![quote(&program)];
+    let mut flag = |name: &str, value: Option<String>| {
+        args.push(format!("--{}", name));
+        if let Some(value) = value {
+            args.push(quote(&value));
+        }
+    };
+
+    flag("format", Some(options.format.extension().to_string()));
+    if options.keep_source_format {
+        flag("keep-format", None);
+    }
+    match options.format {
+        ImageFormat::Png => {
+            if !options.png_compressed {
+                flag("no-png-optimize", None);
+            }
+        }
+        ImageFormat::WebP if options.webp_lossless => flag("lossless", None),
+        _ => flag("quality", Some(encoder_quality(options).to_string())),
+    }
+    if options.resize && options.resize_mode == ResizeMode::FitWithin {
+        let side = options.longest_side.parse::<u32>().unwrap_or(0);
+        flag("max-side", Some(side.to_string()));
+    } else if options.resize {
+        let dimension = |v: &str| v.parse::<u32>().unwrap_or(0).to_string();
+        flag(
+            "resize",
+            Some(format!(
+                "{}x{}",
+                dimension(&options.target_width),
+                dimension(&options.target_height)
+            )),
+        );
+    }
+    match options.color_space {
+        OutputColorSpace::Srgb => {}
+        OutputColorSpace::DisplayP3 => flag("color-space", Some("display-p3".to_string())),
+        OutputColorSpace::AdobeRgb => flag("color-space", Some("adobe-rgb".to_string())),
+    }
+    if options.dpi > 0 {
+        flag("dpi", Some(options.dpi.to_string()));
+    }
+    let adjustments = options.adjustments;
+    for (name, value) in [
+        ("brightness", adjustments.brightness),
+        ("contrast", adjustments.contrast),
+        ("saturation", adjustments.saturation),
+    ] {
+        if value != 0 {
+            flag(name, Some(value.to_string()));
+        }
+    }
+    if adjustments.grayscale {
+        let mode = if adjustments.linear_grayscale {
+            "linear"
+        } else {
+            "gamma"
+        };
+        flag("grayscale", Some(mode.to_string()));
+    }
+    match options.frame_choice {
+        FrameChoice::First => {}
+        FrameChoice::Last => flag("frame", Some("last".to_string())),
+        FrameChoice::Number => flag("frame", Some(options.frame_number.to_string())),
+    }
+    if options.keep_metadata {
+        flag("keep-metadata", None);
+    }
+    if options.flatten_alpha {
+        flag("flatten-alpha", None);
+    }
+    if !options.prefix.is_empty() {
+        flag("prefix", Some(options.prefix.clone()));
+    }
+    if !options.find_pattern.is_empty() {
+        flag("find", Some(options.find_pattern.clone()));
+        flag("replace", Some(options.replace_with.clone()));
+    }
+    if !options.filename_template.is_empty() {
+        flag("template", Some(options.filename_template.clone()));
+    } else if options.auto_suffix {
+        flag("auto-suffix", None);
+    }
+    match options.existing_file_policy {
+        ExistingFilePolicy::Overwrite => {}
+        ExistingFilePolicy::Skip => flag("if-exists", Some("skip".to_string())),
+        ExistingFilePolicy::Rename => flag("if-exists", Some("rename".to_string())),
+    }
+    if let (true, Some(dir)) = (options.use_custom_output, &options.custom_output_path) {
+        flag("out", Some(dir.to_string_lossy().to_string()));
+    }
+    match (options.zip_output, &options.zip_path) {
+        (true, Some(path)) => flag("zip-file", Some(path.to_string_lossy().to_string())),
+        (true, None) => flag("zip", None),
+        _ => {}
+    }
+    if options.verify_output {
+        flag("verify", None);
+    }
+
+    args.push("FILE...".to_string());
+    args.join(" ")
+}
+
+/// Quotes an argument for the shell when it contains spaces or special characters.
+fn quote(arg: &str) -> String {
+    let plain = !arg.is_empty()
+        && arg
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "-_./:\\+=,%".contains(c));
+    if plain {
+        arg.to_string()
+    } else {
+        format!("\"{}\"", arg.replace('"', "\\\""))
+    }
+}
+
+/// Release builds use the GUI subsystem on Windows, so reattach to the launching console.
+fn attach_console() {
+    #[cfg(windows)]
+    {
+        extern "system" {
+            fn AttachConsole(process_id: u32) -> i32;
+        }
+        const ATTACH_PARENT_PROCESS: u32 = u32::MAX;
+        unsafe {
+            AttachConsole(ATTACH_PARENT_PROCESS);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
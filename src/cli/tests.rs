@@ -0,0 +1,88 @@
+//! Checks that "Copy as CLI command" produces a line the headless mode accepts.
+
+use super::*;
+
+/// Splits a line the way a shell would for the quoting `quote` produces.
+fn shell_words(line: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if quoted => word.extend(chars.next()),
+            '"' => quoted = !quoted,
+            ' ' if !quoted => {
+                if !word.is_empty() {
+                    words.push(std::mem::take(&mut word));
+                }
+            }
+            c => word.push(c),
+        }
+    }
+    if !word.is_empty() {
+        words.push(word);
+    }
+    words
+}
+
+/// Feeds `command_line(options)` back into `parse_args`, starting from defaults.
+fn round_trip(options: &ConversionOptions) -> (String, ConversionOptions) {
+    let line = command_line(options);
+    let words = shell_words(&line);
+    assert_eq!(words.last().map(String::as_str), Some("FILE..."));
+    let args = &words[1..words.len() - 1];
+    let (parsed, inputs, _) = parse_args(args, ConversionOptions::default())
+        .unwrap_or_else(|e| panic!("{} rejected: {:#}", line, e));
+    assert!(inputs.is_empty());
+    (line, parsed)
+}
+
+#[test]
+fn command_line_round_trips_through_parse_args() {
+    let options = ConversionOptions {
+        format: ImageFormat::WebP,
+        quality: 72,
+        unified_quality: false,
+        resize: true,
+        resize_mode: ResizeMode::FitWithin,
+        longest_side: "1600".to_string(),
+        keep_metadata: true,
+        prefix: "web ".to_string(),
+        find_pattern: "IMG_".to_string(),
+        replace_with: "photo \"a\"".to_string(),
+        filename_template: "{name}_{width}".to_string(),
+        existing_file_policy: ExistingFilePolicy::Rename,
+        use_custom_output: true,
+        custom_output_path: Some(PathBuf::from("out dir")),
+        zip_output: true,
+        zip_path: Some(PathBuf::from("batch.zip")),
+        verify_output: true,
+        ..ConversionOptions::default()
+    };
+    let (line, parsed) = round_trip(&options);
+    assert_eq!(command_line(&parsed), line);
+    assert_eq!(parsed.prefix, options.prefix);
+    assert_eq!(parsed.find_pattern, options.find_pattern);
+    assert_eq!(parsed.replace_with, options.replace_with);
+    assert_eq!(parsed.filename_template, options.filename_template);
+    assert_eq!(parsed.existing_file_policy, ExistingFilePolicy::Rename);
+    assert_eq!(parsed.custom_output_path, options.custom_output_path);
+    assert!(parsed.zip_output);
+    assert_eq!(parsed.zip_path, options.zip_path);
+}
+
+#[test]
+fn auto_suffix_and_default_zip_round_trip() {
+    let options = ConversionOptions {
+        auto_suffix: true,
+        existing_file_policy: ExistingFilePolicy::Skip,
+        zip_output: true,
+        ..ConversionOptions::default()
+    };
+    let (line, parsed) = round_trip(&options);
+    assert_eq!(command_line(&parsed), line);
+    assert!(parsed.auto_suffix);
+    assert_eq!(parsed.existing_file_policy, ExistingFilePolicy::Skip);
+    assert!(parsed.zip_output && parsed.zip_path.is_none());
+}
@@ -4,8 +4,45 @@
 pub const EXIF_APP1_MARKER: u8 = 0xE1;
 pub const EXIF_ORIENTATION_TAG: u16 = 0x0112;
 pub const EXIF_HEADER: &[u8] = b"Exif\0\0";
-pub const MAX_FILE_SIZE_BYTES: u64 = 100 * 1024 * 1024;
-pub const SUPPORTED_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "heic", "heif"];
+pub const DEFAULT_MAX_FILE_SIZE_MB: u64 = 100;
+/// Highest accepted input size limit; larger entries are clamped to it.
+pub const MAX_FILE_SIZE_LIMIT_MB: u64 = 1024 * 1024;
+/// URL downloads give up when connecting, or waiting for the next chunk, takes longer.
+pub const DOWNLOAD_CONNECT_TIMEOUT_SECS: u64 = 15;
+pub const DOWNLOAD_READ_TIMEOUT_SECS: u64 = 30;
+pub const DEFAULT_DATE_FORMAT: &str = "%Y%m%d";
+pub const PREVIEW_MAX_SIDE: u32 = 320;
+/// Crops narrower or shorter than this fraction of the preview count as a click and are dropped.
+pub const MIN_CROP_FRACTION: f32 = 0.01;
+/// Letterbox removal is skipped when the bars would take more than this share of either side.
+pub const MAX_LETTERBOX_FRACTION: f32 = 0.4;
+/// Distance from the file list's top or bottom edge where a row drag starts auto-scrolling.
+pub const AUTO_SCROLL_EDGE: f32 = 36.0;
+/// Pixels scrolled per tick at the very edge; the speed eases off further in.
+pub const AUTO_SCROLL_STEP: f32 = 18.0;
+pub const AUTO_SCROLL_TICK_MS: u64 = 30;
+/// Second click on the same row within this many milliseconds counts as a double-click.
+pub const DOUBLE_CLICK_MS: u64 = 400;
+/// File list edits kept for undo; older ones are dropped.
+pub const UNDO_DEPTH: usize = 50;
+pub const MAX_RECENT_OUTPUT_DIRS: usize = 5;
+pub const MAX_NAMING_HISTORY: usize = 8;
+/// Files the savings preflight converts in memory before extrapolating to the whole queue.
+pub const PREFLIGHT_SAMPLES: usize = 5;
+/// Smallest contact sheet cell, in pixels, so thumbnails and labels stay legible.
+pub const MIN_CONTACT_SHEET_CELL: u32 = 32;
+/// Quality sweeps encode at every multiple of this step up to 100.
+pub const QUALITY_SWEEP_STEP: u8 = 10;
+pub const PREFETCH_CONCURRENCY: usize = 4;
+pub const DROP_COALESCE_MS: u64 = 150;
+pub const LARGE_ADD_THRESHOLD: usize = 2000;
+/// Unresolved file list lines shown in the import report; the rest are only logged.
+pub const MAX_REPORTED_LIST_LINES: usize = 10;
+pub const ADD_CHUNK_SIZE: usize = 500;
+pub const LOG_CAPACITY: usize = 500;
+pub const SUPPORTED_EXTENSIONS: &[&str] = &[
+    "jpg", "jpeg", "png", "webp", "heic", "heif", "avif", "gif", "tif", "tiff", "svg",
+];
 pub const TEXT_SIZE_SMALL: u16 = 12;
 pub const TEXT_SIZE_NORMAL: u16 = 14;
 pub const TEXT_SIZE_TITLE: u16 = 18;
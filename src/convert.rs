@@ -1,83 +1,724 @@
-//! Image conversion engine with format support for JPEG, PNG, WebP, and HEIC.
+//! Image conversion engine with format support for JPEG, PNG, WebP, HEIC, AVIF, and SVG input.
 
-use crate::state::{ConversionOptions, ImageFormat};
+use crate::state::{
+    Adjustments, ColorCorrection, ConversionOptions, CropRect, ExistingFilePolicy, ExtensionCase,
+    FileInfo, FrameChoice, Histogram, ImageFormat, OutputColorSpace, OutputTimestamp, PngColorTag,
+    ResizeMode, SweepPoint,
+};
 use anyhow::{Context, Result};
 use image::imageops::FilterType;
-use image::DynamicImage;
+use image::{DynamicImage, ImageDecoder};
 use img_parts::jpeg::JpegSegment;
 use img_parts::{ImageEXIF, ImageICC};
+use std::borrow::Cow;
 use std::fs::File;
-use std::io::BufWriter;
+use std::io::Read;
 use std::io::Write;
-use std::path::PathBuf;
+use std::io::{BufReader, BufWriter};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
 
 static SRGB_ICC: &[u8] = include_bytes!("srgb.icc");
 
 struct Metadata {
     exif: Option<Vec<u8>>,
+    /// Replace the EXIF thumbnail with one of the output instead of dropping it.
+    regenerate_thumbnail: bool,
+    /// ASCII IFD0 tags stamped into the EXIF, creating it when the source had none.
+    credits: Vec<(u16, String)>,
 }
 
 const MAGIC_JPEG: &[u8] = &[0xFF, 0xD8, 0xFF];
 const MAGIC_PNG: &[u8] = &[0x89, 0x50, 0x4E, 0x47];
 const MAGIC_WEBP: &[u8] = b"RIFF";
-const MAGIC_HEIC: &[u8] = b"ftyp";
+const MAGIC_FTYP: &[u8] = b"ftyp";
+const MAGIC_GIF: &[u8] = b"GIF8";
+const MAGIC_TIFF_LE: &[u8] = b"II*\0";
+const MAGIC_TIFF_BE: &[u8] = b"MM\0*";
+const HEIF_BRANDS: &[&[u8]] = &[b"heic", b"heix", b"hevc", b"hevx", b"heim", b"heis"];
+const AVIF_BRANDS: &[&[u8]] = &[b"avif", b"avis"];
+/// Generic HEIF brands that both HEIC and AVIF files may carry as their major brand.
+const MIAF_BRANDS: &[&[u8]] = &[b"mif1", b"msf1", b"miaf"];
+const WEBP_CHUNKS: &[&[u8]] = &[b"VP8 ", b"VP8L", b"VP8X"];
 
-/// Validates file format by checking magic bytes at file header.
-fn validate_file_magic(path: &PathBuf) -> Result<()> {
-    let mut file = std::fs::File::open(path)?;
-    let mut header = [0u8; 12];
-    std::io::Read::read(&mut file, &mut header)?;
+/// Image container formats recognized from file content.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SourceFormat {
+    Jpeg,
+    Png,
+    WebP,
+    Heif,
+    Avif,
+    Gif,
+    Tiff,
+    Svg,
+}
 
-    let ext = path
-        .extension()
+impl SourceFormat {
+    /// Maps a file extension to the format it conventionally denotes.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "jpg" | "jpeg" => Some(SourceFormat::Jpeg),
+            "png" => Some(SourceFormat::Png),
+            "webp" => Some(SourceFormat::WebP),
+            "heic" | "heif" => Some(SourceFormat::Heif),
+            "avif" => Some(SourceFormat::Avif),
+            "gif" => Some(SourceFormat::Gif),
+            "tif" | "tiff" => Some(SourceFormat::Tiff),
+            "svg" => Some(SourceFormat::Svg),
+            _ => None,
+        }
+    }
+
+    /// Returns the canonical file extension for the format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            SourceFormat::Jpeg => "jpg",
+            SourceFormat::Png => "png",
+            SourceFormat::WebP => "webp",
+            SourceFormat::Heif => "heic",
+            SourceFormat::Avif => "avif",
+            SourceFormat::Gif => "gif",
+            SourceFormat::Tiff => "tif",
+            SourceFormat::Svg => "svg",
+        }
+    }
+
+    /// Returns false when libheif lacks the decoder this format needs.
+    pub fn is_decodable(self) -> bool {
+        match self {
+            SourceFormat::Heif => crate::heic::support().hevc,
+            SourceFormat::Avif => crate::heic::support().av1,
+            _ => true,
+        }
+    }
+
+    /// Returns true for containers that can hold several frames or pages.
+    pub fn is_multi_frame(self) -> bool {
+        matches!(
+            self,
+            SourceFormat::Gif | SourceFormat::Tiff | SourceFormat::WebP
+        )
+    }
+
+    /// Returns true for ISOBMFF-based formats decoded through libheif.
+    pub fn uses_libheif(self) -> bool {
+        matches!(self, SourceFormat::Heif | SourceFormat::Avif)
+    }
+}
+
+/// Identifies image format from leading magic bytes, independent of extension.
+fn sniff_format(header: &[u8]) -> Result<SourceFormat> {
+    if header.starts_with(MAGIC_JPEG) {
+        Ok(SourceFormat::Jpeg)
+    } else if header.starts_with(MAGIC_PNG) {
+        Ok(SourceFormat::Png)
+    } else if header.starts_with(MAGIC_GIF) {
+        Ok(SourceFormat::Gif)
+    } else if header.starts_with(MAGIC_TIFF_LE) || header.starts_with(MAGIC_TIFF_BE) {
+        Ok(SourceFormat::Tiff)
+    } else if header.len() >= 12 && header.starts_with(MAGIC_WEBP) && &header[8..12] == b"WEBP" {
+        let chunk = header.get(12..16).unwrap_or_default();
+        if !WEBP_CHUNKS.contains(&chunk) {
+            anyhow::bail!(
+                "Unsupported WebP variant: expected a VP8, VP8L, or VP8X chunk, found {:?}",
+                String::from_utf8_lossy(chunk)
+            );
+        }
+        Ok(SourceFormat::WebP)
+    } else if header.len() >= 12 && &header[4..8] == MAGIC_FTYP {
+        sniff_ftyp_brand(header)
+    } else if looks_like_svg(header) {
+        Ok(SourceFormat::Svg)
+    } else {
+        anyhow::bail!("Invalid file format: unrecognized magic bytes")
+    }
+}
+
+/// Tells HEIC from AVIF by the `ftyp` major brand, falling back to the compatible brands
+/// when the major brand is a generic HEIF one such as `mif1`.
+fn sniff_ftyp_brand(header: &[u8]) -> Result<SourceFormat> {
+    let major = &header[8..12];
+    let box_size = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    // Compatible brands follow the major brand and minor version.
+    let compatible = header
+        .get(16..box_size.min(header.len()))
         .unwrap_or_default()
-        .to_string_lossy()
-        .to_lowercase();
+        .chunks_exact(4);
+    let mut generic = false;
+    for brand in std::iter::once(major).chain(compatible) {
+        if AVIF_BRANDS.contains(&brand) {
+            return Ok(SourceFormat::Avif);
+        } else if HEIF_BRANDS.contains(&brand) {
+            return Ok(SourceFormat::Heif);
+        }
+        generic |= MIAF_BRANDS.contains(&brand);
+    }
+    if generic {
+        return Ok(SourceFormat::Heif);
+    }
+    anyhow::bail!(
+        "Unsupported ISO media file: brand {:?} is not HEIC, HEIF, or AVIF",
+        String::from_utf8_lossy(major)
+    )
+}
+
+/// SVG is plain XML, so look for its usual opening markup after any BOM or whitespace.
+fn looks_like_svg(header: &[u8]) -> bool {
+    let text = header.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(header);
+    let start = text
+        .iter()
+        .position(|b| !b.is_ascii_whitespace())
+        .unwrap_or(text.len());
+    let text = &text[start..];
+    [&b"<?xml"[..], b"<svg", b"<!DOCTYPE svg", b"<!--"]
+        .iter()
+        .any(|tag| text.starts_with(tag))
+}
+
+/// Detects file format from content and notes when it disagrees with the extension.
+pub fn detect_source_format(path: &Path) -> Result<SourceFormat> {
+    // Binary formats need 12 bytes; the rest leaves room for whitespace before SVG markup.
+    let mut header = Vec::with_capacity(64);
+    std::fs::File::open(path)?
+        .take(64)
+        .read_to_end(&mut header)?;
+
+    let detected = sniff_format(&header)?;
+
+    let ext = path.extension().unwrap_or_default().to_string_lossy();
+    if let Some(expected) = SourceFormat::from_extension(&ext) {
+        if expected != detected {
+            log::warn!(
+                "{}: extension suggests {:?} but content is {:?}",
+                path.display(),
+                expected,
+                detected
+            );
+        }
+    }
+    Ok(detected)
+}
+
+/// Whether a JPEG reaches its end-of-image marker. Segments are skipped by length, so
+/// embedded thumbnails and data appended after the image (motion photos) are not mistaken for it.
+/// Padding some writers leave between segments is skipped up to the next marker, as decoders do.
+fn jpeg_is_complete(data: &[u8]) -> bool {
+    let mut pos = 2;
+    while pos + 1 < data.len() {
+        if data[pos] != 0xFF {
+            match data[pos..].iter().position(|&b| b == 0xFF) {
+                Some(skip) => pos += skip,
+                None => return false,
+            }
+            continue;
+        }
+        let marker = data[pos + 1];
+        match marker {
+            0xD9 => return true,
+            // Fill bytes, standalone markers, and restart markers carry no length.
+            0xFF => pos += 1,
+            0x01 | 0xD0..=0xD7 => pos += 2,
+            _ => {
+                let Some(len) = data.get(pos + 2..pos + 4) else {
+                    return false;
+                };
+                pos += 2 + u16::from_be_bytes([len[0], len[1]]) as usize;
+                if marker == 0xDA {
+                    // Entropy-coded data runs until a marker other than stuffing or a restart.
+                    while pos + 1 < data.len()
+                        && (data[pos] != 0xFF || matches!(data[pos + 1], 0x00 | 0xD0..=0xD7))
+                    {
+                        pos += 1;
+                    }
+                }
+            }
+        }
+    }
+    false
+}
+
+/// Tokens accepted in filename templates.
+pub const TEMPLATE_TOKENS: &[&str] = &[
+    "stem",
+    "prefix",
+    "suffix",
+    "width",
+    "height",
+    "quality",
+    "format",
+    "index",
+    "date",
+    "exif_date",
+];
+
+/// Returns `{token}` placeholders in a template that are not recognized.
+pub fn unknown_template_tokens(template: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+    substitute_tokens(template, |token| {
+        if !TEMPLATE_TOKENS.contains(&token) {
+            unknown.push(token.to_string());
+        }
+        None
+    });
+    unknown
+}
+
+/// Scans `template` once, left to right, replacing each `{token}` with `value(token)`.
+/// Tokens without a value are kept as written, and substituted text is never rescanned.
+fn substitute_tokens(template: &str, mut value: impl FnMut(&str) -> Option<String>) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        out.push_str(&rest[..start]);
+        match value(&rest[start + 1..start + len]) {
+            Some(v) => out.push_str(&v),
+            None => out.push_str(&rest[start..=start + len]),
+        }
+        rest = &rest[start + len + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Returns true if a chrono strftime pattern is well-formed.
+pub fn is_valid_date_format(format: &str) -> bool {
+    !chrono::format::StrftimeItems::new(format).any(|i| matches!(i, chrono::format::Item::Error))
+}
+
+/// Formats a timestamp with the user's pattern, falling back to the default on bad patterns.
+fn format_date(date: chrono::NaiveDateTime, format: &str) -> String {
+    use std::fmt::Write as _;
+    let mut out = String::new();
+    // Well-formed patterns can still fail (e.g. %z on a naive time), so never unwrap.
+    if is_valid_date_format(format) && write!(out, "{}", date.format(format)).is_ok() {
+        return out;
+    }
+    date.format(crate::constants::DEFAULT_DATE_FORMAT)
+        .to_string()
+}
+
+/// Reads capture time from EXIF DateTimeOriginal, falling back to file modification time.
+fn capture_date(path: &Path) -> Option<chrono::NaiveDateTime> {
+    let from_exif = std::fs::File::open(path).ok().and_then(|file| {
+        let exif = exif::Reader::new()
+            .read_from_container(&mut std::io::BufReader::new(file))
+            .ok()?;
+        let field = exif.get_field(exif::Tag::DateTimeOriginal, exif::In::PRIMARY)?;
+        let exif::Value::Ascii(ref ascii) = field.value else {
+            return None;
+        };
+        let dt = exif::DateTime::from_ascii(ascii.first()?).ok()?;
+        chrono::NaiveDate::from_ymd_opt(dt.year as i32, dt.month as u32, dt.day as u32)?
+            .and_hms_opt(dt.hour as u32, dt.minute as u32, dt.second as u32)
+    });
+    from_exif.or_else(|| {
+        let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+        Some(chrono::DateTime::<chrono::Local>::from(modified).naive_local())
+    })
+}
 
-    let valid = match ext.as_str() {
-        "jpg" | "jpeg" => header.starts_with(MAGIC_JPEG),
-        "png" => header.starts_with(MAGIC_PNG),
-        "webp" => header.starts_with(MAGIC_WEBP) && &header[8..12] == b"WEBP",
-        "heic" | "heif" => header[4..8] == *MAGIC_HEIC,
-        _ => true,
+/// Gives the output the source's modification or capture time so file managers keep it in
+/// chronological order.
+fn apply_output_timestamp(
+    input_path: &Path,
+    output_path: &Path,
+    timestamp: OutputTimestamp,
+) -> Result<()> {
+    let time = match timestamp {
+        OutputTimestamp::Conversion => return Ok(()),
+        OutputTimestamp::SourceModified => std::fs::metadata(input_path)?.modified()?,
+        OutputTimestamp::CaptureDate => capture_date(input_path)
+            .and_then(|date| date.and_local_timezone(chrono::Local).earliest())
+            .context("No capture date")?
+            .into(),
     };
+    File::options()
+        .write(true)
+        .open(output_path)?
+        .set_modified(time)?;
+    Ok(())
+}
 
-    if valid {
-        Ok(())
+/// Applies the configured casing to the output extension.
+fn cased_extension(input_path: &Path, options: &ConversionOptions) -> String {
+    let ext = options.format.extension();
+    let upper = match options.extension_case {
+        ExtensionCase::Lower => false,
+        ExtensionCase::Upper => true,
+        ExtensionCase::MatchInput => input_path
+            .extension()
+            .map(|e| e.to_string_lossy())
+            .is_some_and(|e| {
+                e.chars().any(|c| c.is_ascii_uppercase())
+                    && !e.chars().any(|c| c.is_ascii_lowercase())
+            }),
+    };
+    if upper {
+        ext.to_uppercase()
+    } else {
+        ext.to_string()
+    }
+}
+
+/// Returns the input name without its image extension; names like "scan.v2" or
+/// "screenshot" have none, so they are kept whole.
+fn source_stem(input_path: &Path) -> String {
+    let has_image_extension = input_path
+        .extension()
+        .and_then(|e| SourceFormat::from_extension(&e.to_string_lossy()))
+        .is_some();
+    let stem = if has_image_extension {
+        input_path.file_stem()
+    } else {
+        input_path.file_name()
+    };
+    stem.unwrap_or_default().to_string_lossy().to_string()
+}
+
+/// Returns the stem after find/replace and the smart suffix for the output dimensions.
+fn filename_parts(
+    input_path: &Path,
+    options: &ConversionOptions,
+    width: u32,
+    height: u32,
+) -> (String, String) {
+    let mut stem = source_stem(input_path);
+    if !options.find_pattern.is_empty() {
+        stem = stem.replace(&options.find_pattern, &options.replace_with);
+    }
+    let suffix = if width > 0 && height > 0 {
+        get_smart_suffix(width, height, options)
+    } else {
+        String::new()
+    };
+    (stem, suffix)
+}
+
+/// Builds output filename from the template, or prefix/stem/suffix when no template is set.
+fn build_filename(
+    input_path: &Path,
+    options: &ConversionOptions,
+    width: u32,
+    height: u32,
+    index: usize,
+) -> String {
+    if options.filename_template.is_empty() {
+        let (mut stem, suffix) = filename_parts(input_path, options, width, height);
+        if options.auto_suffix {
+            stem.push_str(&suffix);
+        }
+        return format!(
+            "{}{}.{}",
+            options.prefix,
+            stem,
+            cased_extension(input_path, options)
+        );
+    }
+
+    let name = expand_template(
+        &options.filename_template,
+        input_path,
+        options,
+        width,
+        height,
+        index,
+        &[],
+    );
+    let name = name.replace(['/', '\\'], "_");
+    format!("{}.{}", name, cased_extension(input_path, options))
+}
+
+/// Replaces the `TEMPLATE_TOKENS` placeholders in `template` for one output image, plus the
+/// `extra` tokens given as name/value pairs.
+fn expand_template(
+    template: &str,
+    input_path: &Path,
+    options: &ConversionOptions,
+    width: u32,
+    height: u32,
+    index: usize,
+    extra: &[(&str, &str)],
+) -> String {
+    let (stem, suffix) = filename_parts(input_path, options, width, height);
+    substitute_tokens(template, |token| {
+        let value = match token {
+            "stem" => stem.clone(),
+            "prefix" => options.prefix.clone(),
+            "suffix" => suffix.clone(),
+            "width" => width.to_string(),
+            "height" => height.to_string(),
+            "quality" => encoder_quality(options).to_string(),
+            "format" => options.format.extension().to_string(),
+            "index" => format!("{:04}", index + 1),
+            "date" => format_date(chrono::Local::now().naive_local(), &options.date_format),
+            "exif_date" => capture_date(input_path)
+                .map(|d| format_date(d, &options.date_format))
+                .unwrap_or_default(),
+            _ => {
+                return extra
+                    .iter()
+                    .find(|(name, _)| *name == token)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        Some(value)
+    })
+}
+
+/// Predicts target filename for a queued file from the dimensions it will be encoded at.
+pub fn get_target_filename(input_path: &Path, options: &ConversionOptions, index: usize) -> String {
+    let options = &*options_for(input_path, options);
+    let (w, h) = output_dimensions(input_path, options).unwrap_or((0, 0));
+    build_filename(input_path, options, w, h, index)
+}
+
+/// Format an input is written as: its own when keeping source formats and it can be encoded,
+/// otherwise the selected one.
+pub fn output_format(input_path: &Path, options: &ConversionOptions) -> ImageFormat {
+    if !options.keep_source_format {
+        return options.format;
+    }
+    match detect_source_format(input_path) {
+        Ok(SourceFormat::Jpeg) => ImageFormat::Jpeg,
+        Ok(SourceFormat::Png) => ImageFormat::Png,
+        Ok(SourceFormat::WebP) => ImageFormat::WebP,
+        _ => options.format,
+    }
+}
+
+/// Options with `format` resolved for one input; borrowed when it matches the selection.
+fn options_for<'a>(
+    input_path: &Path,
+    options: &'a ConversionOptions,
+) -> Cow<'a, ConversionOptions> {
+    let format = output_format(input_path, options);
+    if format == options.format {
+        Cow::Borrowed(options)
     } else {
-        anyhow::bail!("Invalid file format: magic bytes mismatch")
+        Cow::Owned(ConversionOptions {
+            format,
+            ..options.clone()
+        })
+    }
+}
+
+/// Options for every output of one input: its main format first, then each extra format it
+/// is not already written in. Extras use their own default quality until the user picks one.
+pub fn options_per_output(
+    input_path: &Path,
+    options: &ConversionOptions,
+) -> Vec<ConversionOptions> {
+    let main = output_format(input_path, options);
+    let mut outputs = vec![options_for(input_path, options).into_owned()];
+    for &format in &options.extra_formats {
+        if format == main || outputs.iter().any(|o| o.format == format) {
+            continue;
+        }
+        let quality = if options.quality_customized {
+            options.quality
+        } else {
+            format.default_quality()
+        };
+        outputs.push(ConversionOptions {
+            format,
+            keep_source_format: false,
+            quality,
+            custom_quality: quality,
+            ..options.clone()
+        });
+    }
+    outputs
+}
+
+/// Converts one file to exactly `output_path`, synchronously and without touching the GUI,
+/// for scripts. Only the main format is written, encoded as `options.format` (or the source
+/// format with keep-format) whatever extension `output_path` has. The output folder, format
+/// subfolders, filename template, existing-file policy, ZIP output, and captions are all
+/// ignored: the file is replaced if present and its folder created if missing.
+pub fn convert_one(
+    input_path: &Path,
+    output_path: &Path,
+    options: &ConversionOptions,
+) -> Result<ConversionReport> {
+    let main = options_per_output(input_path, options)
+        .into_iter()
+        .next()
+        .context("No output format selected")?;
+    convert_image(input_path, &main, 0, OutputTarget::Path(output_path))
+}
+
+/// Converts an input into every selected format, reporting the main output and noting the
+/// extra ones. `zip` writes the outputs into the batch archive instead of the folder.
+pub fn convert_each_format(
+    input_path: &Path,
+    options: &ConversionOptions,
+    index: usize,
+    zip: Option<&ZipSink>,
+) -> Result<ConversionReport> {
+    if options.copy_sidecars && is_sidecar(input_path) {
+        return copy_sidecar(input_path, options, zip);
     }
+    let target = || zip.map_or(OutputTarget::Folder, OutputTarget::Zip);
+    let mut outputs = options_per_output(input_path, options).into_iter();
+    let Some(main) = outputs.next() else {
+        anyhow::bail!("No output format selected");
+    };
+    let mut output = convert_image(input_path, &main, index, target())?;
+    for extra in outputs {
+        let written = convert_image(input_path, &extra, index, target())
+            .with_context(|| format!("{} output", extra.format))?;
+        add_note(
+            &mut output.note,
+            format!("also {} {} KB", extra.format, written.size.div_ceil(1024)),
+        );
+        output.extra_paths.extend(written.path);
+        output.verified &= written.verified;
+    }
+    Ok(output)
 }
 
-/// Generates target filename based on conversion options and input path.
-pub fn get_target_filename(input_path: &PathBuf, options: &ConversionOptions) -> String {
-    let mut file_stem = input_path
-        .file_stem()
+/// A queued file that is neither named nor shaped like a supported image, such as a `.txt`
+/// or `.json` annotation next to the images.
+fn is_sidecar(input_path: &Path) -> bool {
+    let ext = input_path
+        .extension()
         .unwrap_or_default()
         .to_string_lossy()
+        .to_lowercase();
+    !crate::constants::SUPPORTED_EXTENSIONS.contains(&ext.as_str())
+        && detect_source_format(input_path).is_err()
+}
+
+/// Copies a sidecar unchanged under its own name to where the main format's outputs go,
+/// following the existing-file policy. Sidecars already in the output folder are left alone.
+fn copy_sidecar(
+    input_path: &Path,
+    options: &ConversionOptions,
+    zip: Option<&ZipSink>,
+) -> Result<ConversionReport> {
+    let file_name = input_path
+        .file_name()
+        .context("Sidecar has no file name")?
+        .to_string_lossy()
         .to_string();
+    let data = std::fs::read(input_path)
+        .with_context(|| format!("Cannot read {}", input_path.display()))?;
+    let copied = |note: &str, path: Option<PathBuf>| ConversionReport {
+        size: data.len() as u64,
+        note: Some(note.to_string()),
+        skipped: note != SIDECAR_NOTE,
+        path,
+        sidecar: true,
+        ..Default::default()
+    };
 
-    if !options.find_pattern.is_empty() {
-        file_stem = file_stem.replace(&options.find_pattern, &options.replace_with);
+    if let Some(zip) = zip {
+        let entry_name = match format_subfolder(input_path, options) {
+            Some(folder) => format!("{}/{}", folder, file_name),
+            None => file_name,
+        };
+        let mut zip = zip
+            .lock()
+            .map_err(|_| anyhow::anyhow!("ZIP archive is unavailable"))?;
+        let archive = zip
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("ZIP archive is already closed"))?;
+        archive.start_file(entry_name, zip::write::SimpleFileOptions::default())?;
+        archive.write_all(&data)?;
+        return Ok(copied(SIDECAR_NOTE, None));
     }
 
-    if options.auto_suffix {
-        if let Ok((w, h)) = image::image_dimensions(input_path) {
-            file_stem.push_str(&get_smart_suffix(w, h, options.quality, options.format));
+    let output_dir = get_output_dir(input_path, options);
+    ensure_output_dir(&output_dir)?;
+    let mut output_path = output_dir.join(&file_name);
+    let same_file =
+        |path: &Path| std::fs::canonicalize(path).ok() == std::fs::canonicalize(input_path).ok();
+    if output_path.exists() {
+        if same_file(&output_path) {
+            return Ok(copied(
+                "sidecar already in output folder",
+                Some(output_path),
+            ));
+        }
+        match options.existing_file_policy {
+            ExistingFilePolicy::Overwrite => {}
+            ExistingFilePolicy::Skip => return Ok(copied(SKIPPED_NOTE, Some(output_path))),
+            ExistingFilePolicy::Rename => {
+                let mut copy = 0;
+                while output_path.exists() {
+                    copy += 1;
+                    output_path.set_file_name(numbered_name(&file_name, copy));
+                }
+            }
         }
     }
+    std::fs::write(&output_path, &data)
+        .with_context(|| format!("Cannot write {}", output_path.display()))?;
+    Ok(copied(SIDECAR_NOTE, Some(output_path)))
+}
+
+/// Dimensions `convert_image` will encode at, after EXIF orientation and resizing.
+pub fn output_dimensions(input_path: &Path, options: &ConversionOptions) -> Option<(u32, u32)> {
+    let info = read_file_info(input_path).ok()?;
+    // libheif reports dimensions with the rotation already applied.
+    let rotated = !options.ignore_exif_orientation
+        && !detect_source_format(input_path).ok()?.uses_libheif()
+        && (5..=8).contains(&exif_orientation(input_path));
+    let (w, h) = if rotated {
+        (info.height, info.width)
+    } else {
+        (info.width, info.height)
+    };
+    let (w, h) = cropped_dimensions(options.crop, w, h);
+    Some(resized_dimensions(options, w, h))
+}
 
-    let ext = match options.format {
-        ImageFormat::Jpeg => "jpg",
-        ImageFormat::Png => "png",
-        ImageFormat::WebP => "webp",
+/// Extracts EXIF metadata from JPEG, WebP, PNG, or HEIF files, resetting orientation to normal
+/// unless `keep_orientation` asks for the original tag.
+fn extract_metadata(
+    input_path: &Path,
+    regenerate_thumbnail: bool,
+    keep_orientation: bool,
+) -> Option<Metadata> {
+    let exif = if detect_source_format(input_path).is_ok_and(|f| f.uses_libheif()) {
+        crate::heic::read_heic_exif(input_path)
+    } else {
+        read_container_exif(input_path)
     };
 
-    format!("{}{}.{}", options.prefix, file_stem, ext)
+    // Decoding always bakes orientation into pixels, so any EXIF we carry
+    // forward must say "upright" regardless of which encoder writes it.
+    // Keeping the original tag may double-rotate in viewers that honor it.
+    exif.map(|mut e| {
+        if !keep_orientation {
+            patch_orientation_in_place(&mut e);
+        }
+        Metadata {
+            exif: Some(e),
+            regenerate_thumbnail,
+            credits: Vec::new(),
+        }
+    })
+}
+
+/// Artist and copyright tags to stamp into output EXIF, leaving out blank fields.
+fn exif_credits(options: &ConversionOptions) -> Vec<(u16, String)> {
+    [
+        (EXIF_ARTIST_TAG, &options.exif_artist),
+        (EXIF_COPYRIGHT_TAG, &options.exif_copyright),
+    ]
+    .into_iter()
+    .filter(|(_, text)| !text.trim().is_empty())
+    .map(|(tag, text)| (tag, text.trim().to_string()))
+    .collect()
 }
 
-/// Extracts EXIF metadata from JPEG, WebP, or PNG files.
-fn extract_metadata(input_path: &PathBuf) -> Option<Metadata> {
+/// Reads the EXIF payload embedded in a JPEG, WebP, or PNG container.
+fn read_container_exif(input_path: &Path) -> Option<Vec<u8>> {
     let file_bytes = std::fs::read(input_path).ok()?;
     let bytes_owned = bytes::Bytes::from(file_bytes);
     let mut exif = None;
@@ -89,13 +730,16 @@ fn extract_metadata(input_path: &PathBuf) -> Option<Metadata> {
             }
         }
     } else if let Ok(webp) = img_parts::webp::WebP::from_bytes(bytes_owned.clone()) {
-        if let Some(e) = webp.exif() {
+        // Read the chunk directly: img-parts only reports EXIF stored with the JPEG prefix,
+        // while the WebP spec stores the bare TIFF data.
+        let chunk = webp.chunk_by_id(*b"EXIF");
+        if let Some(e) = chunk.and_then(|c| c.content().data()) {
             let v = if e.starts_with(b"Exif\0\0") {
                 e.to_vec()
             } else {
                 let mut buf = Vec::new();
                 buf.extend_from_slice(b"Exif\0\0");
-                buf.extend_from_slice(&e);
+                buf.extend_from_slice(e);
                 buf
             };
             exif = Some(v);
@@ -115,8 +759,7 @@ fn extract_metadata(input_path: &PathBuf) -> Option<Metadata> {
             }
         }
     }
-
-    exif.map(|e| Metadata { exif: Some(e) })
+    exif
 }
 
 /// Resets EXIF orientation tag to 1 (normal) after image rotation.
@@ -166,8 +809,252 @@ fn patch_orientation_in_place(full_payload: &mut Vec<u8>) {
     }
 }
 
+/// Longest side of regenerated EXIF thumbnails.
+const EXIF_THUMBNAIL_SIDE: u32 = 160;
+/// Largest payload, `Exif\0\0` header included, that fits in one APP1 segment.
+const MAX_EXIF_PAYLOAD: usize = 65533;
+
+/// Encodes a small baseline JPEG of the output for the EXIF thumbnail.
+fn exif_thumbnail(img: &DynamicImage) -> Option<Vec<u8>> {
+    let thumb = img
+        .thumbnail(EXIF_THUMBNAIL_SIDE, EXIF_THUMBNAIL_SIDE)
+        .to_rgb8();
+    let mut buf = Vec::new();
+    jpeg_encoder::Encoder::new(&mut buf, 75)
+        .encode(
+            thumb.as_raw(),
+            thumb.width() as u16,
+            thumb.height() as u16,
+            jpeg_encoder::ColorType::Rgb,
+        )
+        .ok()?;
+    // EXIF thumbnails are bare JPEG streams without a JFIF header.
+    let mut jpeg = img_parts::jpeg::Jpeg::from_bytes(buf.into()).ok()?;
+    jpeg.segments_mut().retain(|s| s.marker() != 0xE0);
+    let mut out = Vec::new();
+    jpeg.encoder().write_to(&mut out).ok()?;
+    Some(out)
+}
+
+/// Points IFD1 at `thumbnail`, or drops the thumbnail IFD when `None` or when it would not fit.
+/// The old thumbnail is trimmed when it ends the payload; otherwise it is left unreferenced.
+fn replace_exif_thumbnail(full_payload: &mut Vec<u8>, thumbnail: Option<&[u8]>) {
+    if !full_payload.starts_with(b"Exif\0\0") || full_payload.len() < 14 {
+        return;
+    }
+    let is_le = full_payload[6..].starts_with(b"II");
+    let read_u16 = |data: &[u8], at: usize| {
+        let b = data.get(at..at + 2)?;
+        Some(if is_le {
+            u16::from_le_bytes([b[0], b[1]])
+        } else {
+            u16::from_be_bytes([b[0], b[1]])
+        } as usize)
+    };
+    let read_u32 = |data: &[u8], at: usize| {
+        let b = data.get(at..at + 4)?;
+        Some(if is_le {
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        } else {
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        } as usize)
+    };
+    let u16_bytes = |v: u16| {
+        if is_le {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+    let u32_bytes = |v: u32| {
+        if is_le {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+
+    let tiff = &full_payload[6..];
+    let Some(ifd0) = read_u32(tiff, 4) else {
+        return;
+    };
+    let Some(entries) = read_u16(tiff, ifd0) else {
+        return;
+    };
+    let next_ifd_at = ifd0 + 2 + entries * 12;
+    let Some(ifd1) = read_u32(tiff, next_ifd_at) else {
+        return;
+    };
+
+    let mut end = tiff.len();
+    if let Some(count) = (ifd1 != 0).then(|| read_u16(tiff, ifd1)).flatten() {
+        let (mut offset, mut length) = (None, None);
+        for pos in (0..count).map(|i| ifd1 + 2 + i * 12) {
+            match read_u16(tiff, pos) {
+                Some(0x0201) => offset = read_u32(tiff, pos + 8),
+                Some(0x0202) => length = read_u32(tiff, pos + 8),
+                _ => {}
+            }
+        }
+        if let (Some(offset), Some(length)) = (offset, length) {
+            if offset + length == tiff.len() {
+                // Cameras usually write IFD1 right before the thumbnail; drop both then.
+                end = if ifd1 + 2 + count * 12 + 4 == offset && ifd1 > next_ifd_at {
+                    ifd1
+                } else {
+                    offset
+                };
+            }
+        }
+    }
+    full_payload.truncate(6 + end);
+
+    let mut next_ifd = 0u32;
+    if let Some(thumbnail) = thumbnail {
+        // TIFF offsets must be word-aligned.
+        if !full_payload.len().is_multiple_of(2) {
+            full_payload.push(0);
+        }
+        let new_ifd1 = full_payload.len() - 6;
+        let data_at = new_ifd1 + 2 + 3 * 12 + 4;
+        if 6 + data_at + thumbnail.len() <= MAX_EXIF_PAYLOAD {
+            full_payload.extend_from_slice(&u16_bytes(3));
+            for (tag, kind, value) in [
+                (0x0103u16, 3u16, 6u32), // Compression: JPEG
+                (0x0201, 4, data_at as u32),
+                (0x0202, 4, thumbnail.len() as u32),
+            ] {
+                full_payload.extend_from_slice(&u16_bytes(tag));
+                full_payload.extend_from_slice(&u16_bytes(kind));
+                full_payload.extend_from_slice(&u32_bytes(1));
+                if kind == 3 {
+                    full_payload.extend_from_slice(&u16_bytes(value as u16));
+                    full_payload.extend_from_slice(&[0, 0]);
+                } else {
+                    full_payload.extend_from_slice(&u32_bytes(value));
+                }
+            }
+            full_payload.extend_from_slice(&u32_bytes(0));
+            full_payload.extend_from_slice(thumbnail);
+            next_ifd = new_ifd1 as u32;
+        }
+    }
+    if let Some(pointer) = full_payload.get_mut(6 + next_ifd_at..6 + next_ifd_at + 4) {
+        pointer.copy_from_slice(&u32_bytes(next_ifd));
+    }
+}
+
+/// IFD0 tag naming the creator of the image.
+const EXIF_ARTIST_TAG: u16 = 0x013B;
+/// IFD0 tag holding the copyright notice.
+const EXIF_COPYRIGHT_TAG: u16 = 0x8298;
+
+/// Little-endian EXIF payload with an empty IFD0, for stamping tags into sources without one.
+fn empty_exif() -> Vec<u8> {
+    let mut payload = b"Exif\0\0II*\0".to_vec();
+    payload.extend_from_slice(&8u32.to_le_bytes());
+    payload.extend_from_slice(&0u16.to_le_bytes());
+    payload.extend_from_slice(&0u32.to_le_bytes());
+    payload
+}
+
+/// Rewrites IFD0 at the end of the payload with `tags` added as ASCII values, replacing any
+/// existing ones. The old IFD0 is left unreferenced, so every other offset stays valid.
+fn set_exif_text_tags(full_payload: &mut Vec<u8>, tags: &[(u16, String)]) {
+    if !full_payload.starts_with(b"Exif\0\0") || full_payload.len() < 14 {
+        return;
+    }
+    let is_le = full_payload[6..].starts_with(b"II");
+    let read_u16 = |data: &[u8], at: usize| {
+        let b = data.get(at..at + 2)?;
+        Some(if is_le {
+            u16::from_le_bytes([b[0], b[1]])
+        } else {
+            u16::from_be_bytes([b[0], b[1]])
+        })
+    };
+    let read_u32 = |data: &[u8], at: usize| {
+        let b = data.get(at..at + 4)?;
+        Some(if is_le {
+            u32::from_le_bytes([b[0], b[1], b[2], b[3]])
+        } else {
+            u32::from_be_bytes([b[0], b[1], b[2], b[3]])
+        })
+    };
+    let u16_bytes = |v: u16| {
+        if is_le {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+    let u32_bytes = |v: u32| {
+        if is_le {
+            v.to_le_bytes()
+        } else {
+            v.to_be_bytes()
+        }
+    };
+
+    let tiff = &full_payload[6..];
+    let Some(ifd0) = read_u32(tiff, 4).map(|v| v as usize) else {
+        return;
+    };
+    let Some(count) = read_u16(tiff, ifd0).map(usize::from) else {
+        return;
+    };
+    let Some(next_ifd) = read_u32(tiff, ifd0 + 2 + count * 12) else {
+        return;
+    };
+    let mut entries: Vec<Vec<u8>> = (0..count)
+        .filter_map(|i| tiff.get(ifd0 + 2 + i * 12..ifd0 + 14 + i * 12))
+        .filter(|entry| read_u16(entry, 0).is_some_and(|tag| tags.iter().all(|(t, _)| *t != tag)))
+        .map(<[u8]>::to_vec)
+        .collect();
+
+    // TIFF offsets must be word-aligned.
+    let start = full_payload.len() + full_payload.len() % 2;
+    let new_ifd0 = start - 6;
+    let mut data_at = new_ifd0 + 2 + (entries.len() + tags.len()) * 12 + 4;
+    let mut data = Vec::new();
+    for (tag, text) in tags {
+        let mut value = text.as_bytes().to_vec();
+        value.push(0);
+        let mut entry = Vec::with_capacity(12);
+        entry.extend_from_slice(&u16_bytes(*tag));
+        entry.extend_from_slice(&u16_bytes(2)); // ASCII
+        entry.extend_from_slice(&u32_bytes(value.len() as u32));
+        if value.len() <= 4 {
+            value.resize(4, 0);
+            entry.extend_from_slice(&value);
+        } else {
+            entry.extend_from_slice(&u32_bytes(data_at as u32));
+            if value.len() % 2 == 1 {
+                value.push(0);
+            }
+            data_at += value.len();
+            data.extend_from_slice(&value);
+        }
+        entries.push(entry);
+    }
+    if 6 + data_at > MAX_EXIF_PAYLOAD {
+        log::warn!("EXIF is too large to add artist and copyright tags");
+        return;
+    }
+    entries.sort_by_key(|entry| read_u16(entry, 0));
+
+    full_payload.resize(start, 0);
+    full_payload.extend_from_slice(&u16_bytes(entries.len() as u16));
+    for entry in &entries {
+        full_payload.extend_from_slice(entry);
+    }
+    full_payload.extend_from_slice(&u32_bytes(next_ifd));
+    full_payload.extend_from_slice(&data);
+    full_payload[10..14].copy_from_slice(&u32_bytes(new_ifd0 as u32));
+}
+
 /// Extracts ICC color profile from JPEG, PNG, or WebP files.
-fn extract_icc_profile(input_path: &PathBuf) -> Option<Vec<u8>> {
+fn extract_icc_profile(input_path: &Path) -> Option<Vec<u8>> {
     let file_bytes = std::fs::read(input_path).ok()?;
     let bytes_owned = bytes::Bytes::from(file_bytes);
 
@@ -189,24 +1076,27 @@ fn extract_icc_profile(input_path: &PathBuf) -> Option<Vec<u8>> {
     None
 }
 
-/// Applies EXIF orientation transform to correct image rotation.
-fn apply_orientation(img: DynamicImage, path: &PathBuf) -> DynamicImage {
+/// Reads the EXIF orientation tag, 1 (upright) when missing.
+fn exif_orientation(path: &Path) -> u32 {
     let file = match std::fs::File::open(path) {
         Ok(f) => f,
-        Err(_) => return img,
+        Err(_) => return 1,
     };
     let mut bufreader = std::io::BufReader::new(&file);
     let exifreader = exif::Reader::new();
-    let orientation = exifreader
+    exifreader
         .read_from_container(&mut bufreader)
         .ok()
         .and_then(|e| {
             e.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
                 .and_then(|f| f.value.get_uint(0))
         })
-        .unwrap_or(1);
+        .unwrap_or(1)
+}
 
-    match orientation {
+/// Applies EXIF orientation transform to correct image rotation.
+fn apply_orientation(img: DynamicImage, path: &Path) -> DynamicImage {
+    match exif_orientation(path) {
         2 => img.fliph(),
         3 => img.rotate180(),
         4 => img.flipv(),
@@ -218,6 +1108,131 @@ fn apply_orientation(img: DynamicImage, path: &PathBuf) -> DynamicImage {
     }
 }
 
+/// Builds the destination profile for a wide-gamut output space; `None` for sRGB.
+fn output_profile(space: OutputColorSpace) -> Option<lcms2::Profile> {
+    let xy = |x, y| lcms2::CIExyY { x, y, Y: 1.0 };
+    let d65 = xy(0.3127, 0.3290);
+    let (name, primaries, curve) = match space {
+        OutputColorSpace::Srgb => return None,
+        // Display P3 shares the sRGB transfer curve.
+        OutputColorSpace::DisplayP3 => (
+            "Display P3",
+            lcms2::CIExyYTRIPLE {
+                Red: xy(0.680, 0.320),
+                Green: xy(0.265, 0.690),
+                Blue: xy(0.150, 0.060),
+            },
+            lcms2::ToneCurve::new_parametric(
+                4,
+                &[2.4, 1.0 / 1.055, 0.055 / 1.055, 1.0 / 12.92, 0.04045],
+            )
+            .ok()?,
+        ),
+        OutputColorSpace::AdobeRgb => (
+            "Adobe RGB (1998)",
+            lcms2::CIExyYTRIPLE {
+                Red: xy(0.640, 0.330),
+                Green: xy(0.210, 0.710),
+                Blue: xy(0.150, 0.060),
+            },
+            lcms2::ToneCurve::new(563.0 / 256.0),
+        ),
+    };
+    let mut profile = lcms2::Profile::new_rgb(&d65, &primaries, &[&curve, &curve, &curve]).ok()?;
+    let mut description = lcms2::MLU::new(1);
+    description.set_text_ascii(name, lcms2::Locale::none());
+    profile.write_tag(
+        lcms2::TagSignature::ProfileDescriptionTag,
+        lcms2::Tag::MLU(&description),
+    );
+    Some(profile)
+}
+
+/// ICC bytes embedded in output files for the chosen color space.
+/// A custom sRGB profile that fails to load falls back to the bundled one.
+fn output_icc(options: &ConversionOptions) -> Cow<'static, [u8]> {
+    static DISPLAY_P3: OnceLock<Vec<u8>> = OnceLock::new();
+    static ADOBE_RGB: OnceLock<Vec<u8>> = OnceLock::new();
+    let cell = match options.color_space {
+        OutputColorSpace::Srgb => {
+            return match options.custom_srgb_icc.as_deref().map(load_rgb_profile) {
+                Some(Ok(icc)) => Cow::Owned(icc),
+                Some(Err(e)) => {
+                    log::warn!("Custom sRGB profile unusable ({:#}), using bundled", e);
+                    Cow::Borrowed(SRGB_ICC)
+                }
+                None => Cow::Borrowed(SRGB_ICC),
+            };
+        }
+        OutputColorSpace::DisplayP3 => &DISPLAY_P3,
+        OutputColorSpace::AdobeRgb => &ADOBE_RGB,
+    };
+    Cow::Borrowed(cell.get_or_init(|| {
+        output_profile(options.color_space)
+            .and_then(|p| p.icc().ok())
+            .unwrap_or_default()
+    }))
+}
+
+/// Reads an ICC file and checks that lcms2 accepts it as an RGB profile.
+pub fn load_rgb_profile(path: &Path) -> Result<Vec<u8>> {
+    let icc = std::fs::read(path)?;
+    let profile = lcms2::Profile::new_icc(&icc).context("Invalid ICC profile")?;
+    if profile.color_space() != lcms2::ColorSpaceSignature::RgbData {
+        anyhow::bail!("Not an RGB profile");
+    }
+    Ok(icc)
+}
+
+/// Converts an sRGB image into the chosen output color space.
+fn convert_to_output_space(img: DynamicImage, space: OutputColorSpace) -> Result<DynamicImage> {
+    let Some(out_prof) = output_profile(space) else {
+        return Ok(img);
+    };
+    let in_prof = lcms2::Profile::new_srgb();
+    let intent = lcms2::Intent::RelativeColorimetric;
+    Ok(match img {
+        DynamicImage::ImageRgb16(mut buffer) => {
+            let fmt = lcms2::PixelFormat::RGB_16;
+            let transform = lcms2::Transform::new(&in_prof, fmt, &out_prof, fmt, intent)?;
+            let mut pixels: Vec<[u16; 3]> =
+                buffer.chunks_exact(3).map(|c| [c[0], c[1], c[2]]).collect();
+            transform.transform_in_place(&mut pixels);
+            for (dst, src) in buffer.chunks_exact_mut(3).zip(&pixels) {
+                dst.copy_from_slice(src);
+            }
+            DynamicImage::ImageRgb16(buffer)
+        }
+        DynamicImage::ImageRgba16(mut buffer) => {
+            let fmt = lcms2::PixelFormat::RGBA_16;
+            let transform = lcms2::Transform::new(&in_prof, fmt, &out_prof, fmt, intent)?;
+            let mut pixels: Vec<[u16; 4]> = buffer
+                .chunks_exact(4)
+                .map(|c| [c[0], c[1], c[2], c[3]])
+                .collect();
+            transform.transform_in_place(&mut pixels);
+            for (dst, src) in buffer.chunks_exact_mut(4).zip(&pixels) {
+                dst.copy_from_slice(src);
+            }
+            DynamicImage::ImageRgba16(buffer)
+        }
+        img if img.color().has_alpha() => {
+            let mut buffer = img.to_rgba8();
+            let fmt = lcms2::PixelFormat::RGBA_8;
+            lcms2::Transform::new(&in_prof, fmt, &out_prof, fmt, intent)?
+                .transform_in_place(&mut buffer);
+            DynamicImage::ImageRgba8(buffer)
+        }
+        img => {
+            let mut buffer = img.to_rgb8();
+            let fmt = lcms2::PixelFormat::RGB_8;
+            lcms2::Transform::new(&in_prof, fmt, &out_prof, fmt, intent)?
+                .transform_in_place(&mut buffer);
+            DynamicImage::ImageRgb8(buffer)
+        }
+    })
+}
+
 /// Converts image colors from input ICC profile to sRGB.
 fn apply_color_correction(img: &mut DynamicImage, input_profile: &[u8]) -> Result<()> {
     let in_prof = lcms2::Profile::new_icc(input_profile).context("Invalid ICC profile")?;
@@ -237,9 +1252,38 @@ fn apply_color_correction(img: &mut DynamicImage, input_profile: &[u8]) -> Resul
     Ok(())
 }
 
-/// High-quality image resizing using CatmullRom interpolation.
-fn resize_image_fast(img: &DynamicImage, width: u32, height: u32) -> Result<DynamicImage> {
+/// High-quality image resizing using CatmullRom interpolation, or a box filter for drafts.
+fn resize_image_fast(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    draft: bool,
+) -> Result<DynamicImage> {
+    if draft {
+        return resize_with_filter(img, width, height, fast_image_resize::FilterType::Box);
+    }
+    if is_sixteen_bit(img) {
+        // The SIMD path is 8-bit only; keep the extra precision instead.
+        return Ok(img.resize_exact(width, height, FilterType::CatmullRom));
+    }
+    resize_with_filter(
+        img,
+        width,
+        height,
+        fast_image_resize::FilterType::CatmullRom,
+    )
+}
+
+/// SIMD resize with an explicit convolution filter.
+fn resize_with_filter(
+    img: &DynamicImage,
+    width: u32,
+    height: u32,
+    filter: fast_image_resize::FilterType,
+) -> Result<DynamicImage> {
     use fast_image_resize as fr;
+    ensure_non_empty(img.width(), img.height())?;
+    let (width, height) = (width.max(1), height.max(1));
     let src = fr::images::Image::from_vec_u8(
         img.width(),
         img.height(),
@@ -250,8 +1294,7 @@ fn resize_image_fast(img: &DynamicImage, width: u32, height: u32) -> Result<Dyna
     fr::Resizer::new().resize(
         &src,
         &mut dst,
-        &fr::ResizeOptions::new()
-            .resize_alg(fr::ResizeAlg::Convolution(fr::FilterType::CatmullRom)),
+        &fr::ResizeOptions::new().resize_alg(fr::ResizeAlg::Convolution(filter)),
     )?;
     Ok(DynamicImage::ImageRgba8(
         image::ImageBuffer::from_raw(width, height, dst.into_vec()).context("Buffer")?,
@@ -262,8 +1305,11 @@ fn resize_image_fast(img: &DynamicImage, width: u32, height: u32) -> Result<Dyna
 fn encode_jpeg(
     img: &DynamicImage,
     quality: u8,
+    dpi: u16,
     metadata: Option<&Metadata>,
-    writer: &mut BufWriter<File>,
+    icc: &[u8],
+    draft: bool,
+    writer: &mut impl Write,
 ) -> Result<()> {
     let rgb = img.to_rgb8();
     let (width, height) = (rgb.width() as usize, rgb.height() as usize);
@@ -271,21 +1317,39 @@ fn encode_jpeg(
     let buf = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         let mut comp = mozjpeg::Compress::new(mozjpeg::ColorSpace::JCS_RGB);
         comp.set_size(width, height);
-        comp.set_quality((quality as f32).min(99.0));
+        // 100 means near-lossless: full-precision quant tables and no smoothing.
+        if quality >= 100 {
+            comp.set_quality(100.0);
+            comp.set_smoothing_factor(0);
+        } else {
+            comp.set_quality(quality as f32);
+            comp.set_smoothing_factor(if draft { 0 } else { 1 });
+        }
         comp.set_progressive_mode();
         comp.set_optimize_scans(true);
-        comp.set_smoothing_factor(1);
         comp.set_chroma_sampling_pixel_sizes((1, 1), (1, 1));
+        if dpi > 0 {
+            comp.set_pixel_density(mozjpeg::PixelDensity {
+                unit: mozjpeg::PixelDensityUnit::Inches,
+                x: dpi,
+                y: dpi,
+            });
+        }
 
         let mut comp = comp.start_compress(Vec::new()).unwrap();
         comp.write_scanlines(rgb.as_raw()).unwrap();
         comp.finish().unwrap()
     }))
     .unwrap_or_else(|_| {
+        log::warn!("mozjpeg failed, using fallback JPEG encoder");
+        crate::diagnostics::note_jpeg_fallback();
         let mut buf = Vec::new();
         let mut enc = jpeg_encoder::Encoder::new(&mut buf, quality);
         enc.set_optimized_huffman_tables(true);
         enc.set_progressive(true);
+        if dpi > 0 {
+            enc.set_density(jpeg_encoder::Density::Inch { x: dpi, y: dpi });
+        }
         let _ = enc.encode(
             rgb.as_raw(),
             rgb.width() as u16,
@@ -297,18 +1361,16 @@ fn encode_jpeg(
 
     match img_parts::jpeg::Jpeg::from_bytes(buf.clone().into()) {
         Ok(mut jpeg) => {
-            jpeg.set_icc_profile(Some(SRGB_ICC.into()));
-            if let Some(meta) = metadata {
-                if let Some(raw_exif) = &meta.exif {
-                    let mut payload = raw_exif.clone();
-                    patch_orientation_in_place(&mut payload);
-                    let segments = jpeg.segments_mut();
-                    segments.retain(|s| !s.contents().starts_with(b"Exif\0\0"));
-                    segments.insert(
-                        0,
-                        JpegSegment::new_with_contents(0xE1, bytes::Bytes::from(payload)),
-                    );
-                }
+            if !icc.is_empty() {
+                jpeg.set_icc_profile(Some(icc.to_vec().into()));
+            }
+            if let Some(payload) = metadata.and_then(|meta| exif_payload(meta, img)) {
+                let segments = jpeg.segments_mut();
+                segments.retain(|s| !s.contents().starts_with(b"Exif\0\0"));
+                segments.insert(
+                    0,
+                    JpegSegment::new_with_contents(0xE1, bytes::Bytes::from(payload)),
+                );
             }
             jpeg.encoder().write_to(writer)?;
         }
@@ -317,26 +1379,178 @@ fn encode_jpeg(
     Ok(())
 }
 
-/// Encodes image to PNG format with optional oxipng optimization.
-fn encode_png(img: &DynamicImage, compressed: bool, writer: &mut BufWriter<File>) -> Result<()> {
-    let (width, height) = (img.width(), img.height());
-    let has_alpha = img.color().has_alpha() && img.to_rgba8().pixels().any(|p| p.0[3] < 255);
+/// Builds the `Exif\0\0`-prefixed payload to embed in the output, with the thumbnail
+/// regenerated or dropped and the credits stamped in.
+fn exif_payload(meta: &Metadata, img: &DynamicImage) -> Option<Vec<u8>> {
+    let payload = meta.exif.clone().map(|mut payload| {
+        // The source thumbnail still shows the image before resizing and rotation.
+        let thumbnail = meta
+            .regenerate_thumbnail
+            .then(|| exif_thumbnail(img))
+            .flatten();
+        replace_exif_thumbnail(&mut payload, thumbnail.as_deref());
+        payload
+    });
+    if meta.credits.is_empty() {
+        return payload;
+    }
+    let mut payload = payload.unwrap_or_else(empty_exif);
+    set_exif_text_tags(&mut payload, &meta.credits);
+    Some(payload)
+}
+
+/// Maps the image onto at most `max_colors` palette entries, exactly when its colors already
+/// fit and via NeuQuant otherwise. Returns indices, the RGB palette, and alpha when not opaque.
+fn index_image(img: &DynamicImage, max_colors: usize) -> (Vec<u8>, Vec<u8>, Option<Vec<u8>>) {
+    let rgba = img.to_rgba8();
+    let max_colors = max_colors.clamp(2, 256);
+
+    let mut lookup: std::collections::HashMap<[u8; 4], u8> = std::collections::HashMap::new();
+    let mut colors: Vec<[u8; 4]> = Vec::new();
+    let mut indices = Vec::with_capacity(rgba.len() / 4);
+    for p in rgba.pixels() {
+        let index = match lookup.get(&p.0) {
+            Some(&index) => index,
+            None if colors.len() < max_colors => {
+                let index = colors.len() as u8;
+                lookup.insert(p.0, index);
+                colors.push(p.0);
+                index
+            }
+            None => break,
+        };
+        indices.push(index);
+    }
+
+    // Resizing blends new colors into the image, so it may no longer fit the palette.
+    if indices.len() * 4 < rgba.len() {
+        let quantizer = color_quant::NeuQuant::new(10, max_colors, rgba.as_raw());
+        indices = rgba
+            .pixels()
+            .map(|p| quantizer.index_of(&p.0) as u8)
+            .collect();
+        colors = (0..max_colors)
+            .filter_map(|i| quantizer.lookup(i))
+            .collect();
+    }
+
+    let palette = colors.iter().flat_map(|c| [c[0], c[1], c[2]]).collect();
+    let alpha = colors
+        .iter()
+        .any(|c| c[3] < 255)
+        .then(|| colors.iter().map(|c| c[3]).collect());
+    (indices, palette, alpha)
+}
+
+/// Palette size of an indexed PNG, or `None` for truecolor and grayscale PNGs.
+fn png_palette_size(input_path: &Path) -> Option<usize> {
+    let reader = png::Decoder::new(File::open(input_path).ok()?)
+        .read_info()
+        .ok()?;
+    let info = reader.info();
+    (info.color_type == png::ColorType::Indexed)
+        .then(|| info.palette.as_ref().map_or(256, |p| p.len() / 3))
+}
+
+/// Whether the image carries 16 bits per channel.
+fn is_sixteen_bit(img: &DynamicImage) -> bool {
+    matches!(
+        img,
+        DynamicImage::ImageRgb16(_) | DynamicImage::ImageRgba16(_)
+    )
+}
+
+/// Encodes image to PNG format, optimized with oxipng at preset `effort` (0–6) when given.
+/// `icc` is embedded as an iCCP chunk; `None` marks the image with the chunks `tag` picks
+/// instead, and an empty profile leaves it untagged.
+/// `palette` writes an indexed PNG with at most that many colors.
+fn encode_png(
+    img: &DynamicImage,
+    effort: Option<u8>,
+    dpi: u16,
+    icc: Option<&[u8]>,
+    tag: PngColorTag,
+    palette: Option<usize>,
+    writer: &mut impl Write,
+) -> Result<()> {
+    let (width, height) = (img.width(), img.height());
+    let sixteen_bit = is_sixteen_bit(img);
+    let has_alpha = img.color().has_alpha()
+        && if sixteen_bit {
+            img.to_rgba16().pixels().any(|p| p.0[3] < u16::MAX)
+        } else {
+            img.to_rgba8().pixels().any(|p| p.0[3] < 255)
+        };
 
     let mut buffer = Vec::new();
     {
-        let (comp, filter) = if compressed {
+        let (comp, filter) = if effort.is_some() {
             (png::Compression::Best, png::FilterType::Paeth)
         } else {
             (png::Compression::Default, png::FilterType::Sub)
         };
 
-        let mut enc = png::Encoder::new(&mut buffer, width, height);
-        enc.set_depth(png::BitDepth::Eight);
+        let mut enc = match icc {
+            Some([]) => png::Encoder::new(&mut buffer, width, height),
+            Some(icc) => {
+                let mut info = png::Info::with_size(width, height);
+                info.icc_profile = Some(icc.to_vec().into());
+                png::Encoder::with_info(&mut buffer, info)?
+            }
+            None => {
+                let mut enc = png::Encoder::new(&mut buffer, width, height);
+                match tag {
+                    PngColorTag::Srgb => enc.set_source_srgb(png::SrgbRenderingIntent::Perceptual),
+                    PngColorTag::Linear => {
+                        enc.set_source_gamma(png::ScaledFloat::new(1.0));
+                        enc.set_source_chromaticities(png::SourceChromaticities::new(
+                            (0.3127, 0.3290),
+                            (0.64, 0.33),
+                            (0.30, 0.60),
+                            (0.15, 0.06),
+                        ));
+                    }
+                    PngColorTag::None => {}
+                }
+                enc
+            }
+        };
+        enc.set_depth(if sixteen_bit {
+            png::BitDepth::Sixteen
+        } else {
+            png::BitDepth::Eight
+        });
         enc.set_compression(comp);
         enc.set_filter(filter);
-        enc.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+        if dpi > 0 {
+            let per_meter = (dpi as f64 / 0.0254).round() as u32;
+            enc.set_pixel_dims(Some(png::PixelDimensions {
+                xppu: per_meter,
+                yppu: per_meter,
+                unit: png::Unit::Meter,
+            }));
+        }
 
-        if has_alpha {
+        if sixteen_bit {
+            // PNG stores 16-bit samples big-endian.
+            let samples = if has_alpha {
+                enc.set_color(png::ColorType::Rgba);
+                img.to_rgba16().into_raw()
+            } else {
+                enc.set_color(png::ColorType::Rgb);
+                img.to_rgb16().into_raw()
+            };
+            let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_be_bytes()).collect();
+            enc.write_header()?.write_image_data(&bytes)?;
+        } else if let Some(max_colors) = palette {
+            let (indices, colors, alpha) = index_image(img, max_colors);
+            enc.set_color(png::ColorType::Indexed);
+            enc.set_palette(colors);
+            if let Some(alpha) = alpha {
+                enc.set_trns(alpha);
+            }
+            enc.write_header()?.write_image_data(&indices)?;
+        } else if has_alpha {
             enc.set_color(png::ColorType::Rgba);
             enc.write_header()?.write_image_data(&img.to_rgba8())?;
         } else {
@@ -345,13 +1559,16 @@ fn encode_png(img: &DynamicImage, compressed: bool, writer: &mut BufWriter<File>
         }
     }
 
-    if compressed {
-        let mut opts = oxipng::Options::from_preset(6);
+    if let Some(effort) = effort {
+        let mut opts = oxipng::Options::from_preset(effort);
         opts.strip = oxipng::StripChunks::Safe;
         opts.optimize_alpha = true;
         match oxipng::optimize_from_memory(&buffer, &opts) {
             Ok(optimized) => writer.write_all(&optimized)?,
-            Err(_) => writer.write_all(&buffer)?,
+            Err(e) => {
+                log::warn!("oxipng failed ({}), writing unoptimized PNG", e);
+                writer.write_all(&buffer)?
+            }
         }
     } else {
         writer.write_all(&buffer)?;
@@ -359,142 +1576,2048 @@ fn encode_png(img: &DynamicImage, compressed: bool, writer: &mut BufWriter<File>
     Ok(())
 }
 
+/// Writes an encoded PNG with an eXIf chunk holding `payload`. Added after oxipng, which
+/// strips eXIf along with the other ancillary chunks.
+fn write_png_exif(png_data: &[u8], payload: Vec<u8>, writer: &mut impl Write) -> Result<()> {
+    let Ok(mut png) = img_parts::png::Png::from_bytes(png_data.to_vec().into()) else {
+        writer.write_all(png_data)?;
+        return Ok(());
+    };
+    let tiff = bytes::Bytes::from(payload).slice(6..);
+    let chunks = png.chunks_mut();
+    chunks.retain(|c| c.kind() != *b"eXIf");
+    // Viewers only read orientation from an eXIf chunk that precedes the image data.
+    let position = chunks
+        .iter()
+        .position(|c| c.kind() == *b"IDAT")
+        .unwrap_or(chunks.len());
+    chunks.insert(position, img_parts::png::PngChunk::new(*b"eXIf", tiff));
+    png.encoder().write_to(writer)?;
+    Ok(())
+}
+
 /// Encodes image to WebP format with lossy compression.
-fn encode_webp(img: &DynamicImage, quality: u8, writer: &mut BufWriter<File>) -> Result<()> {
+fn encode_webp(
+    img: &DynamicImage,
+    quality: u8,
+    lossless: bool,
+    icc: &[u8],
+    metadata: Option<&Metadata>,
+    writer: &mut impl Write,
+) -> Result<()> {
     let rgba = img.to_rgba8();
     let (width, height) = (rgba.width(), rgba.height());
     let encoder = webp::Encoder::from_rgba(rgba.as_raw(), width, height);
-    let webp_data = encoder.encode(quality as f32);
+    let webp_data = if lossless {
+        encoder.encode_lossless()
+    } else {
+        encoder.encode(quality as f32)
+    };
+    let exif = metadata.and_then(|meta| exif_payload(meta, img));
+    write_webp(&webp_data, icc, exif, writer)
+}
 
-    match img_parts::webp::WebP::from_bytes(webp_data.to_vec().into()) {
-        Ok(mut webp) => {
-            webp.set_icc_profile(Some(SRGB_ICC.into()));
-            webp.encoder().write_to(writer)?;
+/// Writes an encoded WebP stream with the ICC profile and EXIF payload embedded, if any.
+/// Fails instead of writing a corrupt file when the encoder returned nothing usable.
+fn write_webp(
+    webp_data: &[u8],
+    icc: &[u8],
+    exif: Option<Vec<u8>>,
+    writer: &mut impl Write,
+) -> Result<()> {
+    if webp_data.is_empty() {
+        anyhow::bail!("WebP encoder produced no data");
+    }
+    let mut webp = img_parts::webp::WebP::from_bytes(webp_data.to_vec().into())
+        .map_err(|e| anyhow::anyhow!("WebP encoder produced an invalid stream: {}", e))?;
+    if !icc.is_empty() {
+        webp.set_icc_profile(Some(icc.to_vec().into()));
+    }
+    if let Some(payload) = exif {
+        // set_exif marks the EXIF flag but stores the JPEG-style prefix, so the chunk is
+        // rewritten with the bare TIFF data the WebP spec calls for.
+        let tiff = bytes::Bytes::from(payload).slice(6..);
+        webp.set_exif(Some(tiff.clone()));
+        for chunk in webp.chunks_mut().iter_mut() {
+            if chunk.id() == *b"EXIF" {
+                *chunk = img_parts::riff::RiffChunk::new(
+                    *b"EXIF",
+                    img_parts::riff::RiffContent::Data(tiff.clone()),
+                );
+            }
         }
-        Err(_) => writer.write_all(&webp_data)?,
     }
+    webp.encoder().write_to(writer)?;
     Ok(())
 }
 
-/// Main conversion function that orchestrates loading, processing, and encoding.
-pub fn convert_image(input_path: &PathBuf, options: &ConversionOptions) -> Result<()> {
-    validate_file_magic(input_path)?;
+/// Decodes a source image with sRGB color correction and, when `orient` is set, EXIF
+/// orientation applied. `high_bit_depth` keeps HEIC sources above 8 bits at 16 bits per channel.
+pub fn load_source_image(
+    input_path: &Path,
+    frame: FrameChoice,
+    frame_number: u32,
+    high_bit_depth: bool,
+    correction: ColorCorrection,
+    orient: bool,
+) -> Result<(DynamicImage, SourceFormat)> {
+    let source_format = detect_source_format(input_path)?;
 
-    const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024;
-    let file_size = std::fs::metadata(input_path).map(|m| m.len()).unwrap_or(0);
-    if file_size > MAX_FILE_SIZE {
-        anyhow::bail!("File too large (max 100MB)");
+    let (mut img, _) = if source_format == SourceFormat::Svg {
+        (render_svg(input_path, |w, h| (w, h))?, None)
+    } else if source_format.uses_libheif() {
+        if !source_format.is_decodable() {
+            anyhow::bail!(
+                "{} support unavailable: libheif has no decoder installed",
+                source_format.extension().to_uppercase()
+            );
+        }
+        crate::heic::load_heic_via_libheif(input_path, high_bit_depth)?
+    } else if source_format.is_multi_frame() {
+        let count = frame_count(input_path, source_format)?;
+        let index = resolve_frame(frame, frame_number, count)?;
+        (decode_frame(input_path, source_format, index)?, None)
+    } else {
+        let decoder_format = match source_format {
+            SourceFormat::Png => image::ImageFormat::Png,
+            SourceFormat::WebP => image::ImageFormat::WebP,
+            _ => image::ImageFormat::Jpeg,
+        };
+        let mut reader = image::io::Reader::open(input_path)?;
+        reader.set_format(decoder_format);
+        (reader.decode().context("Failed to decode image")?, None)
+    };
+    ensure_non_empty(img.width(), img.height())?;
+
+    if orient && !source_format.uses_libheif() {
+        img = apply_orientation(img, input_path);
     }
 
-    let ext = input_path
-        .extension()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_lowercase();
+    if correction != ColorCorrection::Off {
+        img = correct_to_srgb(img, extract_icc_profile(input_path).as_deref(), correction).0;
+    }
+    Ok((img, source_format))
+}
 
-    let (mut img, _) = if ext == "heic" || ext == "heif" {
-        crate::heic::load_heic_via_libheif(input_path).context("Failed to load HEIC")?
+/// Converts pixels from the embedded ICC profile to sRGB as `correction` allows.
+/// The flag is set when the transform failed and the pixels are still in the source space.
+fn correct_to_srgb(
+    img: DynamicImage,
+    icc: Option<&[u8]>,
+    correction: ColorCorrection,
+) -> (DynamicImage, bool) {
+    let Some(icc) = icc else {
+        return (img, false);
+    };
+    match correction {
+        ColorCorrection::Off => return (img, false),
+        ColorCorrection::SkipSrgb if is_srgb_profile(icc) => return (img, false),
+        _ => {}
+    }
+    let mut img = if img.color().has_alpha() {
+        DynamicImage::ImageRgba8(img.to_rgba8())
     } else {
-        (
-            image::open(input_path).context("Failed to decode image")?,
-            None,
-        )
+        DynamicImage::ImageRgb8(img.to_rgb8())
     };
+    match apply_color_correction(&mut img, icc) {
+        Ok(()) => (img, false),
+        Err(e) => {
+            log::warn!(
+                "Color correction failed ({:#}), leaving the output untagged",
+                e
+            );
+            (img, true)
+        }
+    }
+}
 
-    if ext != "heic" && ext != "heif" {
-        img = apply_orientation(img, input_path);
+/// Recognizes sRGB profiles by their description, e.g. "sRGB IEC61966-2.1" or "sRGB built-in".
+fn is_srgb_profile(icc: &[u8]) -> bool {
+    lcms2::Profile::new_icc(icc)
+        .ok()
+        .filter(|p| p.color_space() == lcms2::ColorSpaceSignature::RgbData)
+        .and_then(|p| p.info(lcms2::InfoType::Description, lcms2::Locale::none()))
+        .is_some_and(|d| d.to_ascii_lowercase().contains("srgb"))
+}
+
+/// Loads system fonts once for SVG text rendering.
+fn svg_fonts() -> std::sync::Arc<resvg::usvg::fontdb::Database> {
+    static FONTS: OnceLock<std::sync::Arc<resvg::usvg::fontdb::Database>> = OnceLock::new();
+    FONTS
+        .get_or_init(|| {
+            let mut fonts = resvg::usvg::fontdb::Database::new();
+            fonts.load_system_fonts();
+            std::sync::Arc::new(fonts)
+        })
+        .clone()
+}
+
+/// Parses an SVG, resolving relative image references next to the file.
+fn parse_svg(input_path: &Path) -> Result<resvg::usvg::Tree> {
+    let options = resvg::usvg::Options {
+        resources_dir: input_path.parent().map(Path::to_path_buf),
+        fontdb: svg_fonts(),
+        ..Default::default()
+    };
+    resvg::usvg::Tree::from_data(&std::fs::read(input_path)?, &options)
+        .context("Failed to parse SVG")
+}
+
+/// Intrinsic SVG size from width/height, or the viewBox when those are missing.
+fn svg_dimensions(tree: &resvg::usvg::Tree) -> (u32, u32) {
+    let size = tree.size();
+    (
+        (size.width().ceil() as u32).max(1),
+        (size.height().ceil() as u32).max(1),
+    )
+}
+
+/// Rasterizes an SVG at the size `target` picks from its intrinsic size.
+fn render_svg(
+    input_path: &Path,
+    target: impl FnOnce(u32, u32) -> (u32, u32),
+) -> Result<DynamicImage> {
+    let tree = parse_svg(input_path)?;
+    let (w, h) = svg_dimensions(&tree);
+    let (tw, th) = target(w, h);
+    ensure_non_empty(tw, th)?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(tw, th)
+        .with_context(|| format!("SVG render size {}x{} is too large", tw, th))?;
+    let transform = resvg::tiny_skia::Transform::from_scale(
+        tw as f32 / tree.size().width(),
+        th as f32 / tree.size().height(),
+    );
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    let buffer = pixmap_to_image(&pixmap).context("SVG render failed")?;
+    Ok(DynamicImage::ImageRgba8(buffer))
+}
+
+/// Copies a rendered pixmap out; tiny-skia stores premultiplied alpha.
+fn pixmap_to_image(pixmap: &resvg::tiny_skia::Pixmap) -> Option<image::RgbaImage> {
+    let pixels = pixmap
+        .pixels()
+        .iter()
+        .flat_map(|p| {
+            let c = p.demultiply();
+            [c.red(), c.green(), c.blue(), c.alpha()]
+        })
+        .collect();
+    image::RgbaImage::from_raw(pixmap.width(), pixmap.height(), pixels)
+}
+
+/// Renders one centered line of text with the system sans-serif font, shortened with an
+/// ellipsis to fit `width`.
+fn render_label(label: &str, width: u32, height: u32, color: [u8; 3]) -> Option<image::RgbaImage> {
+    let size = height as f32 * 0.7;
+    // Average sans-serif glyphs are a bit over half as wide as they are tall.
+    let max_chars = ((width as f32 / (size * 0.55)) as usize).max(1);
+    let label = if label.chars().count() > max_chars {
+        let kept: String = label.chars().take(max_chars.saturating_sub(1)).collect();
+        format!("{}…", kept)
+    } else {
+        label.to_string()
+    };
+    let escaped = label
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;");
+    let svg = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}"><text x="{x}" y="{y}" font-family="sans-serif" font-size="{size}" text-anchor="middle" fill="rgb({r},{g},{b})">{text}</text></svg>"#,
+        w = width,
+        h = height,
+        x = width as f32 / 2.0,
+        y = height as f32 * 0.75,
+        size = size,
+        r = color[0],
+        g = color[1],
+        b = color[2],
+        text = escaped,
+    );
+    let options = resvg::usvg::Options {
+        fontdb: svg_fonts(),
+        ..Default::default()
+    };
+    let tree = resvg::usvg::Tree::from_str(&svg, &options).ok()?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(width, height)?;
+    resvg::render(
+        &tree,
+        resvg::tiny_skia::Transform::identity(),
+        &mut pixmap.as_mut(),
+    );
+    pixmap_to_image(&pixmap)
+}
+
+/// Parses `#rrggbb` (the `#` is optional).
+pub fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.trim().trim_start_matches('#');
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
     }
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
 
-    let is_jpg_input = ext == "jpg" || ext == "jpeg";
-    let is_jpg_output = matches!(options.format, ImageFormat::Jpeg);
-    let metadata = if options.keep_metadata && is_jpg_input && is_jpg_output {
-        extract_metadata(input_path)
+/// Composites `inputs` into one grid image, each scaled to fit a square cell with its file
+/// name below, and writes it to the first input's output folder. The name comes from the
+/// filename settings applied to a `contact_sheet` stem.
+pub fn write_contact_sheet(
+    inputs: &[PathBuf],
+    options: &ConversionOptions,
+) -> Result<ConversionReport> {
+    const GAP: u32 = 8;
+    let first = inputs
+        .first()
+        .context("No files to put on a contact sheet")?;
+    let columns = options.contact_sheet_columns.clamp(1, inputs.len() as u32);
+    let cell = options
+        .contact_sheet_cell
+        .max(crate::constants::MIN_CONTACT_SHEET_CELL);
+    let label_height = if options.contact_sheet_labels {
+        (cell / 10).max(14)
     } else {
-        None
+        0
+    };
+    let rows = (inputs.len() as u32).div_ceil(columns);
+    let width = columns * (cell + GAP) + GAP;
+    let height = rows * (cell + label_height + GAP) + GAP;
+    let limit = options.format.max_dimension();
+    if width > limit || height > limit {
+        anyhow::bail!(
+            "Contact sheet of {}x{} exceeds the {} limit of {}px per side; use fewer files or smaller cells",
+            width,
+            height,
+            options.format,
+            limit
+        );
+    }
+    let background = parse_hex_color(&options.contact_sheet_background).unwrap_or([255; 3]);
+    let luma =
+        0.299 * background[0] as f32 + 0.587 * background[1] as f32 + 0.114 * background[2] as f32;
+    let label_color = if luma > 128.0 { [0; 3] } else { [255; 3] };
+    let [r, g, b] = background;
+    let mut sheet = image::RgbaImage::from_pixel(width, height, image::Rgba([r, g, b, 255]));
+
+    for (i, path) in inputs.iter().enumerate() {
+        let (column, row) = (i as u32 % columns, i as u32 / columns);
+        let x = GAP + column * (cell + GAP);
+        let y = GAP + row * (cell + label_height + GAP);
+        let img = match load_source_image(
+            path,
+            options.frame_choice,
+            options.frame_number,
+            false,
+            options.color_correction,
+            !options.ignore_exif_orientation,
+        ) {
+            Ok((img, _)) => img,
+            Err(e) => {
+                log::warn!("Contact sheet skipped {}: {:#}", path.display(), e);
+                continue;
+            }
+        };
+        let scale = (cell as f64 / img.width().max(img.height()) as f64).min(1.0);
+        let tw = ((img.width() as f64 * scale).round() as u32).clamp(1, cell);
+        let th = ((img.height() as f64 * scale).round() as u32).clamp(1, cell);
+        let thumb = resize_image_fast(&img, tw, th, options.draft_mode)
+            .unwrap_or_else(|_| img.thumbnail_exact(tw, th));
+        image::imageops::overlay(
+            &mut sheet,
+            &thumb.to_rgba8(),
+            (x + (cell - tw) / 2) as i64,
+            (y + (cell - th) / 2) as i64,
+        );
+        if label_height > 0 {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if let Some(label) = render_label(&name, cell, label_height, label_color) {
+                image::imageops::overlay(&mut sheet, &label, x as i64, (y + cell) as i64);
+            }
+        }
+    }
+
+    let options = ConversionOptions {
+        keep_source_format: false,
+        color_space: OutputColorSpace::Srgb,
+        output_timestamp: OutputTimestamp::Conversion,
+        write_captions: false,
+        ..options.clone()
+    };
+    let sheet = DynamicImage::ImageRgba8(sheet);
+    let name_source = first.with_file_name("contact_sheet");
+    write_output(
+        &name_source,
+        &options,
+        0,
+        OutputTarget::Folder,
+        (width, height),
+        Some(format!("{} files", inputs.len())),
+        |mut writer| {
+            encode_output(
+                &sheet,
+                &options,
+                None,
+                options.webp_lossless,
+                None,
+                true,
+                &mut writer,
+            )
+            .context(ENCODE_FAILED)
+        },
+    )
+}
+
+/// Reads image dimensions and file size without decoding pixel data.
+pub fn read_file_info(input_path: &Path) -> Result<FileInfo> {
+    let size_bytes = std::fs::metadata(input_path)?.len();
+    let format = detect_source_format(input_path)?;
+    let (width, height) = if format == SourceFormat::Svg {
+        svg_dimensions(&parse_svg(input_path)?)
+    } else if format.uses_libheif() {
+        crate::heic::read_heic_dimensions(input_path)?
+    } else {
+        image_dimensions(input_path)?
+    };
+    Ok(FileInfo {
+        width,
+        height,
+        size_bytes,
+        frames: frame_count(input_path, format).unwrap_or(1) as u32,
+    })
+}
+
+/// Reads dimensions from the header by content, so missing or wrong extensions do not matter.
+fn image_dimensions(input_path: &Path) -> Result<(u32, u32)> {
+    Ok(image::io::Reader::open(input_path)?
+        .with_guessed_format()?
+        .into_dimensions()?)
+}
+
+/// Checks for real transparency, reading only the header when the color type has no alpha.
+pub fn has_transparency(input_path: &Path) -> bool {
+    let color = match detect_source_format(input_path) {
+        Ok(SourceFormat::Jpeg) | Err(_) => return false,
+        Ok(SourceFormat::Heif | SourceFormat::Avif) => return crate::heic::has_alpha(input_path),
+        // Header color types say little for these, so check the decoded first frame directly.
+        Ok(SourceFormat::Gif | SourceFormat::Tiff | SourceFormat::Svg) => None,
+        Ok(SourceFormat::Png) => File::open(input_path)
+            .ok()
+            .and_then(|f| image::codecs::png::PngDecoder::new(BufReader::new(f)).ok())
+            .map(|d| d.color_type()),
+        Ok(SourceFormat::WebP) => File::open(input_path)
+            .ok()
+            .and_then(|f| image::codecs::webp::WebPDecoder::new(BufReader::new(f)).ok())
+            .map(|d| d.color_type()),
+    };
+    if color.is_some_and(|c| !c.has_alpha()) {
+        return false;
+    }
+    load_source_image(
+        input_path,
+        FrameChoice::First,
+        1,
+        false,
+        ColorCorrection::Off,
+        false,
+    )
+    .map(|(img, _)| img.to_rgba8().pixels().any(|p| p.0[3] < 255))
+    .unwrap_or(false)
+}
+
+/// Composites transparent pixels onto white for formats without an alpha channel.
+fn flatten_onto_white(img: DynamicImage) -> DynamicImage {
+    if !img.color().has_alpha() {
+        return img;
+    }
+    let rgba = img.to_rgba8();
+    DynamicImage::ImageRgb8(image::RgbImage::from_fn(
+        rgba.width(),
+        rgba.height(),
+        |x, y| {
+            let [r, g, b, a] = rgba.get_pixel(x, y).0;
+            let blend = |c: u8| ((c as u32 * a as u32 + 255 * (255 - a as u32) + 127) / 255) as u8;
+            image::Rgb([blend(r), blend(g), blend(b)])
+        },
+    ))
+}
+
+/// Counts GIF frames or TIFF pages without decoding pixel data; other formats have one.
+pub fn frame_count(input_path: &Path, format: SourceFormat) -> Result<usize> {
+    if format == SourceFormat::WebP {
+        // Each animation frame is an ANMF chunk; still images have none.
+        let webp = img_parts::webp::WebP::from_bytes(std::fs::read(input_path)?.into())?;
+        return Ok(webp.chunks_by_id(*b"ANMF").count().max(1));
+    }
+    let reader = BufReader::new(File::open(input_path)?);
+    let mut count = 0;
+    match format {
+        SourceFormat::Gif => {
+            let mut opts = gif::DecodeOptions::new();
+            opts.skip_frame_decoding(true);
+            let mut decoder = opts.read_info(reader)?;
+            while decoder.read_next_frame()?.is_some() {
+                count += 1;
+            }
+        }
+        SourceFormat::Tiff => {
+            let mut decoder = tiff::decoder::Decoder::new(reader)?;
+            count = 1;
+            while decoder.more_images() {
+                decoder.next_image()?;
+                count += 1;
+            }
+        }
+        _ => count = 1,
+    }
+    Ok(count)
+}
+
+/// Maps the frame option to a 0-based index, failing when the file has too few frames.
+fn resolve_frame(frame: FrameChoice, frame_number: u32, count: usize) -> Result<usize> {
+    match frame {
+        FrameChoice::First => Ok(0),
+        FrameChoice::Last => Ok(count.saturating_sub(1)),
+        FrameChoice::Number => {
+            let index = frame_number.max(1) as usize - 1;
+            if index >= count {
+                anyhow::bail!("Frame {} not found (file has {})", frame_number, count);
+            }
+            Ok(index)
+        }
+    }
+}
+
+/// Decodes one GIF frame (composited onto the canvas) or one TIFF page.
+fn decode_frame(input_path: &Path, format: SourceFormat, index: usize) -> Result<DynamicImage> {
+    use image::AnimationDecoder;
+    use tiff::decoder::DecodingResult as D;
+    use tiff::ColorType as C;
+
+    let reader = BufReader::new(File::open(input_path)?);
+    if format == SourceFormat::Gif {
+        let frame = image::codecs::gif::GifDecoder::new(reader)?
+            .into_frames()
+            .nth(index)
+            .context("GIF frame missing")??;
+        return Ok(DynamicImage::ImageRgba8(frame.into_buffer()));
+    }
+    if format == SourceFormat::WebP {
+        let decoder = image::codecs::webp::WebPDecoder::new(reader)?;
+        if !decoder.has_animation() {
+            return Ok(DynamicImage::from_decoder(decoder)?);
+        }
+        let frame = decoder
+            .into_frames()
+            .nth(index)
+            .context("WebP frame missing")??;
+        return Ok(DynamicImage::ImageRgba8(frame.into_buffer()));
+    }
+    if index == 0 {
+        // The image crate handles more TIFF color types for the first page.
+        let mut reader = image::io::Reader::new(reader);
+        reader.set_format(image::ImageFormat::Tiff);
+        return reader.decode().context("Failed to decode image");
+    }
+
+    let mut decoder = tiff::decoder::Decoder::new(reader)?;
+    decoder.seek_to_image(index)?;
+    let (w, h) = decoder.dimensions()?;
+    let color = decoder.colortype()?;
+    let img = match (decoder.read_image()?, color) {
+        (D::U8(buf), C::Gray(8)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageLuma8)
+        }
+        (D::U8(buf), C::GrayA(8)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageLumaA8)
+        }
+        (D::U8(buf), C::RGB(8)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageRgb8)
+        }
+        (D::U8(buf), C::RGBA(8)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageRgba8)
+        }
+        (D::U16(buf), C::Gray(16)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageLuma16)
+        }
+        (D::U16(buf), C::GrayA(16)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageLumaA16)
+        }
+        (D::U16(buf), C::RGB(16)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageRgb16)
+        }
+        (D::U16(buf), C::RGBA(16)) => {
+            image::ImageBuffer::from_raw(w, h, buf).map(DynamicImage::ImageRgba16)
+        }
+        _ => None,
+    };
+    img.with_context(|| {
+        format!(
+            "Unsupported color type {:?} in TIFF page {}",
+            color,
+            index + 1
+        )
+    })
+}
+
+/// Renders a color-managed thumbnail matching what the converted output will look like,
+/// plus the histogram of the full-size source before adjustments.
+pub fn render_preview(
+    input_path: &Path,
+    max_side: u32,
+    frame: FrameChoice,
+    frame_number: u32,
+    correction: ColorCorrection,
+    adjustments: Adjustments,
+    orient: bool,
+) -> Result<(image::RgbaImage, Histogram)> {
+    let (img, _) = load_source_image(input_path, frame, frame_number, false, correction, orient)?;
+    let histogram = compute_histogram(&img);
+    let thumbnail = apply_adjustments(img.thumbnail(max_side, max_side), adjustments).to_rgba8();
+    Ok((thumbnail, histogram))
+}
+
+/// Encodes `input_path` in memory at every multiple of `QUALITY_SWEEP_STEP` up to 100, measuring
+/// each output's size and its SSIM against a lossless PNG run through the same pipeline.
+pub fn quality_sweep(input_path: &Path, options: &ConversionOptions) -> Result<Vec<SweepPoint>> {
+    let options = ConversionOptions {
+        unified_quality: false,
+        webp_lossless: false,
+        auto_lossless: false,
+        verify_output: false,
+        verify_alpha: false,
+        ..options_for(input_path, options).into_owned()
     };
+    if options.format == ImageFormat::Png {
+        anyhow::bail!("PNG output is lossless, so quality does not change it");
+    }
+    let encode = |options: &ConversionOptions| -> Result<Vec<u8>> {
+        let mut buffer = Vec::new();
+        convert_image(input_path, options, 0, OutputTarget::Stream(&mut buffer))?;
+        Ok(buffer)
+    };
+    let reference = encode(&ConversionOptions {
+        format: ImageFormat::Png,
+        png_compressed: false,
+        keep_png_palette: false,
+        ..options.clone()
+    })?;
+    let reference = image::load_from_memory(&reference).context(DECODE_FAILED)?;
+    let reference = if options.flatten_alpha && !options.format.supports_alpha() {
+        flatten_onto_white(reference)
+    } else {
+        reference
+    };
+    let step = crate::constants::QUALITY_SWEEP_STEP;
+    (1..=100 / step)
+        .map(|i| {
+            let quality = i * step;
+            let buffer = encode(&ConversionOptions {
+                quality,
+                ..options.clone()
+            })?;
+            let ssim = image::load_from_memory(&buffer)
+                .ok()
+                .and_then(|output| ssim(&reference, &output));
+            Ok(SweepPoint {
+                quality,
+                size: buffer.len() as u64,
+                ssim,
+            })
+        })
+        .collect()
+}
+
+/// Mean structural similarity of two images' luma over 8x8 blocks; `None` when their sizes
+/// differ or they are smaller than one block.
+fn ssim(a: &DynamicImage, b: &DynamicImage) -> Option<f64> {
+    const BLOCK: u32 = 8;
+    const C1: f64 = (0.01 * 255.0) * (0.01 * 255.0);
+    const C2: f64 = (0.03 * 255.0) * (0.03 * 255.0);
+    if (a.width(), a.height()) != (b.width(), b.height()) {
+        return None;
+    }
+    let (a, b) = (a.to_luma8(), b.to_luma8());
+    let (width, height) = a.dimensions();
+    let n = (BLOCK * BLOCK) as f64;
+    let mut total = 0.0;
+    let mut blocks = 0;
+    for by in (0..height / BLOCK).map(|y| y * BLOCK) {
+        for bx in (0..width / BLOCK).map(|x| x * BLOCK) {
+            let (mut sa, mut sb, mut saa, mut sbb, mut sab) = (0.0, 0.0, 0.0, 0.0, 0.0);
+            for y in by..by + BLOCK {
+                for x in bx..bx + BLOCK {
+                    let pa = a.get_pixel(x, y).0[0] as f64;
+                    let pb = b.get_pixel(x, y).0[0] as f64;
+                    sa += pa;
+                    sb += pb;
+                    saa += pa * pa;
+                    sbb += pb * pb;
+                    sab += pa * pb;
+                }
+            }
+            let (ma, mb) = (sa / n, sb / n);
+            let (va, vb, cov) = (saa / n - ma * ma, sbb / n - mb * mb, sab / n - ma * mb);
+            total += ((2.0 * ma * mb + C1) * (2.0 * cov + C2))
+                / ((ma * ma + mb * mb + C1) * (va + vb + C2));
+            blocks += 1;
+        }
+    }
+    (blocks > 0).then(|| total / blocks as f64)
+}
+
+/// Counts how often each 8-bit red, green, and blue value occurs.
+fn compute_histogram(img: &DynamicImage) -> Histogram {
+    let mut channels = Box::new([[0u32; 256]; 3]);
+    for pixel in img.to_rgb8().pixels() {
+        for (counts, &value) in channels.iter_mut().zip(pixel.0.iter()) {
+            counts[value as usize] += 1;
+        }
+    }
+    Histogram { channels }
+}
+
+/// Applies brightness, contrast, and HSL saturation in that order, keeping the pixel type.
+fn apply_adjustments(img: DynamicImage, adjustments: Adjustments) -> DynamicImage {
+    if adjustments.is_neutral() {
+        return img;
+    }
+    let mut img = img;
+    if adjustments.brightness != 0 {
+        // brighten adds raw sample values, so scale the step to the channel depth.
+        let max = if is_sixteen_bit(&img) { 65535 } else { 255 };
+        img = img.brighten(adjustments.brightness * max / 100);
+    }
+    if adjustments.contrast != 0 {
+        img = img.adjust_contrast(adjustments.contrast as f32);
+    }
+    if adjustments.saturation == 0 && !adjustments.grayscale {
+        return img;
+    }
 
-    if let Some(icc) = extract_icc_profile(input_path) {
-        if img.color().has_alpha() {
-            img = DynamicImage::ImageRgba8(img.to_rgba8());
+    let factor = 1.0 + adjustments.saturation as f32 / 100.0;
+    let mut buffer = img.to_rgba32f();
+    for pixel in buffer.pixels_mut() {
+        let [r, g, b, _] = &mut pixel.0;
+        if adjustments.saturation != 0 {
+            let (h, s, l) = rgb_to_hsl(*r, *g, *b);
+            (*r, *g, *b) = hsl_to_rgb(h, (s * factor).clamp(0.0, 1.0), l);
+        }
+        if adjustments.grayscale {
+            let y = gray_level(*r, *g, *b, adjustments.linear_grayscale);
+            (*r, *g, *b) = (y, y, y);
+        }
+    }
+    let adjusted = DynamicImage::ImageRgba32F(buffer);
+    match (is_sixteen_bit(&img), img.color().has_alpha()) {
+        (false, false) => DynamicImage::ImageRgb8(adjusted.to_rgb8()),
+        (false, true) => DynamicImage::ImageRgba8(adjusted.to_rgba8()),
+        (true, false) => DynamicImage::ImageRgb16(adjusted.to_rgb16()),
+        (true, true) => DynamicImage::ImageRgba16(adjusted.to_rgba16()),
+    }
+}
+
+/// Gray level of a normalized sRGB pixel from Rec. 709 weights. `linear` weights in linear
+/// light and re-encodes, keeping saturated colors from turning too dark or too light.
+fn gray_level(r: f32, g: f32, b: f32, linear: bool) -> f32 {
+    let weigh = |r: f32, g: f32, b: f32| 0.2126 * r + 0.7152 * g + 0.0722 * b;
+    if !linear {
+        return weigh(r, g, b).clamp(0.0, 1.0);
+    }
+    let decode = |c: f32| {
+        if c <= 0.04045 {
+            c / 12.92
         } else {
-            img = DynamicImage::ImageRgb8(img.to_rgb8());
+            ((c + 0.055) / 1.055).powf(2.4)
         }
-        let _ = apply_color_correction(&mut img, &icc);
+    };
+    let y = weigh(decode(r), decode(g), decode(b)).clamp(0.0, 1.0);
+    if y <= 0.0031308 {
+        y * 12.92
+    } else {
+        1.055 * y.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Converts normalized RGB to hue (0-1), saturation, and lightness.
+fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let l = (max + min) / 2.0;
+    let d = max - min;
+    if d <= f32::EPSILON {
+        return (0.0, 0.0, l);
     }
+    let s = d / (1.0 - (2.0 * l - 1.0).abs());
+    let h = if max == r {
+        ((g - b) / d).rem_euclid(6.0)
+    } else if max == g {
+        (b - r) / d + 2.0
+    } else {
+        (r - g) / d + 4.0
+    };
+    (h / 6.0, s, l)
+}
 
-    let processed = if options.resize {
-        let (w, h) = (
-            options.target_width.parse().unwrap_or(0),
-            options.target_height.parse().unwrap_or(0),
+/// Converts hue (0-1), saturation, and lightness back to normalized RGB.
+fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h6 = h * 6.0;
+    let x = c * (1.0 - (h6.rem_euclid(2.0) - 1.0).abs());
+    let (r, g, b) = match h6 as u32 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    (r + m, g + m, b + m)
+}
+
+/// Size of the region `crop` keeps from a `width`×`height` image.
+pub fn cropped_dimensions(crop: Option<CropRect>, width: u32, height: u32) -> (u32, u32) {
+    match crop {
+        Some(crop) if width > 0 && height > 0 => {
+            let (_, _, w, h) = crop.pixels(width, height);
+            (w, h)
+        }
+        _ => (width, height),
+    }
+}
+
+/// Cuts the image down to the crop region when one is set.
+fn apply_crop(img: DynamicImage, crop: Option<CropRect>) -> DynamicImage {
+    match crop {
+        Some(crop) if img.width() > 0 && img.height() > 0 => {
+            let (x, y, w, h) = crop.pixels(img.width(), img.height());
+            img.crop_imm(x, y, w, h)
+        }
+        _ => img,
+    }
+}
+
+/// Crops solid black bars off the edges. A pixel is black when no channel exceeds `tolerance`;
+/// images that would lose more than `MAX_LETTERBOX_FRACTION` of a side are left whole.
+fn remove_letterbox(img: DynamicImage, tolerance: u8) -> DynamicImage {
+    let (w, h) = (img.width(), img.height());
+    if w == 0 || h == 0 {
+        return img;
+    }
+    let rgb = img.to_rgb8();
+    let dark = |x: u32, y: u32| rgb.get_pixel(x, y).0.iter().all(|&c| c <= tolerance);
+    let dark_row = |y: u32| (0..w).all(|x| dark(x, y));
+    let Some(top) = (0..h).find(|&y| !dark_row(y)) else {
+        return img;
+    };
+    let bottom = (top..h).rev().find(|&y| !dark_row(y)).map_or(h, |y| y + 1);
+    let dark_col = |x: u32| (top..bottom).all(|y| dark(x, y));
+    let left = (0..w).find(|&x| !dark_col(x)).unwrap_or(0);
+    let right = (left..w).rev().find(|&x| !dark_col(x)).map_or(w, |x| x + 1);
+    let (cw, ch) = (right - left, bottom - top);
+    if (cw, ch) == (w, h) {
+        return img;
+    }
+    let min_kept = 1.0 - crate::constants::MAX_LETTERBOX_FRACTION;
+    if (cw as f32) < w as f32 * min_kept || (ch as f32) < h as f32 * min_kept {
+        log::info!(
+            "Letterbox removal would crop {}x{} to {}x{}, keeping the whole image",
+            w,
+            h,
+            cw,
+            ch
         );
-        if w > 0 || h > 0 {
-            let (fw, fh) = (
-                if w == 0 { u32::MAX } else { w },
-                if h == 0 { u32::MAX } else { h },
-            );
-            resize_image_fast(&img, fw, fh)
-                .unwrap_or_else(|_| img.resize(fw, fh, FilterType::Lanczos3))
-        } else {
-            img
+        return img;
+    }
+    img.crop_imm(left, top, cw, ch)
+}
+
+/// Computes output dimensions, downscaled if needed to fit the output format's size limit.
+pub fn resized_dimensions(options: &ConversionOptions, src_w: u32, src_h: u32) -> (u32, u32) {
+    let (w, h) = requested_dimensions(options, src_w, src_h);
+    fit_format_limit(options.format, w, h)
+}
+
+/// Size the image itself is scaled to: the output size, or with Pad the largest size that
+/// fits inside it at the source aspect ratio.
+fn scaled_size(options: &ConversionOptions, src_w: u32, src_h: u32) -> (u32, u32) {
+    let (tw, th) = resized_dimensions(options, src_w, src_h);
+    if !pads(options) || src_w == 0 || src_h == 0 {
+        return (tw, th);
+    }
+    let scale = (tw as f64 / src_w as f64).min(th as f64 / src_h as f64);
+    (
+        ((src_w as f64 * scale).round() as u32).clamp(1, tw),
+        ((src_h as f64 * scale).round() as u32).clamp(1, th),
+    )
+}
+
+/// Pad only applies to an exact width and height, which would otherwise stretch the image.
+fn pads(options: &ConversionOptions) -> bool {
+    resize_mode_label(options) == Some("pad")
+}
+
+/// Pad color; an invalid entry falls back to black.
+fn pad_color(options: &ConversionOptions) -> [u8; 3] {
+    parse_hex_color(&options.pad_color).unwrap_or([0; 3])
+}
+
+/// Centers `img` on an opaque `width`x`height` canvas of `color`, keeping 16-bit depth.
+fn pad_to_canvas(img: DynamicImage, width: u32, height: u32, color: [u8; 3]) -> DynamicImage {
+    if (img.width(), img.height()) == (width, height) {
+        return img;
+    }
+    let x = width.saturating_sub(img.width()) as i64 / 2;
+    let y = height.saturating_sub(img.height()) as i64 / 2;
+    if is_sixteen_bit(&img) {
+        let [r, g, b] = color.map(|c| c as u16 * 257);
+        let mut canvas =
+            image::ImageBuffer::from_pixel(width, height, image::Rgba([r, g, b, u16::MAX]));
+        image::imageops::overlay(&mut canvas, &img.to_rgba16(), x, y);
+        DynamicImage::ImageRgba16(canvas)
+    } else {
+        let [r, g, b] = color;
+        let mut canvas = image::RgbaImage::from_pixel(width, height, image::Rgba([r, g, b, 255]));
+        image::imageops::overlay(&mut canvas, &img.to_rgba8(), x, y);
+        DynamicImage::ImageRgba8(canvas)
+    }
+}
+
+/// Computes the requested output dimensions: the explicit resize, then the megapixel budget,
+/// whichever is tighter.
+fn requested_dimensions(options: &ConversionOptions, src_w: u32, src_h: u32) -> (u32, u32) {
+    let (w, h) = explicit_dimensions(options, src_w, src_h);
+    fit_megapixels(options, w, h)
+}
+
+/// Dimensions from the resize fields; a blank width or height follows the source aspect ratio.
+fn explicit_dimensions(options: &ConversionOptions, src_w: u32, src_h: u32) -> (u32, u32) {
+    let w: u32 = options.target_width.parse().unwrap_or(0);
+    let h: u32 = options.target_height.parse().unwrap_or(0);
+    if !options.resize || src_w == 0 || src_h == 0 {
+        return (src_w, src_h);
+    }
+    if options.resize_mode == ResizeMode::FitWithin {
+        return fit_longest_side(options, src_w, src_h);
+    }
+    let scaled = |target: u32, num: u32, den: u32| {
+        ((target as f64 * num as f64 / den as f64).round() as u32).max(1)
+    };
+    match (w, h) {
+        (0, 0) => (src_w, src_h),
+        (w, 0) => (w, scaled(w, src_h, src_w)),
+        (0, h) => (scaled(h, src_w, src_h), h),
+        (w, h) if options.lock_aspect && !options.pad_to_fit => {
+            let scale = (w as f64 / src_w as f64).min(h as f64 / src_h as f64);
+            (
+                ((src_w as f64 * scale).round() as u32).clamp(1, w),
+                ((src_h as f64 * scale).round() as u32).clamp(1, h),
+            )
+        }
+        (w, h) => (w, h),
+    }
+}
+
+/// Scales dimensions down, keeping the aspect ratio, so the longest side is at most the limit.
+/// Images already within it, or a blank limit, keep their size.
+fn fit_longest_side(options: &ConversionOptions, src_w: u32, src_h: u32) -> (u32, u32) {
+    let limit: u32 = options.longest_side.parse().unwrap_or(0);
+    let longest = src_w.max(src_h);
+    if limit == 0 || longest <= limit {
+        return (src_w, src_h);
+    }
+    let scale = limit as f64 / longest as f64;
+    let fit = |side: u32| ((side as f64 * scale).round() as u32).clamp(1, limit);
+    (fit(src_w), fit(src_h))
+}
+
+/// Scales dimensions down, keeping the aspect ratio, to fit the megapixel budget if one is set.
+fn fit_megapixels(options: &ConversionOptions, w: u32, h: u32) -> (u32, u32) {
+    let Some(budget) = options
+        .max_megapixels
+        .parse::<f64>()
+        .ok()
+        .filter(|mp| *mp > 0.0)
+        .map(|mp| mp * 1_000_000.0)
+    else {
+        return (w, h);
+    };
+    let pixels = w as f64 * h as f64;
+    if pixels <= budget {
+        return (w, h);
+    }
+    // Round down so the result stays within the budget.
+    let scale = (budget / pixels).sqrt();
+    let fit = |side: u32| ((side as f64 * scale).floor() as u32).max(1);
+    (fit(w), fit(h))
+}
+
+/// Scales dimensions down, keeping the aspect ratio, so neither side exceeds the format limit.
+fn fit_format_limit(format: ImageFormat, w: u32, h: u32) -> (u32, u32) {
+    let max = format.max_dimension();
+    if w <= max && h <= max {
+        return (w, h);
+    }
+    let scale = max as f64 / w.max(h) as f64;
+    let fit = |side: u32| ((side as f64 * scale).round() as u32).clamp(1, max);
+    (fit(w), fit(h))
+}
+
+/// Rejects images that decoded or resized to zero pixels, which the encoders cannot handle.
+fn ensure_non_empty(width: u32, height: u32) -> Result<()> {
+    if width == 0 || height == 0 {
+        anyhow::bail!("Image has invalid dimensions {}x{}", width, height);
+    }
+    Ok(())
+}
+
+/// Shared archive a batch writes into when ZIP output is on; `None` once finished.
+pub type ZipSink = Mutex<Option<zip::ZipWriter<File>>>;
+
+/// Where a conversion's encoded image goes.
+pub enum OutputTarget<'a> {
+    /// Next to the input or into the output folder.
+    Folder,
+    /// An entry of the batch archive.
+    Zip(&'a ZipSink),
+    /// A raw stream such as stdout; no file name or caption is written.
+    Stream(&'a mut dyn Write),
+    /// Exactly this file, replacing any existing one; no name is built and no caption written.
+    Path(&'a Path),
+}
+
+/// Leading context of errors raised while reading the source, shown first in the file's status.
+const DECODE_FAILED: &str = "decode failed";
+/// Leading context of errors raised by the output encoder.
+const ENCODE_FAILED: &str = "encode failed";
+
+/// Main conversion function that orchestrates loading, processing, and encoding.
+/// Returns the output size in bytes and a short note when the encoder made an automatic choice.
+pub fn convert_image(
+    input_path: &Path,
+    options: &ConversionOptions,
+    index: usize,
+    target: OutputTarget,
+) -> Result<ConversionReport> {
+    let options = &*options_for(input_path, options);
+    let unknown = unknown_template_tokens(&options.filename_template);
+    if !unknown.is_empty() {
+        anyhow::bail!("Unknown filename token {{{}}}", unknown.join("}, {"));
+    }
+
+    let max_file_size = max_file_bytes(options);
+    let file_size = std::fs::metadata(input_path).map(|m| m.len()).unwrap_or(0);
+    if file_size > max_file_size {
+        anyhow::bail!("File too large (max {}MB)", options.max_file_size_mb);
+    }
+
+    if options.keep_animation && options.format == ImageFormat::WebP {
+        let source_format = detect_source_format(input_path)?;
+        if matches!(source_format, SourceFormat::Gif | SourceFormat::WebP)
+            && frame_count(input_path, source_format)? > 1
+        {
+            return convert_animation(input_path, source_format, options, index, target);
         }
+    }
+
+    // Only PNG can store more than 8 bits per channel.
+    let high_bit_depth = options.heic_high_bit_depth && options.format == ImageFormat::Png;
+    let source_format = detect_source_format(input_path).context(DECODE_FAILED)?;
+    // Partial downloads decode "successfully" with a gray bottom, which spoils datasets.
+    if source_format == SourceFormat::Jpeg && !jpeg_is_complete(&std::fs::read(input_path)?) {
+        anyhow::bail!(
+            "{}: truncated file, JPEG end marker (FF D9) is missing",
+            DECODE_FAILED
+        );
+    }
+    let (img, source_format) = match source_format {
+        // SVGs have no pixel size of their own, so render straight at the resize target.
+        SourceFormat::Svg => (
+            render_svg(input_path, |w, h| scaled_size(options, w, h)).context(DECODE_FAILED)?,
+            SourceFormat::Svg,
+        ),
+        // Corrected below so a failed transform can be reported on the file.
+        _ => load_source_image(
+            input_path,
+            options.frame_choice,
+            options.frame_number,
+            high_bit_depth,
+            ColorCorrection::Off,
+            !options.ignore_exif_orientation,
+        )
+        .context(DECODE_FAILED)?,
+    };
+    let (img, color_failed) = correct_to_srgb(
+        img,
+        extract_icc_profile(input_path).as_deref(),
+        options.color_correction,
+    );
+
+    let img = apply_crop(img, options.crop);
+    let (w, h) = (img.width(), img.height());
+    let img = if options.remove_letterbox {
+        remove_letterbox(img, options.letterbox_tolerance)
     } else {
         img
     };
+    let letterbox_removed = (img.width(), img.height()) != (w, h);
 
-    let parent = input_path.parent().unwrap_or(std::path::Path::new("."));
-    let out_parent = if options.use_custom_output {
-        options
-            .custom_output_path
-            .as_ref()
-            .filter(|p| p.exists())
-            .map(|p| p.as_path())
-            .unwrap_or(parent)
+    let has_exif_source = matches!(
+        source_format,
+        SourceFormat::Jpeg | SourceFormat::Png | SourceFormat::WebP
+    ) || source_format.uses_libheif();
+    let is_jpg_output = matches!(options.format, ImageFormat::Jpeg);
+    let mut metadata = if options.keep_metadata && has_exif_source {
+        extract_metadata(
+            input_path,
+            options.regenerate_exif_thumbnail,
+            options.keep_exif_orientation,
+        )
     } else {
-        parent
+        None
     };
-
-    let mut stem = input_path
-        .file_stem()
-        .unwrap_or_default()
-        .to_string_lossy()
-        .to_string();
-    if !options.find_pattern.is_empty() {
-        stem = stem.replace(&options.find_pattern, &options.replace_with);
+    let credits = exif_credits(options);
+    if is_jpg_output && !credits.is_empty() {
+        metadata
+            .get_or_insert(Metadata {
+                exif: None,
+                regenerate_thumbnail: false,
+                credits: Vec::new(),
+            })
+            .credits = credits;
     }
-    if options.auto_suffix {
-        stem.push_str(&get_smart_suffix(
-            processed.width(),
-            processed.height(),
-            options.quality,
+
+    let (tw, th) = resized_dimensions(options, img.width(), img.height());
+    let fitted_to_limit = (tw, th) != requested_dimensions(options, img.width(), img.height());
+    if fitted_to_limit {
+        log::warn!(
+            "{} exceeds the {} limit of {}px per side, downscaling to {}x{}",
+            input_path.display(),
             options.format,
+            options.format.max_dimension(),
+            tw,
+            th
+        );
+    }
+    let (sw, sh) = scaled_size(options, img.width(), img.height());
+    let processed = if (sw, sh) != (img.width(), img.height()) {
+        resize_image_fast(&img, sw, sh, options.draft_mode).unwrap_or_else(|e| {
+            log::warn!("SIMD resize failed ({}), using fallback resizer", e);
+            img.resize_exact(sw, sh, FilterType::Lanczos3)
+        })
+    } else {
+        img
+    };
+    let processed = if pads(options) {
+        pad_to_canvas(processed, tw, th, pad_color(options))
+    } else {
+        processed
+    };
+
+    ensure_non_empty(processed.width(), processed.height())?;
+
+    let processed = apply_adjustments(processed, options.adjustments);
+
+    let processed = if options.flatten_alpha && !options.format.supports_alpha() {
+        flatten_onto_white(processed)
+    } else {
+        processed
+    };
+
+    let processed = convert_to_output_space(processed, options.color_space)?;
+
+    let palette = (options.keep_png_palette
+        && options.format == ImageFormat::Png
+        && source_format == SourceFormat::Png
+        && !is_sixteen_bit(&processed))
+    .then(|| png_palette_size(input_path))
+    .flatten();
+
+    let mut note = None;
+    if options.draft_mode {
+        add_note(&mut note, "draft".to_string());
+    }
+    if color_failed {
+        add_note(&mut note, "color correction failed, untagged".to_string());
+    }
+    if letterbox_removed {
+        add_note(&mut note, format!("letterbox removed ({}x{})", w, h));
+    }
+    if fitted_to_limit {
+        add_note(
+            &mut note,
+            format!("downscaled to {}x{} ({} limit)", tw, th, options.format),
+        );
+    }
+    let webp_lossless = matches!(options.format, ImageFormat::WebP)
+        && (options.webp_lossless || (options.auto_lossless && looks_like_screenshot(&processed)));
+    if webp_lossless && !options.webp_lossless {
+        add_note(&mut note, "lossless (screenshot)".to_string());
+    }
+    if source_format.is_multi_frame() {
+        let count = frame_count(input_path, source_format)?;
+        if count > 1 {
+            let frame = resolve_frame(options.frame_choice, options.frame_number, count)?;
+            add_note(&mut note, format!("frame {} of {}", frame + 1, count));
+        }
+    }
+
+    // Only lossless outputs are expected to carry the resized alpha through unchanged.
+    let alpha_reference = (options.verify_alpha
+        && processed.color().has_alpha()
+        && (options.format == ImageFormat::Png || webp_lossless))
+        .then(|| alpha_channel(&processed));
+
+    let report = write_output(
+        input_path,
+        options,
+        index,
+        target,
+        (processed.width(), processed.height()),
+        note,
+        |writer| {
+            let encode = |mut out: &mut dyn Write| {
+                encode_output(
+                    &processed,
+                    options,
+                    metadata.as_ref(),
+                    webp_lossless,
+                    palette,
+                    !color_failed,
+                    &mut out,
+                )
+                .context(ENCODE_FAILED)
+            };
+            match &alpha_reference {
+                None => encode(writer),
+                Some(expected) => {
+                    let mut buffer = Vec::new();
+                    encode(&mut buffer)?;
+                    verify_alpha(expected, &buffer)?;
+                    writer.write_all(&buffer)?;
+                    Ok(())
+                }
+            }
+        },
+    )?;
+    Ok(ConversionReport {
+        quality: (options.format != ImageFormat::Png && !webp_lossless)
+            .then(|| encoder_quality(options)),
+        ..report
+    })
+}
+
+/// Re-encodes every frame of an animated GIF or WebP as an animated WebP.
+fn convert_animation(
+    input_path: &Path,
+    source_format: SourceFormat,
+    options: &ConversionOptions,
+    index: usize,
+    target: OutputTarget,
+) -> Result<ConversionReport> {
+    use image::AnimationDecoder;
+
+    // Both decoders composite each frame onto the full canvas.
+    let reader = BufReader::new(File::open(input_path)?);
+    let frames = if source_format == SourceFormat::Gif {
+        image::codecs::gif::GifDecoder::new(reader).and_then(|d| d.into_frames().collect_frames())
+    } else {
+        image::codecs::webp::WebPDecoder::new(reader).and_then(|d| d.into_frames().collect_frames())
+    }
+    .context(DECODE_FAILED)?;
+    let (w, h) = frames
+        .first()
+        .context("Animation has no frames")?
+        .buffer()
+        .dimensions();
+    let (w, h) = cropped_dimensions(options.crop, w, h);
+    let (tw, th) = resized_dimensions(options, w, h);
+    ensure_non_empty(tw, th)?;
+    let source_icc = extract_icc_profile(input_path);
+    let mut color_failed = false;
+
+    let mut elapsed_ms = 0.0_f64;
+    let mut rendered = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let (numer, denom) = frame.delay().numer_denom_ms();
+        let (mut img, failed) = correct_to_srgb(
+            apply_crop(DynamicImage::ImageRgba8(frame.into_buffer()), options.crop),
+            source_icc.as_deref(),
+            options.color_correction,
+        );
+        color_failed |= failed;
+        let (sw, sh) = scaled_size(options, w, h);
+        if (sw, sh) != (w, h) {
+            img = resize_image_fast(&img, sw, sh, options.draft_mode)
+                .unwrap_or_else(|_| img.resize_exact(sw, sh, FilterType::Lanczos3));
+        }
+        if pads(options) {
+            img = pad_to_canvas(img, tw, th, pad_color(options));
+        }
+        let img = apply_adjustments(img, options.adjustments);
+        let img = convert_to_output_space(img, options.color_space)?;
+        rendered.push((img.to_rgba8(), elapsed_ms.round() as i32));
+        elapsed_ms += numer as f64 / denom.max(1) as f64;
+    }
+
+    let mut config =
+        webp::WebPConfig::new().map_err(|_| anyhow::anyhow!("WebP encoder unavailable"))?;
+    config.lossless = i32::from(options.webp_lossless);
+    config.quality = encoder_quality(options) as f32;
+    let mut encoder = webp::AnimEncoder::new(tw, th, &config);
+    for (buffer, timestamp) in &rendered {
+        encoder.add_frame(webp::AnimFrame::from_rgba(
+            buffer.as_raw(),
+            tw,
+            th,
+            *timestamp,
         ));
     }
+    let webp_data = encoder
+        .try_encode()
+        .map_err(|e| anyhow::anyhow!("{}: animated WebP: {:?}", ENCODE_FAILED, e))?;
+
+    let mut note = Some(format!("animated, {} frames", rendered.len()));
+    let icc = if color_failed {
+        add_note(&mut note, "color correction failed, untagged".to_string());
+        Cow::Borrowed(&[][..])
+    } else {
+        output_icc(options)
+    };
+    let report = write_output(
+        input_path,
+        options,
+        index,
+        target,
+        (tw, th),
+        note,
+        |mut writer| write_webp(&webp_data, &icc, None, &mut writer).context(ENCODE_FAILED),
+    )?;
+    Ok(ConversionReport {
+        quality: (!options.webp_lossless).then(|| encoder_quality(options)),
+        ..report
+    })
+}
+
+/// Appends a comma-separated remark to a file's note.
+fn add_note(note: &mut Option<String>, remark: String) {
+    *note = Some(match note.take() {
+        Some(existing) => format!("{}, {}", existing, remark),
+        None => remark,
+    });
+}
+
+/// What a successful conversion wrote, kept on the file for display and summaries.
+#[derive(Debug, Clone, Default)]
+pub struct ConversionReport {
+    pub size: u64,
+    /// Automatic encoder decisions and other remarks shown next to the file.
+    pub note: Option<String>,
+    /// Pixel size of the written image; zero for copied sidecars.
+    pub dimensions: (u32, u32),
+    /// Encoder quality used for lossy output; `None` for PNG, lossless WebP, and copies.
+    pub quality: Option<u8>,
+    /// The output already existed and was left alone.
+    pub skipped: bool,
+    /// Where the output was written; `None` inside a ZIP or on a stream.
+    pub path: Option<PathBuf>,
+    /// Where the extra formats' outputs were written.
+    pub extra_paths: Vec<PathBuf>,
+    /// A sidecar copied unchanged; its source is never deleted.
+    pub sidecar: bool,
+    /// Every written output was decoded back and checked.
+    pub verified: bool,
+}
+
+/// Note of a file left alone because its output already existed.
+pub const SKIPPED_NOTE: &str = "skipped, output exists";
+/// Note on a sidecar file copied next to the outputs instead of being converted.
+const SIDECAR_NOTE: &str = "copied unconverted";
+
+/// Encodes into memory, verifying the result when `verify` is set.
+fn encode_to_buffer(
+    verify: bool,
+    (width, height): (u32, u32),
+    encode: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<Vec<u8>> {
+    let mut buffer = Vec::new();
+    encode(&mut buffer)?;
+    if verify {
+        verify_output(image::load_from_memory(&buffer), width, height)?;
+    }
+    Ok(buffer)
+}
+
+/// Names the output after its encoded size and writes it to `target`,
+/// applying the existing-file policy and verification.
+fn write_output(
+    input_path: &Path,
+    options: &ConversionOptions,
+    index: usize,
+    target: OutputTarget,
+    (width, height): (u32, u32),
+    mut note: Option<String>,
+    encode: impl FnOnce(&mut dyn Write) -> Result<()>,
+) -> Result<ConversionReport> {
+    let zip = match target {
+        OutputTarget::Folder => None,
+        OutputTarget::Zip(zip) => Some(zip),
+        OutputTarget::Stream(out) => {
+            let buffer = encode_to_buffer(options.verify_output, (width, height), encode)?;
+            out.write_all(&buffer)?;
+            out.flush()?;
+            return Ok(ConversionReport {
+                size: buffer.len() as u64,
+                note,
+                dimensions: (width, height),
+                verified: options.verify_output,
+                ..Default::default()
+            });
+        }
+        OutputTarget::Path(output_path) => {
+            let buffer = encode_to_buffer(options.verify_output, (width, height), encode)?;
+            if let Some(dir) = output_path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                ensure_output_dir(dir)?;
+            }
+            let temp_path = temp_sibling(output_path);
+            if let Err(e) = std::fs::write(&temp_path, &buffer)
+                .and_then(|()| std::fs::rename(&temp_path, output_path))
+            {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(e).with_context(|| format!("Cannot write {}", output_path.display()));
+            }
+            return Ok(ConversionReport {
+                size: buffer.len() as u64,
+                note,
+                dimensions: (width, height),
+                path: Some(output_path.to_path_buf()),
+                verified: options.verify_output,
+                ..Default::default()
+            });
+        }
+    };
+
+    let file_name = build_filename(input_path, options, width, height, index);
+    let caption = options
+        .write_captions
+        .then(|| caption_text(input_path, options, width, height, index));
+
+    if let Some(zip) = zip {
+        let buffer = encode_to_buffer(options.verify_output, (width, height), encode)?;
+        let mut zip = zip
+            .lock()
+            .map_err(|_| anyhow::anyhow!("ZIP archive is unavailable"))?;
+        let archive = zip
+            .as_mut()
+            .ok_or_else(|| anyhow::anyhow!("ZIP archive is already closed"))?;
+        // Encoded images are already compressed, so deflating them again only costs time.
+        let entry_options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let in_folder = |name: String| match format_subfolder(input_path, options) {
+            Some(folder) => format!("{}/{}", folder, name),
+            None => name,
+        };
+        let mut entry_name = in_folder(file_name.clone());
+        let mut copy = 0;
+        loop {
+            match archive.start_file(entry_name.as_str(), entry_options) {
+                // The duplicate check runs before anything is written, so retrying is safe.
+                Err(zip::result::ZipError::InvalidArchive("Duplicate filename"))
+                    if options.existing_file_policy == ExistingFilePolicy::Rename =>
+                {
+                    copy += 1;
+                    entry_name = in_folder(numbered_name(&file_name, copy));
+                }
+                result => break result?,
+            }
+        }
+        if copy > 0 {
+            add_note(&mut note, format!("saved as {}", entry_name));
+        }
+        archive.write_all(&buffer)?;
+        if let Some(caption) = caption {
+            let caption_name = Path::new(&entry_name).with_extension("txt");
+            archive.start_file(
+                caption_name.to_string_lossy(),
+                zip::write::SimpleFileOptions::default(),
+            )?;
+            archive.write_all(caption.as_bytes())?;
+        }
+        return Ok(ConversionReport {
+            size: buffer.len() as u64,
+            note,
+            dimensions: (width, height),
+            verified: options.verify_output,
+            ..Default::default()
+        });
+    }
+
+    let output_dir = get_output_dir(input_path, options);
+    ensure_output_dir(&output_dir)?;
+    let mut output_path = output_dir.join(&file_name);
+    let skipped = |path: &Path| -> Result<ConversionReport> {
+        Ok(ConversionReport {
+            size: std::fs::metadata(path)?.len(),
+            note: Some(SKIPPED_NOTE.to_string()),
+            dimensions: (width, height),
+            skipped: true,
+            path: Some(path.to_path_buf()),
+            ..Default::default()
+        })
+    };
+    if options.existing_file_policy == ExistingFilePolicy::Skip && output_path.exists() {
+        return skipped(&output_path);
+    }
+
+    // Encode into a hidden sibling and rename it into place only once it is complete, so a
+    // crash or cancel never leaves a truncated image under the output name.
+    let temp_path = temp_sibling(&output_path);
+    let write_result = (|| -> Result<()> {
+        let mut writer = BufWriter::new(File::create(&temp_path)?);
+        encode(&mut writer)?;
+        writer.flush()?;
+        writer.get_ref().sync_all()?;
+        Ok(())
+    })()
+    .and_then(|()| {
+        if options.verify_output {
+            verify_output(decode_file(&temp_path), width, height)?;
+        }
+        Ok(())
+    });
+
+    if let Err(e) = write_result {
+        let _ = std::fs::remove_file(&temp_path);
+        if is_disk_full(&e) {
+            anyhow::bail!("Disk full: not enough space to write output");
+        }
+        return Err(e);
+    }
 
-    let ext_out = match options.format {
-        ImageFormat::Jpeg => "jpg",
-        ImageFormat::Png => "png",
-        ImageFormat::WebP => "webp",
+    // Skip and Rename claim their name atomically, so two inputs of the same batch that map
+    // to one output cannot both write it; the claimed empty file is then replaced at once.
+    let claimed = match options.existing_file_policy {
+        ExistingFilePolicy::Overwrite => Ok(()),
+        ExistingFilePolicy::Skip => match create_new(&output_path) {
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                let _ = std::fs::remove_file(&temp_path);
+                return skipped(&output_path);
+            }
+            file => file.map(drop),
+        },
+        ExistingFilePolicy::Rename => {
+            let mut copy = 0;
+            let claimed = loop {
+                match create_new(&output_path) {
+                    Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                        copy += 1;
+                        output_path.set_file_name(numbered_name(&file_name, copy));
+                    }
+                    file => break file.map(drop),
+                }
+            };
+            if copy > 0 {
+                add_note(
+                    &mut note,
+                    format!("saved as {}", numbered_name(&file_name, copy)),
+                );
+            }
+            claimed
+        }
     };
-    let output_path = out_parent.join(format!("{}{}.{}", options.prefix, stem, ext_out));
+    if let Err(e) = claimed {
+        let _ = std::fs::remove_file(&temp_path);
+        return Err(e).with_context(|| format!("Cannot write {}", output_path.display()));
+    }
+    if let Err(e) = std::fs::rename(&temp_path, &output_path) {
+        let _ = std::fs::remove_file(&temp_path);
+        // Only an empty name claimed above is ours to remove; Overwrite keeps the old file.
+        if options.existing_file_policy != ExistingFilePolicy::Overwrite {
+            let _ = std::fs::remove_file(&output_path);
+        }
+        return Err(e).with_context(|| format!("Cannot write {}", output_path.display()));
+    }
+    if let Err(e) = apply_output_timestamp(input_path, &output_path, options.output_timestamp) {
+        log::warn!("{}: cannot set file date: {:#}", output_path.display(), e);
+    }
+
+    if let Some(caption) = caption {
+        let caption_path = output_path.with_extension("txt");
+        std::fs::write(&caption_path, caption)
+            .with_context(|| format!("Cannot write caption {}", caption_path.display()))?;
+    }
+    Ok(ConversionReport {
+        size: std::fs::metadata(&output_path)?.len(),
+        note,
+        dimensions: (width, height),
+        path: Some(output_path),
+        verified: options.verify_output,
+        ..Default::default()
+    })
+}
 
-    let mut writer = BufWriter::new(File::create(&output_path)?);
+/// Sidecar caption for dataset tools: the caption template with the filename tokens plus
+/// `{original}`, the input file name.
+fn caption_text(
+    input_path: &Path,
+    options: &ConversionOptions,
+    width: u32,
+    height: u32,
+    index: usize,
+) -> String {
+    let original = input_path.file_name().unwrap_or_default().to_string_lossy();
+    expand_template(
+        &options.caption_template,
+        input_path,
+        options,
+        width,
+        height,
+        index,
+        &[("original", &original)],
+    )
+}
 
+/// Hidden, uniquely named file next to `path` that output is encoded into before the rename.
+fn temp_sibling(path: &Path) -> PathBuf {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path.with_file_name(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4().simple()))
+}
+
+/// Decodes an image file by its content, since temporary names carry no usable extension.
+fn decode_file(path: &Path) -> image::ImageResult<DynamicImage> {
+    image::io::Reader::open(path)?
+        .with_guessed_format()?
+        .decode()
+}
+
+/// Creates `path` only if nothing exists there yet.
+fn create_new(path: &Path) -> std::io::Result<File> {
+    File::options().write(true).create_new(true).open(path)
+}
+
+/// `name (n).ext` for the n-th copy of `file_name`.
+fn numbered_name(file_name: &str, n: usize) -> String {
+    let path = Path::new(file_name);
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    match path.extension() {
+        Some(extension) => format!("{} ({}).{}", stem, n, extension.to_string_lossy()),
+        None => format!("{} ({})", stem, n),
+    }
+}
+
+/// Encoder quality for lossy output; unified mode maps the 0–100 perceptual slider per format.
+/// The anchors roughly line up visual quality: perceptual 75 gives JPEG 78 and WebP 77.
+/// AVIF and HEIC are decode-only here, so they have no mapping.
+pub fn encoder_quality(options: &ConversionOptions) -> u8 {
+    if !options.unified_quality {
+        return options.quality;
+    }
+    let p = options.perceptual_quality.min(100) as u32;
+    let q = match options.format {
+        ImageFormat::Jpeg => 30 + p * 65 / 100,
+        ImageFormat::WebP => 25 + p * 70 / 100,
+        ImageFormat::Png => return options.quality,
+    };
+    q as u8
+}
+
+/// Parses a max file size entry in megabytes, clamping values above the limit.
+pub fn parse_max_file_size(value: &str) -> Option<u64> {
+    let mb = value.trim().parse::<u64>().ok().filter(|&mb| mb >= 1)?;
+    Some(mb.min(crate::constants::MAX_FILE_SIZE_LIMIT_MB))
+}
+
+/// Input size limit in bytes; saturates rather than overflowing for huge settings.
+pub fn max_file_bytes(options: &ConversionOptions) -> u64 {
+    options.max_file_size_mb.saturating_mul(1024 * 1024)
+}
+
+/// oxipng preset for PNG output: fastest for drafts, scaled from the slider in unified mode.
+pub fn png_effort(options: &ConversionOptions) -> u8 {
+    if options.draft_mode {
+        0
+    } else if options.unified_quality {
+        (options.perceptual_quality.min(100) as u32 * 6 / 100) as u8
+    } else {
+        6
+    }
+}
+
+/// Encodes the processed image in the selected output format.
+/// Without `tagged` no color profile is embedded, since the pixels' space is unknown.
+fn encode_output(
+    img: &DynamicImage,
+    options: &ConversionOptions,
+    metadata: Option<&Metadata>,
+    webp_lossless: bool,
+    palette: Option<usize>,
+    tagged: bool,
+    writer: &mut impl Write,
+) -> Result<()> {
+    let icc = if tagged {
+        output_icc(options)
+    } else {
+        Cow::Borrowed(&[][..])
+    };
     match options.format {
-        ImageFormat::Jpeg => {
-            encode_jpeg(&processed, options.quality, metadata.as_ref(), &mut writer)?
+        ImageFormat::Jpeg => encode_jpeg(
+            img,
+            encoder_quality(options),
+            options.dpi,
+            metadata,
+            &icc,
+            options.draft_mode,
+            writer,
+        ),
+        ImageFormat::Png => {
+            let png_icc =
+                (!tagged || options.color_space != OutputColorSpace::Srgb).then_some(&*icc);
+            let effort = options.png_compressed.then(|| png_effort(options));
+            let tag = options.png_color_tag;
+            match metadata.and_then(|meta| exif_payload(meta, img)) {
+                Some(payload) => {
+                    let mut buffer = Vec::new();
+                    encode_png(img, effort, options.dpi, png_icc, tag, palette, &mut buffer)?;
+                    write_png_exif(&buffer, payload, writer)
+                }
+                None => encode_png(img, effort, options.dpi, png_icc, tag, palette, writer),
+            }
         }
-        ImageFormat::Png => encode_png(&processed, options.png_compressed, &mut writer)?,
-        ImageFormat::WebP => encode_webp(&processed, options.quality, &mut writer)?,
+        ImageFormat::WebP => encode_webp(
+            img,
+            encoder_quality(options),
+            webp_lossless,
+            &icc,
+            metadata,
+            writer,
+        ),
+    }
+}
+
+/// Creates the archive a ZIP batch writes into.
+pub fn create_zip_sink(path: &Path) -> Result<ZipSink> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        ensure_output_dir(dir)?;
+    }
+    let file = File::create(path)
+        .with_context(|| format!("Cannot create ZIP archive {}", path.display()))?;
+    Ok(Mutex::new(Some(zip::ZipWriter::new(file))))
+}
+
+/// Writes the archive's central directory; entries added afterwards are rejected.
+pub fn finish_zip_sink(sink: &ZipSink) -> Result<()> {
+    let archive = sink
+        .lock()
+        .map_err(|_| anyhow::anyhow!("ZIP archive is unavailable"))?
+        .take();
+    if let Some(archive) = archive {
+        archive.finish()?;
     }
     Ok(())
 }
 
-/// Generates resolution and quality suffix for filenames.
-fn get_smart_suffix(width: u32, height: u32, quality: u8, format: ImageFormat) -> String {
-    let short_side = width.min(height);
-    if matches!(format, ImageFormat::Png) {
-        format!("-{}p", short_side)
+/// ZIP archive path for a batch; defaults to `converted.zip` in the first file's output folder.
+pub fn zip_output_path(options: &ConversionOptions, first_input: &Path) -> PathBuf {
+    options
+        .zip_path
+        .clone()
+        .unwrap_or_else(|| output_root_dir(first_input, options).join("converted.zip"))
+}
+
+/// Heuristic for UI captures: few distinct colors and mostly flat runs between neighbors.
+fn looks_like_screenshot(img: &DynamicImage) -> bool {
+    const MAX_COLORS: usize = 4096;
+    const SAMPLE_ROWS: u32 = 256;
+
+    let rgb = img.to_rgb8();
+    let (width, height) = rgb.dimensions();
+    if width < 2 {
+        return false;
+    }
+    let row_step = (height / SAMPLE_ROWS).max(1);
+    let mut colors = std::collections::HashSet::new();
+    let (mut flat, mut pairs) = (0u64, 0u64);
+    for y in (0..height).step_by(row_step as usize) {
+        for x in 1..width {
+            let (prev, cur) = (rgb.get_pixel(x - 1, y), rgb.get_pixel(x, y));
+            pairs += 1;
+            if prev == cur {
+                flat += 1;
+            }
+            if colors.len() <= MAX_COLORS {
+                colors.insert(cur.0);
+            }
+        }
+    }
+    colors.len() <= MAX_COLORS && flat * 2 > pairs
+}
+
+/// Returns true if any error in the chain is an out-of-space I/O error.
+fn is_disk_full(err: &anyhow::Error) -> bool {
+    // ENOSPC on Unix, ERROR_HANDLE_DISK_FULL / ERROR_DISK_FULL on Windows.
+    let codes: &[i32] = if cfg!(windows) { &[39, 112] } else { &[28] };
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<std::io::Error>()
+            .and_then(|io| io.raw_os_error())
+            .is_some_and(|code| codes.contains(&code))
+    })
+}
+
+/// Resolves the directory an input file's output will be written to.
+pub fn get_output_dir(input_path: &Path, options: &ConversionOptions) -> PathBuf {
+    let root = output_root_dir(input_path, options);
+    match format_subfolder(input_path, options) {
+        Some(folder) => root.join(folder),
+        None => root,
+    }
+}
+
+/// Format-named folder (`jpg`, `png`, `webp`) outputs are sorted into, when enabled. It sits
+/// directly under the output folder, and inside a ZIP it prefixes the entry name.
+pub fn format_subfolder(input_path: &Path, options: &ConversionOptions) -> Option<&'static str> {
+    options
+        .format_subfolders
+        .then(|| output_format(input_path, options).extension())
+}
+
+/// Folder outputs of `input_path` go to, before any per-format subfolder.
+pub fn output_root_dir(input_path: &Path, options: &ConversionOptions) -> PathBuf {
+    let parent = if crate::remote::is_downloaded(input_path) {
+        // Downloaded images live in a temp folder; default their output to Downloads.
+        dirs::download_dir().unwrap_or_else(|| PathBuf::from("."))
+    } else {
+        input_path.parent().unwrap_or(Path::new(".")).to_path_buf()
+    };
+    if options.use_custom_output {
+        // Missing folders are created and files rejected when the output is written.
+        options.custom_output_path.clone().unwrap_or(parent)
     } else {
-        format!("-{}p-{}q", short_side, quality)
+        parent
+    }
+}
+
+/// Creates the output folder if missing; fails clearly when the path names a file.
+pub fn ensure_output_dir(dir: &Path) -> Result<()> {
+    if dir.is_file() {
+        anyhow::bail!("Output folder {} is a file, not a folder", dir.display());
     }
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Cannot create output folder {}", dir.display()))
 }
+
+/// Roughly estimates encoded output size from source dimensions and target format.
+pub fn estimate_output_size(input_path: &Path, options: &ConversionOptions) -> u64 {
+    let options = &*options_for(input_path, options);
+    let file_size = std::fs::metadata(input_path).map(|m| m.len()).unwrap_or(0);
+    let Ok((w, h)) = image_dimensions(input_path) else {
+        return file_size;
+    };
+    let (w, h) = cropped_dimensions(options.crop, w, h);
+    let (w, h) = resized_dimensions(options, w, h);
+    let bytes_per_pixel = match options.format {
+        ImageFormat::Png => 1.8,
+        ImageFormat::Jpeg | ImageFormat::WebP => 0.6 * encoder_quality(options) as f64 / 100.0,
+    };
+    (w as f64 * h as f64 * bytes_per_pixel) as u64
+}
+
+/// Re-decodes written output to confirm it is a valid image of expected dimensions.
+fn verify_output(decoded: image::ImageResult<DynamicImage>, width: u32, height: u32) -> Result<()> {
+    let decoded = decoded.context("Verification failed: output unreadable")?;
+    if decoded.width() != width || decoded.height() != height {
+        anyhow::bail!(
+            "Verification failed: expected {}x{}, got {}x{}",
+            width,
+            height,
+            decoded.width(),
+            decoded.height()
+        );
+    }
+    Ok(())
+}
+
+/// Extracts the alpha channel as one byte per pixel.
+fn alpha_channel(img: &DynamicImage) -> Vec<u8> {
+    img.to_rgba8().pixels().map(|p| p[3]).collect()
+}
+
+/// Re-decodes an encoded lossless output and confirms its alpha matches `expected`.
+fn verify_alpha(expected: &[u8], encoded: &[u8]) -> Result<()> {
+    let decoded =
+        image::load_from_memory(encoded).context("Alpha check failed: output unreadable")?;
+    let actual = alpha_channel(&decoded);
+    if actual.len() != expected.len() {
+        anyhow::bail!("Alpha check failed: output has a different pixel count");
+    }
+    let (changed, largest) = expected
+        .iter()
+        .zip(&actual)
+        .map(|(a, b)| a.abs_diff(*b))
+        .filter(|&d| d > 0)
+        .fold((0usize, 0u8), |(n, max), d| (n + 1, max.max(d)));
+    if changed > 0 {
+        anyhow::bail!(
+            "Alpha check failed: {} of {} pixels changed (largest difference {})",
+            changed,
+            expected.len(),
+            largest
+        );
+    }
+    Ok(())
+}
+
+/// Generates the resolution, quality, and optional resize mode suffix from the encoded size.
+fn get_smart_suffix(width: u32, height: u32, options: &ConversionOptions) -> String {
+    let mut suffix = format!("-{}{}", width.min(height), options.suffix_size_letter);
+    if !matches!(options.format, ImageFormat::Png) {
+        suffix.push_str(&format!(
+            "-{}{}",
+            encoder_quality(options),
+            options.suffix_quality_letter
+        ));
+    }
+    if options.suffix_mode {
+        if let Some(mode) = resize_mode_label(options) {
+            suffix.push('-');
+            suffix.push_str(mode);
+        }
+    }
+    suffix
+}
+
+/// Short name of the active resize mode, `None` when images keep their size.
+fn resize_mode_label(options: &ConversionOptions) -> Option<&'static str> {
+    if !options.resize {
+        return None;
+    }
+    if options.resize_mode == ResizeMode::FitWithin {
+        return options
+            .longest_side
+            .parse::<u32>()
+            .is_ok_and(|side| side > 0)
+            .then_some("fit");
+    }
+    let w: u32 = options.target_width.parse().unwrap_or(0);
+    let h: u32 = options.target_height.parse().unwrap_or(0);
+    match (w, h) {
+        (0, 0) => None,
+        (_, 0) | (0, _) => Some("fit"),
+        _ if options.pad_to_fit => Some("pad"),
+        _ if options.lock_aspect => Some("fit"),
+        _ => Some("exact"),
+    }
+}
+
+/// One timed permutation reported by `run_benchmark`.
+pub struct BenchmarkResult {
+    pub stage: &'static str,
+    pub setting: String,
+    pub millis: f64,
+    pub bytes: Option<u64>,
+}
+
+/// Times each resize filter (to half size) and encoder setting on one image.
+pub fn run_benchmark(input_path: &Path) -> Result<Vec<BenchmarkResult>> {
+    use fast_image_resize::FilterType as Fr;
+    use std::time::Instant;
+
+    let (img, _) = load_source_image(
+        input_path,
+        FrameChoice::First,
+        1,
+        false,
+        ColorCorrection::default(),
+        true,
+    )?;
+    let (w, h) = ((img.width() / 2).max(1), (img.height() / 2).max(1));
+    let mut results = Vec::new();
+
+    for (name, filter) in [
+        ("CatmullRom (SIMD)", Fr::CatmullRom),
+        ("Lanczos3 (SIMD)", Fr::Lanczos3),
+        ("Mitchell (SIMD)", Fr::Mitchell),
+        ("Bilinear (SIMD)", Fr::Bilinear),
+    ] {
+        let start = Instant::now();
+        resize_with_filter(&img, w, h, filter)?;
+        results.push(BenchmarkResult {
+            stage: "resize",
+            setting: name.to_string(),
+            millis: start.elapsed().as_secs_f64() * 1000.0,
+            bytes: None,
+        });
+    }
+    let start = Instant::now();
+    img.resize_exact(w, h, FilterType::Lanczos3);
+    results.push(BenchmarkResult {
+        stage: "resize",
+        setting: "Lanczos3 (image fallback)".to_string(),
+        millis: start.elapsed().as_secs_f64() * 1000.0,
+        bytes: None,
+    });
+
+    /// Scratch file for encoder output, removed however the benchmark ends.
+    struct Scratch(PathBuf);
+    impl Drop for Scratch {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.0);
+        }
+    }
+    let guard = Scratch(std::env::temp_dir().join(format!(
+        "sic-benchmark-{}-{}",
+        std::process::id(),
+        uuid::Uuid::new_v4()
+    )));
+    let scratch = &guard.0;
+    let mut encode = |setting: String, f: &dyn Fn(&mut BufWriter<File>) -> Result<()>| {
+        let start = Instant::now();
+        let mut writer = BufWriter::new(File::create(scratch)?);
+        f(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        let millis = start.elapsed().as_secs_f64() * 1000.0;
+        let bytes = std::fs::metadata(scratch)?.len();
+        results.push(BenchmarkResult {
+            stage: "encode",
+            setting,
+            millis,
+            bytes: Some(bytes),
+        });
+        Ok::<_, anyhow::Error>(())
+    };
+    for q in [70, 82, 92] {
+        encode(format!("JPEG q{}", q), &|w| {
+            encode_jpeg(&img, q, 0, None, SRGB_ICC, false, w)
+        })?;
+    }
+    for q in [65, 78, 90] {
+        encode(format!("WebP q{}", q), &|w| {
+            encode_webp(&img, q, false, SRGB_ICC, None, w)
+        })?;
+    }
+    encode("WebP lossless".to_string(), &|w| {
+        encode_webp(&img, 100, true, SRGB_ICC, None, w)
+    })?;
+    for optimized in [false, true] {
+        let setting = if optimized {
+            "PNG optimized"
+        } else {
+            "PNG default"
+        };
+        encode(setting.to_string(), &|w| {
+            encode_png(
+                &img,
+                optimized.then_some(6),
+                0,
+                None,
+                PngColorTag::Srgb,
+                None,
+                w,
+            )
+        })?;
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests;
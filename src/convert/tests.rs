@@ -0,0 +1,309 @@
+//! Invariant checks for the conversion pipeline. Fixtures are generated in code and every
+//! output is encoded into memory through `OutputTarget::Stream`, so no golden files are needed.
+
+use super::*;
+use crate::constants::{EXIF_HEADER, EXIF_ORIENTATION_TAG};
+use img_parts::jpeg::Jpeg;
+
+/// Scratch folder holding one test's fixtures, removed when dropped.
+struct Fixtures(PathBuf);
+
+impl Fixtures {
+    fn new() -> Self {
+        let dir = std::env::temp_dir()
+            .join("SimpleImageConverterTests")
+            .join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        Fixtures(dir)
+    }
+
+    fn write(&self, name: &str, bytes: &[u8]) -> PathBuf {
+        let path = self.0.join(name);
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    /// Opaque 40x20 gradient, wider than tall so any rotation shows in the dimensions.
+    fn jpeg(&self, name: &str, orientation: Option<u16>) -> PathBuf {
+        let img = image::RgbImage::from_fn(40, 20, |x, y| {
+            image::Rgb([(x * 6) as u8, (y * 12) as u8, 128])
+        });
+        let mut bytes = Vec::new();
+        image::codecs::jpeg::JpegEncoder::new_with_quality(&mut bytes, 95)
+            .encode_image(&img)
+            .unwrap();
+        if let Some(orientation) = orientation {
+            let mut jpeg = Jpeg::from_bytes(bytes.into()).unwrap();
+            jpeg.segments_mut().insert(
+                0,
+                JpegSegment::new_with_contents(0xE1, orientation_exif(orientation).into()),
+            );
+            bytes = Vec::new();
+            jpeg.encoder().write_to(&mut bytes).unwrap();
+        }
+        self.write(name, &bytes)
+    }
+
+    /// 32x32 PNG whose left half is fully transparent.
+    fn transparent_png(&self, name: &str) -> PathBuf {
+        let img = image::RgbaImage::from_fn(32, 32, |x, _| {
+            image::Rgba([200, 40, 40, if x < 16 { 0 } else { 255 }])
+        });
+        let mut bytes = Vec::new();
+        DynamicImage::ImageRgba8(img)
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageOutputFormat::Png,
+            )
+            .unwrap();
+        self.write(name, &bytes)
+    }
+
+    /// 8x8 PNG of one color, tagged with the given ICC profile.
+    fn tagged_png(&self, name: &str, color: [u8; 3], icc: &[u8]) -> PathBuf {
+        let mut bytes = Vec::new();
+        {
+            let mut info = png::Info::with_size(8, 8);
+            info.icc_profile = Some(icc.to_vec().into());
+            let mut enc = png::Encoder::with_info(&mut bytes, info).unwrap();
+            enc.set_color(png::ColorType::Rgb);
+            enc.set_depth(png::BitDepth::Eight);
+            let pixels: Vec<u8> = color.repeat(64);
+            enc.write_header()
+                .unwrap()
+                .write_image_data(&pixels)
+                .unwrap();
+        }
+        self.write(name, &bytes)
+    }
+}
+
+impl Drop for Fixtures {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Little-endian EXIF payload whose IFD0 holds only the orientation tag.
+fn orientation_exif(orientation: u16) -> Vec<u8> {
+    let mut payload = EXIF_HEADER.to_vec();
+    payload.extend_from_slice(b"II*\0");
+    payload.extend_from_slice(&8u32.to_le_bytes());
+    payload.extend_from_slice(&1u16.to_le_bytes());
+    payload.extend_from_slice(&EXIF_ORIENTATION_TAG.to_le_bytes());
+    payload.extend_from_slice(&3u16.to_le_bytes());
+    payload.extend_from_slice(&1u32.to_le_bytes());
+    payload.extend_from_slice(&orientation.to_le_bytes());
+    payload.extend_from_slice(&[0, 0]);
+    payload.extend_from_slice(&0u32.to_le_bytes());
+    payload
+}
+
+fn options(format: ImageFormat) -> ConversionOptions {
+    ConversionOptions {
+        format,
+        png_compressed: false,
+        ..ConversionOptions::default()
+    }
+}
+
+/// Converts into memory, returning the encoded bytes, their decoded image, and the report.
+fn convert(input: &Path, options: &ConversionOptions) -> (Vec<u8>, DynamicImage, ConversionReport) {
+    let mut bytes = Vec::new();
+    let report = convert_image(input, options, 0, OutputTarget::Stream(&mut bytes)).unwrap();
+    let decoded = image::load_from_memory(&bytes).unwrap();
+    (bytes, decoded, report)
+}
+
+/// Orientation tag of an encoded output, `None` when it carries no EXIF or no such tag.
+fn output_orientation(bytes: &[u8]) -> Option<u32> {
+    exif::Reader::new()
+        .read_from_container(&mut std::io::Cursor::new(bytes))
+        .ok()
+        .and_then(|e| {
+            e.get_field(exif::Tag::Orientation, exif::In::PRIMARY)
+                .and_then(|f| f.value.get_uint(0))
+        })
+}
+
+#[test]
+fn resize_keeps_aspect_ratio_in_every_format() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.jpeg("gradient.jpg", None);
+    for format in ImageFormat::ALL {
+        let options = ConversionOptions {
+            resize: true,
+            target_width: "20".to_string(),
+            ..options(format)
+        };
+        let (_, decoded, report) = convert(&input, &options);
+        assert_eq!((decoded.width(), decoded.height()), (20, 10), "{}", format);
+        assert_eq!(report.dimensions, (20, 10), "{}", format);
+    }
+}
+
+#[test]
+fn fit_within_never_enlarges() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.jpeg("gradient.jpg", None);
+    for (limit, expected) in [("10", (10, 5)), ("400", (40, 20))] {
+        let options = ConversionOptions {
+            resize: true,
+            resize_mode: ResizeMode::FitWithin,
+            longest_side: limit.to_string(),
+            ..options(ImageFormat::Png)
+        };
+        let (_, decoded, _) = convert(&input, &options);
+        assert_eq!((decoded.width(), decoded.height()), expected);
+    }
+}
+
+#[test]
+fn alpha_survives_png_and_lossless_webp() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.transparent_png("alpha.png");
+    for format in [ImageFormat::Png, ImageFormat::WebP] {
+        let options = ConversionOptions {
+            webp_lossless: true,
+            ..options(format)
+        };
+        let (_, decoded, _) = convert(&input, &options);
+        let rgba = decoded.to_rgba8();
+        assert_eq!(rgba.get_pixel(4, 4).0[3], 0, "{}", format);
+        assert_eq!(rgba.get_pixel(28, 4).0[3], 255, "{}", format);
+    }
+}
+
+#[test]
+fn jpeg_flattens_transparency_onto_white() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.transparent_png("alpha.png");
+    let options = ConversionOptions {
+        flatten_alpha: true,
+        ..options(ImageFormat::Jpeg)
+    };
+    let (_, decoded, _) = convert(&input, &options);
+    let rgb = decoded.to_rgb8();
+    assert!(rgb.get_pixel(4, 16).0.iter().all(|&c| c > 245));
+}
+
+#[test]
+fn exif_orientation_is_applied_and_reset() {
+    let fixtures = Fixtures::new();
+    // 6 rotates 90° clockwise, so the 40x20 source is shown 20x40.
+    let input = fixtures.jpeg("rotated.jpg", Some(6));
+    for format in ImageFormat::ALL {
+        let options = ConversionOptions {
+            keep_metadata: true,
+            ..options(format)
+        };
+        let (bytes, decoded, _) = convert(&input, &options);
+        assert_eq!((decoded.width(), decoded.height()), (20, 40), "{}", format);
+        // The pixels are upright now; a kept tag of 6 would rotate them twice.
+        assert_eq!(output_orientation(&bytes), Some(1), "{}", format);
+    }
+}
+
+#[test]
+fn ignoring_exif_orientation_keeps_stored_pixels() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.jpeg("rotated.jpg", Some(6));
+    let options = ConversionOptions {
+        ignore_exif_orientation: true,
+        ..options(ImageFormat::Png)
+    };
+    let (_, decoded, _) = convert(&input, &options);
+    assert_eq!((decoded.width(), decoded.height()), (40, 20));
+}
+
+#[test]
+fn srgb_is_embedded_in_every_format() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.jpeg("gradient.jpg", None);
+
+    let (bytes, _, _) = convert(&input, &options(ImageFormat::Jpeg));
+    let jpeg = Jpeg::from_bytes(bytes.into()).unwrap();
+    assert_eq!(jpeg.icc_profile().as_deref(), Some(SRGB_ICC));
+
+    let (bytes, _, _) = convert(&input, &options(ImageFormat::WebP));
+    let webp = img_parts::webp::WebP::from_bytes(bytes.into()).unwrap();
+    assert_eq!(webp.icc_profile().as_deref(), Some(SRGB_ICC));
+
+    let (bytes, _, _) = convert(&input, &options(ImageFormat::Png));
+    let reader = png::Decoder::new(bytes.as_slice()).read_info().unwrap();
+    assert!(reader.info().srgb.is_some());
+}
+
+#[test]
+fn wide_gamut_sources_are_converted_to_srgb() {
+    let fixtures = Fixtures::new();
+    let p3 = output_icc(&ConversionOptions {
+        color_space: OutputColorSpace::DisplayP3,
+        ..ConversionOptions::default()
+    });
+    let input = fixtures.tagged_png("p3.png", [200, 100, 100], &p3);
+
+    let (_, decoded, _) = convert(&input, &options(ImageFormat::Png));
+    let [r, g, b] = decoded.to_rgb8().get_pixel(4, 4).0;
+    // P3 reds lie outside sRGB, so they come out more saturated.
+    assert!(r > 200 && g < 100 && b < 100, "got {:?}", [r, g, b]);
+
+    let untouched = ConversionOptions {
+        color_correction: ColorCorrection::Off,
+        ..options(ImageFormat::Png)
+    };
+    let (_, decoded, _) = convert(&input, &untouched);
+    assert_eq!(decoded.to_rgb8().get_pixel(4, 4).0, [200, 100, 100]);
+}
+
+#[test]
+fn template_tokens_in_substituted_text_are_kept_literally() {
+    let input = Path::new("photos/a{width}b.jpg");
+    let options = ConversionOptions {
+        filename_template: "{stem}_{width}x{height}".to_string(),
+        caption_template: "{original} {stem} {unknown}".to_string(),
+        ..options(ImageFormat::Png)
+    };
+    assert_eq!(
+        build_filename(input, &options, 40, 20, 0),
+        "a{width}b_40x20.png"
+    );
+    assert_eq!(
+        caption_text(input, &options, 40, 20, 0),
+        "a{width}b.jpg a{width}b {unknown}"
+    );
+}
+
+#[test]
+fn report_says_whether_the_output_was_verified() {
+    let fixtures = Fixtures::new();
+    let input = fixtures.jpeg("gradient.jpg", None);
+    for verify_output in [false, true] {
+        let options = ConversionOptions {
+            verify_output,
+            ..options(ImageFormat::WebP)
+        };
+        let (_, _, report) = convert(&input, &options);
+        assert_eq!(report.verified, verify_output);
+    }
+}
+
+#[test]
+fn jpeg_completeness_skips_padding_between_segments() {
+    let fixtures = Fixtures::new();
+    let bytes = std::fs::read(fixtures.jpeg("gradient.jpg", None)).unwrap();
+    assert!(jpeg_is_complete(&bytes));
+
+    // Zero padding after the first segment, as some camera firmware writes between segments.
+    let first_end = 4 + u16::from_be_bytes([bytes[4], bytes[5]]) as usize;
+    let mut padded = bytes[..first_end].to_vec();
+    padded.extend_from_slice(&[0; 7]);
+    padded.extend_from_slice(&bytes[first_end..]);
+    assert!(jpeg_is_complete(&padded));
+    let input = fixtures.write("padded.jpg", &padded);
+    let (_, decoded, _) = convert(&input, &options(ImageFormat::Png));
+    assert_eq!((decoded.width(), decoded.height()), (40, 20));
+
+    let truncated = &padded[..padded.len() - 2];
+    assert!(!jpeg_is_complete(truncated));
+}
@@ -0,0 +1,38 @@
+//! Runtime report of the codecs this build actually loaded, shown in the About panel.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once mozjpeg has failed and the pure-Rust JPEG encoder took over.
+static JPEG_FALLBACK_USED: AtomicBool = AtomicBool::new(false);
+
+/// Records that a JPEG was written by the fallback encoder.
+pub fn note_jpeg_fallback() {
+    JPEG_FALLBACK_USED.store(true, Ordering::Relaxed);
+}
+
+/// Lines describing codec availability and versions, suitable for bug reports.
+pub fn report() -> Vec<String> {
+    let heif = crate::heic::support();
+    let [major, minor, patch] = heif.version;
+    let available = |ok: bool| if ok { "available" } else { "missing" };
+    // Little CMS encodes its version as e.g. 2160 for 2.16.
+    let lcms = lcms2::version();
+
+    vec![
+        format!("Simple Image Converter {}", env!("CARGO_PKG_VERSION")),
+        format!("libheif {}.{}.{}", major, minor, patch),
+        format!("HEIC decoding (HEVC): {}", available(heif.hevc)),
+        format!("AVIF decoding (AV1): {}", available(heif.av1)),
+        format!(
+            "JPEG encoder: mozjpeg{}",
+            if JPEG_FALLBACK_USED.load(Ordering::Relaxed) {
+                " (fell back to jpeg-encoder this session)"
+            } else {
+                ""
+            }
+        ),
+        "WebP encoder: libwebp".to_string(),
+        "PNG optimizer: oxipng".to_string(),
+        format!("Little CMS {}.{}", lcms / 1000, lcms % 1000 / 10),
+    ]
+}
@@ -1,229 +1,1882 @@
 //! Message handlers extracted from main.rs for cleaner architecture.
 
+use crate::constants::{
+    ADD_CHUNK_SIZE, AUTO_SCROLL_EDGE, AUTO_SCROLL_STEP, DOUBLE_CLICK_MS, DROP_COALESCE_MS,
+    LARGE_ADD_THRESHOLD, MAX_NAMING_HISTORY, MAX_RECENT_OUTPUT_DIRS, MAX_REPORTED_LIST_LINES,
+    MIN_CROP_FRACTION, PREFETCH_CONCURRENCY, PREVIEW_MAX_SIDE, UNDO_DEPTH,
+};
+use crate::convert::{
+    finish_zip_sink, get_output_dir, load_rgb_profile, parse_max_file_size, quality_sweep,
+    read_file_info, render_preview, write_contact_sheet, ConversionReport,
+};
+use crate::manifest;
 use crate::message::Message;
-use crate::settings;
-use crate::state::{AppState, FileItem, FileStatus};
+use crate::state::{
+    AppState, ColorCorrection, ConversionOptions, ConversionOrder, CropRect, DoubleClickAction,
+    ExistingFilePolicy, ExtensionCase, FileInfo, FileItem, FileStatus, FrameChoice, Histogram,
+    Language, LastRun, ListEdit, NamingRule, OutputColorSpace, OutputTimestamp, PngColorTag,
+    Preview, QualityPreset, ResizeMode, ResizeSettings, StatusFilter, SweepPoint, UiDensity,
+};
+use crate::theme::dimensions;
+use crate::{remote, settings};
 use iced::Command;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use std::time::Duration;
 
 /// Toggles dark mode theme and saves preference.
 pub fn handle_dark_theme(state: &mut AppState, is_dark: bool) -> Command<Message> {
     state.options.is_dark_mode = is_dark;
-    settings::save_settings(&state.options);
+    persist_settings(state);
     Command::none()
 }
 
-/// Adds selected files to the conversion queue.
-pub fn handle_files_selected(
+/// Switches between the simple and full settings layout and saves preference.
+pub fn handle_simple_view(state: &mut AppState, simple: bool) -> Command<Message> {
+    state.options.simple_view = simple;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Switches the UI language and saves preference.
+pub fn handle_language(state: &mut AppState, language: Language) -> Command<Message> {
+    state.options.language = language;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Switches the file list between comfortable and compact row density.
+pub fn handle_compact_list(state: &mut AppState, compact: bool) -> Command<Message> {
+    state.options.ui_density = if compact {
+        UiDensity::Compact
+    } else {
+        UiDensity::Comfortable
+    };
+    persist_settings(state);
+    Command::none()
+}
+
+/// Saves settings; the first failure of the session is shown to the user.
+fn persist_settings(state: &mut AppState) {
+    if let Some(warning) = settings::save_settings(&state.options) {
+        if !state.settings_warned {
+            state.settings_warned = true;
+            state.settings_warning = Some(warning);
+        }
+    }
+}
+
+/// Remembers the folder of the last file added after `start` for the next file dialog.
+fn remember_input_dir(state: &mut AppState, start: usize) {
+    let dir = state.files[start..]
+        .last()
+        .and_then(|f| f.path.parent())
+        .map(|d| d.to_path_buf());
+    if dir.is_some() && dir != state.options.last_input_dir {
+        state.options.last_input_dir = dir;
+        persist_settings(state);
+    }
+}
+
+/// Saves the queue for the next session when queue restoring is enabled.
+fn persist_queue(state: &AppState) {
+    if state.options.restore_queue {
+        let paths: Vec<_> = state.files.iter().map(|f| f.path.clone()).collect();
+        settings::save_queue(&paths);
+    }
+}
+
+/// Toggles stopping the batch at the first failed file.
+pub fn handle_fail_fast(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.fail_fast = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles saving the queue for restoring at next launch.
+pub fn handle_restore_queue(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.restore_queue = v;
+    persist_settings(state);
+    if v {
+        persist_queue(state);
+    } else {
+        settings::save_queue(&[]);
+    }
+    Command::none()
+}
+
+/// Limits how many files are probed at once so large drops don't starve other work.
+static PREFETCH_PERMITS: tokio::sync::Semaphore =
+    tokio::sync::Semaphore::const_new(PREFETCH_CONCURRENCY);
+
+/// Reads dimensions and size of newly added files in the background.
+fn prefetch_info(items: &[FileItem]) -> Command<Message> {
+    Command::batch(items.iter().map(|item| {
+        let id = item.id;
+        let path = item.path.clone();
+        Command::perform(
+            async move {
+                let _permit = PREFETCH_PERMITS.acquire().await.ok()?;
+                tokio::task::spawn_blocking(move || read_file_info(&path).ok())
+                    .await
+                    .ok()
+                    .flatten()
+            },
+            move |info| Message::FileInfoReady(id, info),
+        )
+    }))
+}
+
+/// Adds selected or dropped files to the conversion queue, asking first when there are very many.
+pub fn handle_files_selected(paths: Vec<std::path::PathBuf>) -> Command<Message> {
+    if paths.len() <= LARGE_ADD_THRESHOLD {
+        return prepare_files(paths);
+    }
+    Command::perform(
+        async move {
+            let res = rfd::AsyncMessageDialog::new()
+                .set_title("Add Many Files")
+                .set_description(format!(
+                    "Add {} files to the queue? Reading their details may take a while.",
+                    paths.len()
+                ))
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show()
+                .await;
+            if res == rfd::MessageDialogResult::Yes {
+                paths
+            } else {
+                Vec::new()
+            }
+        },
+        Message::AddFilesConfirmed,
+    )
+}
+
+/// Checks and wraps the next chunk of `paths` off the UI thread, so huge adds stay responsive.
+pub fn prepare_files(mut paths: Vec<std::path::PathBuf>) -> Command<Message> {
+    if paths.is_empty() {
+        return Command::none();
+    }
+    Command::perform(
+        async move {
+            tokio::task::spawn_blocking(move || {
+                let rest = paths.split_off(paths.len().min(ADD_CHUNK_SIZE));
+                let items = paths
+                    .into_iter()
+                    .filter(|path| path.is_file())
+                    .map(FileItem::new)
+                    .collect();
+                (items, rest)
+            })
+            .await
+            .unwrap_or_default()
+        },
+        |(items, rest)| Message::FilesPrepared(items, rest),
+    )
+}
+
+/// Appends a prepared chunk, skipping files already queued, and requests the next one.
+pub fn handle_files_prepared(
+    state: &mut AppState,
+    items: Vec<FileItem>,
+    rest: Vec<std::path::PathBuf>,
+) -> Command<Message> {
+    let start = state.files.len();
+    let mut queued: HashSet<std::path::PathBuf> =
+        state.files.iter().map(|f| f.path.clone()).collect();
+    let mut rows = Vec::new();
+    for item in items {
+        if queued.insert(item.path.clone()) {
+            log::info!("Added {}", item.path.display());
+            rows.push((state.files.len(), item.clone()));
+            state.files.push(item);
+        }
+    }
+    // Chunks of one large add undo together.
+    let merge = state.add_batch_open && matches!(state.undo_stack.last(), Some(ListEdit::Added(_)));
+    if merge {
+        if let Some(ListEdit::Added(prev)) = state.undo_stack.last_mut() {
+            prev.extend(rows);
+        }
+    } else if !rows.is_empty() {
+        record_edit(state, ListEdit::Added(rows));
+    }
+    state.add_batch_open = !rest.is_empty();
+    let prefetch = prefetch_info(&state.files[start..]);
+    if !rest.is_empty() {
+        return Command::batch([prefetch, prepare_files(rest)]);
+    }
+    remember_input_dir(state, start);
+    persist_queue(state);
+    prefetch
+}
+
+/// Reads a picked file list off the UI thread.
+pub fn handle_file_list_selected(path: Option<std::path::PathBuf>) -> Command<Message> {
+    let Some(path) = path else {
+        return Command::none();
+    };
+    Command::perform(
+        async move {
+            tokio::task::spawn_blocking(move || manifest::read_file_list(&path))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|r| r)
+                .unwrap_or_else(|e| manifest::FileList {
+                    unresolved: vec![format!("{:#}", e)],
+                    ..Default::default()
+                })
+        },
+        |list| Message::FileListRead(list.items, list.unresolved),
+    )
+}
+
+/// Enqueues the files of an imported list in order and reports lines that were not added.
+pub fn handle_file_list_read(
+    state: &mut AppState,
+    items: Vec<FileItem>,
+    unresolved: Vec<String>,
+) -> Command<Message> {
+    let added = handle_files_prepared(state, items, Vec::new());
+    if unresolved.is_empty() {
+        return added;
+    }
+    for line in &unresolved {
+        log::warn!("File list {}", line);
+    }
+    let mut description = unresolved
+        .iter()
+        .take(MAX_REPORTED_LIST_LINES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("\n");
+    if unresolved.len() > MAX_REPORTED_LIST_LINES {
+        description.push_str(&format!(
+            "\n…and {} more",
+            unresolved.len() - MAX_REPORTED_LIST_LINES
+        ));
+    }
+    let report = Command::perform(
+        async move {
+            rfd::AsyncMessageDialog::new()
+                .set_title("Some Files Were Not Added")
+                .set_description(description)
+                .set_level(rfd::MessageLevel::Warning)
+                .show()
+                .await;
+        },
+        |_| Message::FileListReported,
+    );
+    Command::batch([added, report])
+}
+
+/// Collects an OS file drop; the OS sends one event per file, so drops are batched briefly.
+pub fn handle_file_dropped(state: &mut AppState, path: std::path::PathBuf) -> Command<Message> {
+    state.pending_drops.push(path);
+    if state.pending_drops.len() > 1 {
+        return Command::none();
+    }
+    Command::perform(
+        tokio::time::sleep(std::time::Duration::from_millis(DROP_COALESCE_MS)),
+        |_| Message::DroppedFilesFlushed,
+    )
+}
+
+/// Adds all files collected during the drop window as a single batch.
+pub fn handle_dropped_files_flushed(state: &mut AppState) -> Command<Message> {
+    let paths = std::mem::take(&mut state.pending_drops);
+    handle_files_selected(paths)
+}
+
+/// Stores prefetched file info on the matching queue item.
+pub fn handle_file_info_ready(
+    state: &mut AppState,
+    id: uuid::Uuid,
+    info: Option<FileInfo>,
+) -> Command<Message> {
+    if let Some(file) = state.files.iter_mut().find(|f| f.id == id) {
+        file.info = info;
+    }
+    Command::none()
+}
+
+/// Updates the pending URL text.
+pub fn handle_url_input(state: &mut AppState, v: String) -> Command<Message> {
+    state.url_input = v;
+    Command::none()
+}
+
+/// Enqueues a downloaded image. A failed download is reported in the status area instead,
+/// since its URL is no file path that could be converted or retried.
+pub fn handle_url_downloaded(
+    state: &mut AppState,
+    url: String,
+    result: Result<std::path::PathBuf, String>,
+) -> Command<Message> {
+    match result {
+        Ok(path) => {
+            log::info!("Downloaded {} to {}", url, path.display());
+            let item = FileItem::new(path);
+            let cmd = prefetch_info(std::slice::from_ref(&item));
+            record_edit(
+                state,
+                ListEdit::Added(vec![(state.files.len(), item.clone())]),
+            );
+            state.files.push(item);
+            persist_queue(state);
+            return cmd;
+        }
+        Err(e) => {
+            log::error!("Download failed for {}: {}", url, e);
+            state.failed_downloads.push((url, e));
+        }
+    }
+    Command::none()
+}
+
+/// Initiates drag operation for file reordering.
+pub fn handle_item_drag_started(state: &mut AppState, id: uuid::Uuid) -> Command<Message> {
+    state.dragging_id = Some(id);
+    Command::none()
+}
+
+/// Completes drag-drop file reordering onto the row `to_id` the button was released over.
+/// Rows are tracked by id so the move stays correct however the list is displayed, even
+/// when auto-scroll moved rows under a still cursor.
+pub fn handle_item_dropped(state: &mut AppState, to_id: uuid::Uuid) -> Command<Message> {
+    if let Some(from_id) = state.dragging_id {
+        let from_index = state.files.iter().position(|f| f.id == from_id);
+        let to_index = state.files.iter().position(|f| f.id == to_id);
+        if let (Some(from_index), Some(to_index)) = (from_index, to_index) {
+            if from_index != to_index {
+                let item = state.files.remove(from_index);
+                state.files.insert(to_index, item);
+                record_edit(
+                    state,
+                    ListEdit::Moved {
+                        from: from_index,
+                        to: to_index,
+                    },
+                );
+                state.selected_indices.clear();
+                persist_queue(state);
+            }
+        }
+    }
+    state.dragging_id = None;
+    state.hovered_id = None;
+    state.band_anchor = None;
+    Command::none()
+}
+
+/// Abandons a row drag or press-drag selection released outside the file list.
+/// Drops onto a row arrive as `ItemDropped` from the row itself, so a release that reaches
+/// the window never reorders anything.
+pub fn handle_drag_cancelled(state: &mut AppState) -> Command<Message> {
+    state.dragging_id = None;
+    state.hovered_id = None;
+    state.band_anchor = None;
+    Command::none()
+}
+
+/// Updates hover target during drag operation, or stretches a press-drag selection.
+pub fn handle_item_hovered(state: &mut AppState, id: Option<uuid::Uuid>) -> Command<Message> {
+    if state.dragging_id.is_some() {
+        state.hovered_id = id;
+    } else if let Some((anchor, select)) = state.band_anchor {
+        let Some(index) = id.and_then(|id| state.files.iter().position(|f| f.id == id)) else {
+            return Command::none();
+        };
+        let band = anchor.min(index)..=anchor.max(index);
+        state.selected_indices = state.band_base.clone();
+        let filter = state.status_filter;
+        // Rows hidden by the status filter between the two ends are left alone.
+        let shown: Vec<usize> = band.filter(|&i| filter.matches(&state.files[i])).collect();
+        for i in shown {
+            if select {
+                state.selected_indices.insert(i);
+            } else {
+                state.selected_indices.remove(&i);
+            }
+        }
+    }
+    Command::none()
+}
+
+/// Shows only files of one status; the selection is cleared so hidden rows are never acted on.
+pub fn handle_status_filter(state: &mut AppState, filter: StatusFilter) -> Command<Message> {
+    state.status_filter = filter;
+    state.selected_indices.clear();
+    state.band_anchor = None;
+    Command::none()
+}
+
+/// Tracks cursor position and applies file list resizing while dragging the splitter.
+pub fn handle_cursor_moved(state: &mut AppState, y: f32) -> Command<Message> {
+    state.cursor_y = y;
+    if let Some((start_y, start_height)) = state.list_resize_origin {
+        state.options.file_list_height = (start_height + y - start_y).clamp(
+            dimensions::FILE_LIST_MIN_HEIGHT,
+            dimensions::FILE_LIST_MAX_HEIGHT,
+        );
+    }
+    Command::none()
+}
+
+/// Remembers where the user scrolled the file list and how far it can go.
+pub fn handle_file_list_scrolled(state: &mut AppState, y: f32, max: f32) -> Command<Message> {
+    state.file_list_scroll_y = y;
+    state.file_list_scroll_max = Some(max);
+    Command::none()
+}
+
+/// Tracks the cursor over the file list for drag auto-scrolling.
+pub fn handle_file_list_pointer_moved(state: &mut AppState, y: Option<f32>) -> Command<Message> {
+    state.file_list_pointer_y = y;
+    Command::none()
+}
+
+/// Scrolls the file list while a dragged row is held near its top or bottom edge.
+pub fn handle_drag_auto_scroll(state: &mut AppState) -> Command<Message> {
+    let (Some(_), Some(y)) = (state.dragging_id, state.file_list_pointer_y) else {
+        return Command::none();
+    };
+    let height = state.options.file_list_height;
+    let depth = if y < AUTO_SCROLL_EDGE {
+        -(AUTO_SCROLL_EDGE - y)
+    } else if y > height - AUTO_SCROLL_EDGE {
+        y - (height - AUTO_SCROLL_EDGE)
+    } else {
+        return Command::none();
+    };
+    let step = AUTO_SCROLL_STEP * (depth / AUTO_SCROLL_EDGE).clamp(-1.0, 1.0);
+    // Until the list reports its extent, assume rows about as tall as the edge band.
+    let max = state
+        .file_list_scroll_max
+        .unwrap_or(state.files.len() as f32 * AUTO_SCROLL_EDGE);
+    let next = (state.file_list_scroll_y + step).clamp(0.0, max);
+    if next == state.file_list_scroll_y {
+        return Command::none();
+    }
+    state.file_list_scroll_y = next;
+    pin_file_list_scroll(state)
+}
+
+/// Scrolls the file list back to the remembered offset in case status updates moved it.
+pub fn pin_file_list_scroll(state: &AppState) -> Command<Message> {
+    iced::widget::scrollable::scroll_to(
+        crate::view::file_list_scroll_id(),
+        iced::widget::scrollable::AbsoluteOffset {
+            x: 0.0,
+            y: state.file_list_scroll_y,
+        },
+    )
+}
+
+/// Begins resizing the file list from the current cursor position.
+pub fn handle_list_resize_started(state: &mut AppState) -> Command<Message> {
+    state.list_resize_origin = Some((state.cursor_y, state.options.file_list_height));
+    Command::none()
+}
+
+/// Finishes file list resizing and persists the chosen height.
+pub fn handle_list_resize_ended(state: &mut AppState) -> Command<Message> {
+    if state.list_resize_origin.take().is_some() {
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Toggles file selection state for batch operations.
+pub fn handle_toggle_selection(state: &mut AppState, index: usize) -> Command<Message> {
+    let now = std::time::Instant::now();
+    let double = state.last_click.take().is_some_and(|(last, at)| {
+        last == index && now.duration_since(at) < Duration::from_millis(DOUBLE_CLICK_MS)
+    });
+    if double {
+        state.band_anchor = None;
+        return Command::perform(async {}, move |_| Message::FileDoubleClicked(index));
+    }
+    state.last_click = Some((index, now));
+    let id = state.files.get(index).map(|f| f.id);
+    // Holding the button and moving over other rows extends the same change to them.
+    state.band_base = state.selected_indices.clone();
+    state.band_anchor = Some((index, !state.selected_indices.contains(&index)));
+    if state.selected_indices.contains(&index) {
+        state.selected_indices.remove(&index);
+        if state.preview_id == id {
+            state.preview_id = None;
+        }
+        Command::none()
+    } else {
+        state.selected_indices.insert(index);
+        state.preview_id = id;
+        refresh_crop_preview(state);
+        request_preview(state, index)
+    }
+}
+
+/// Tracks the cursor over the preview and stretches the crop being drawn.
+pub fn handle_crop_pointer_moved(state: &mut AppState, x: f32, y: f32) -> Command<Message> {
+    state.crop_cursor = Some((x, y));
+    if let Some(anchor) = state.crop_anchor {
+        set_crop(state, Some(CropRect::between(anchor, (x, y))));
+    }
+    Command::none()
+}
+
+/// Starts drawing a crop from the cursor position; crops are fixed while converting.
+pub fn handle_crop_drag_started(state: &mut AppState) -> Command<Message> {
+    if !state.is_processing {
+        state.crop_anchor = state.crop_cursor;
+    }
+    Command::none()
+}
+
+/// Finishes drawing a crop; a click without a real drag clears it.
+pub fn handle_crop_drag_ended(state: &mut AppState) -> Command<Message> {
+    if state.crop_anchor.take().is_some() {
+        let crop = state
+            .preview_id
+            .and_then(|id| state.files.iter().find(|f| f.id == id))
+            .and_then(|f| f.crop);
+        if crop.is_some_and(|c| c.width < MIN_CROP_FRACTION || c.height < MIN_CROP_FRACTION) {
+            set_crop(state, None);
+        }
+    }
+    Command::none()
+}
+
+/// Removes the crop of the previewed file.
+pub fn handle_crop_cleared(state: &mut AppState) -> Command<Message> {
+    set_crop(state, None);
+    Command::none()
+}
+
+/// Opens a queued file in the OS default image app and remembers it for re-reading on return.
+pub fn handle_edit_externally(state: &mut AppState, id: uuid::Uuid) -> Command<Message> {
+    let Some(file) = state.files.iter().find(|f| f.id == id) else {
+        return Command::none();
+    };
+    if let Err(e) = open_in_default_app(&file.path) {
+        log::error!("Could not open {}: {}", file.path.display(), e);
+        return Command::none();
+    }
+    let modified = modified_time(&file.path);
+    state.external_edits.retain(|(edited, _)| *edited != id);
+    state.external_edits.push((id, modified));
+    Command::none()
+}
+
+/// Re-reads files changed in an external editor since they were opened, dropping their
+/// cached preview and last result so the next conversion uses the edit.
+pub fn handle_window_focused(state: &mut AppState) -> Command<Message> {
+    let files = &state.files;
+    state
+        .external_edits
+        .retain(|(id, _)| files.iter().any(|f| f.id == *id));
+    let mut changed = Vec::new();
+    for (id, modified) in &mut state.external_edits {
+        let Some(index) = files.iter().position(|f| f.id == *id) else {
+            continue;
+        };
+        let current = modified_time(&files[index].path);
+        if current != *modified {
+            *modified = current;
+            changed.push(index);
+        }
+    }
+
+    let mut commands = Vec::new();
+    for index in changed {
+        let file = &mut state.files[index];
+        log::info!("{} changed externally, reloading", file.path.display());
+        file.info = None;
+        file.status = FileStatus::Pending;
+        file.note = None;
+        file.report = None;
+        let id = file.id;
+        commands.push(prefetch_info(std::slice::from_ref(file)));
+        state.previews.remove(&id);
+        state.histograms.remove(&id);
+        if state.preview_id == Some(id) {
+            commands.push(request_preview(state, index));
+        }
+    }
+    refresh_crop_preview(state);
+    Command::batch(commands)
+}
+
+/// Last modification time of a file, if the file system reports one.
+fn modified_time(path: &Path) -> Option<std::time::SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Launches the platform's default application for `path` without waiting for it.
+fn open_in_default_app(path: &Path) -> std::io::Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.args(["/C", "start", ""]);
+        command
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+    } else {
+        std::process::Command::new("xdg-open")
+    };
+    command.arg(path).spawn().map(|_| ())
+}
+
+/// Opens the folder containing `path` in the platform file manager, selecting it where possible.
+fn show_in_folder(path: &Path) -> std::io::Result<()> {
+    if cfg!(windows) {
+        let mut select = std::ffi::OsString::from("/select,");
+        select.push(path);
+        std::process::Command::new("explorer").arg(select).spawn()?;
+    } else if cfg!(target_os = "macos") {
+        std::process::Command::new("open")
+            .arg("-R")
+            .arg(path)
+            .spawn()?;
+    } else {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        std::process::Command::new("xdg-open").arg(dir).spawn()?;
+    }
+    Ok(())
+}
+
+/// Stores the crop on the previewed file and redraws the dimmed preview.
+fn set_crop(state: &mut AppState, crop: Option<CropRect>) {
+    let id = state.preview_id;
+    if let Some(file) = state.files.iter_mut().find(|f| Some(f.id) == id) {
+        file.crop = crop;
+    }
+    refresh_crop_preview(state);
+}
+
+/// Rebuilds the preview overlay that dims everything outside the crop.
+fn refresh_crop_preview(state: &mut AppState) {
+    state.crop_preview = state.preview_id.and_then(|id| {
+        let crop = state.files.iter().find(|f| f.id == id)?.crop?;
+        let preview = state.previews.get(&id)?.as_ref()?;
+        Some((id, dim_outside_crop(preview, crop)))
+    });
+}
+
+/// Darkens the pixels outside `crop` so the kept region stands out.
+fn dim_outside_crop(preview: &Preview, crop: CropRect) -> iced::widget::image::Handle {
+    let width = preview.width;
+    let (x0, y0, w, h) = crop.pixels(width, preview.height);
+    let mut rgba = preview.pixels.to_vec();
+    for (i, px) in rgba.chunks_exact_mut(4).enumerate() {
+        let (x, y) = (i as u32 % width, i as u32 / width);
+        if x < x0 || y < y0 || x >= x0 + w || y >= y0 + h {
+            for c in &mut px[..3] {
+                *c /= 3;
+            }
+        }
+    }
+    iced::widget::image::Handle::from_pixels(width, preview.height, rgba)
+}
+
+/// Starts background rendering of a file's preview unless it is already cached.
+fn request_preview(state: &AppState, index: usize) -> Command<Message> {
+    let Some(file) = state.files.get(index) else {
+        return Command::none();
+    };
+    if state.previews.contains_key(&file.id) {
+        return Command::none();
+    }
+    let id = file.id;
+    let path = file.path.clone();
+    let (frame, frame_number) = (state.options.frame_choice, state.options.frame_number);
+    let (adjustments, correction) = (state.options.adjustments, state.options.color_correction);
+    let orient = !state.options.ignore_exif_orientation;
+    Command::perform(
+        async move {
+            tokio::task::spawn_blocking(move || {
+                render_preview(
+                    &path,
+                    PREVIEW_MAX_SIDE,
+                    frame,
+                    frame_number,
+                    correction,
+                    adjustments,
+                    orient,
+                )
+                .ok()
+            })
+            .await
+            .ok()
+            .flatten()
+        },
+        move |preview| {
+            Message::PreviewReady(
+                id,
+                preview.map(|(img, histogram)| (Preview::new(img), histogram)),
+            )
+        },
+    )
+}
+
+/// Starts a background quality sweep of the previewed file at its crop and current settings.
+pub fn handle_quality_sweep(state: &mut AppState) -> Command<Message> {
+    let Some(file) = state.files.iter().find(|f| Some(f.id) == state.preview_id) else {
+        return Command::none();
+    };
+    let id = file.id;
+    let path = file.path.clone();
+    let options = ConversionOptions {
+        crop: file.crop,
+        ..state.options.clone()
+    };
+    state.sweep_running = true;
+    Command::perform(
+        async move {
+            tokio::task::spawn_blocking(move || {
+                quality_sweep(&path, &options).map_err(|e| format!("{:#}", e))
+            })
+            .await
+            .unwrap_or_else(|e| Err(e.to_string()))
+        },
+        move |result| Message::QualitySweepReady(id, result),
+    )
+}
+
+/// Shows a finished quality sweep, replacing the previous one.
+pub fn handle_quality_sweep_ready(
+    state: &mut AppState,
+    id: uuid::Uuid,
+    result: Result<Vec<SweepPoint>, String>,
+) -> Command<Message> {
+    state.sweep_running = false;
+    match result {
+        Ok(points) => state.quality_sweep = Some((id, points)),
+        Err(e) => log::error!("Quality sweep failed: {}", e),
+    }
+    Command::none()
+}
+
+/// Caches a rendered preview and histogram, or marks the preview unavailable if rendering failed.
+pub fn handle_preview_ready(
+    state: &mut AppState,
+    id: uuid::Uuid,
+    preview: Option<(Preview, Histogram)>,
+) -> Command<Message> {
+    if state.files.iter().any(|f| f.id == id) {
+        let preview = preview.map(|(preview, histogram)| {
+            state.histograms.insert(id, histogram);
+            preview
+        });
+        state.previews.insert(id, preview);
+        refresh_crop_preview(state);
+    }
+    Command::none()
+}
+
+/// Removes all selected files from the queue.
+pub fn handle_delete_selected(state: &mut AppState) -> Command<Message> {
+    let mut indices: Vec<usize> = state.selected_indices.iter().cloned().collect();
+    indices.sort_by(|a, b| b.cmp(a));
+    let mut rows = Vec::new();
+    for idx in indices {
+        if idx < state.files.len() {
+            rows.push((idx, state.files.remove(idx)));
+        }
+    }
+    if !rows.is_empty() {
+        rows.reverse();
+        record_edit(state, ListEdit::Removed(rows));
+    }
+    state.selected_indices.clear();
+    state.preview_id = None;
+    let files = &state.files;
+    state
+        .previews
+        .retain(|id, _| files.iter().any(|f| f.id == *id));
+    state
+        .histograms
+        .retain(|id, _| files.iter().any(|f| f.id == *id));
+    persist_queue(state);
+    Command::none()
+}
+
+/// Pushes a list edit onto the undo history, dropping the oldest past `UNDO_DEPTH`.
+/// A fresh edit makes the undone ones unreachable, so the redo history is cleared.
+fn record_edit(state: &mut AppState, edit: ListEdit) {
+    let mut dropped: Vec<ListEdit> = state.redo_stack.drain(..).collect();
+    state.undo_stack.push(edit);
+    if state.undo_stack.len() > UNDO_DEPTH {
+        dropped.push(state.undo_stack.remove(0));
+    }
+    state.add_batch_open = false;
+    release_downloads(state, dropped);
+}
+
+/// Deletes the downloaded images in edits that left the history, once no queued file or
+/// remaining undo step can bring them back.
+fn release_downloads(state: &AppState, dropped: Vec<ListEdit>) {
+    fn rows(edit: &ListEdit) -> &[(usize, FileItem)] {
+        match edit {
+            ListEdit::Added(rows) | ListEdit::Removed(rows) => rows,
+            ListEdit::Moved { .. } => &[],
+        }
+    }
+    let reachable: HashSet<&Path> = state
+        .undo_stack
+        .iter()
+        .chain(&state.redo_stack)
+        .flat_map(rows)
+        .map(|(_, item)| item.path.as_path())
+        .chain(state.files.iter().map(|f| f.path.as_path()))
+        .collect();
+    for (_, item) in dropped.iter().flat_map(rows) {
+        if remote::is_downloaded(&item.path) && !reachable.contains(item.path.as_path()) {
+            remote::remove_download(&item.path);
+        }
+    }
+}
+
+/// Takes the given files out of the list by id and returns them, as they are now,
+/// at their recorded positions.
+fn take_rows(state: &mut AppState, rows: Vec<(usize, FileItem)>) -> Vec<(usize, FileItem)> {
+    let mut found: Vec<(usize, usize)> = rows
+        .iter()
+        .filter_map(|(pos, item)| {
+            let current = state.files.iter().position(|f| f.id == item.id)?;
+            Some((current, *pos))
+        })
+        .collect();
+    found.sort_by_key(|(current, _)| std::cmp::Reverse(*current));
+    let mut taken: Vec<(usize, FileItem)> = found
+        .into_iter()
+        .map(|(current, pos)| (pos, state.files.remove(current)))
+        .collect();
+    taken.sort_by_key(|(pos, _)| *pos);
+    taken
+}
+
+/// Puts files back at their recorded positions, clamped to the list's end.
+fn insert_rows(state: &mut AppState, rows: Vec<(usize, FileItem)>) -> Vec<(usize, FileItem)> {
+    for (pos, item) in &rows {
+        let pos = (*pos).min(state.files.len());
+        state.files.insert(pos, item.clone());
+    }
+    rows
+}
+
+/// Plays `edit` backwards for undo or forwards for redo and returns it for the other stack.
+fn replay_edit(state: &mut AppState, edit: ListEdit, forward: bool) -> ListEdit {
+    match edit {
+        ListEdit::Added(rows) if forward => ListEdit::Added(insert_rows(state, rows)),
+        ListEdit::Added(rows) => ListEdit::Added(take_rows(state, rows)),
+        ListEdit::Removed(rows) if forward => ListEdit::Removed(take_rows(state, rows)),
+        ListEdit::Removed(rows) => ListEdit::Removed(insert_rows(state, rows)),
+        ListEdit::Moved { from, to } => {
+            let (a, b) = if forward { (from, to) } else { (to, from) };
+            if a < state.files.len() && b < state.files.len() {
+                let item = state.files.remove(a);
+                state.files.insert(b, item);
+            }
+            ListEdit::Moved { from, to }
+        }
+    }
+}
+
+/// Resets selection and drops previews of files no longer queued after an undo or redo.
+fn finish_replay(state: &mut AppState) {
+    state.selected_indices.clear();
+    state.band_anchor = None;
+    state.add_batch_open = false;
+    let files = &state.files;
+    if !files.iter().any(|f| Some(f.id) == state.preview_id) {
+        state.preview_id = None;
+    }
+    state
+        .previews
+        .retain(|id, _| files.iter().any(|f| f.id == *id));
+    state
+        .histograms
+        .retain(|id, _| files.iter().any(|f| f.id == *id));
+    persist_queue(state);
+}
+
+/// Reverts the most recent file list edit.
+pub fn handle_undo(state: &mut AppState) -> Command<Message> {
+    if state.is_processing {
+        return Command::none();
+    }
+    if let Some(edit) = state.undo_stack.pop() {
+        let edit = replay_edit(state, edit, false);
+        state.redo_stack.push(edit);
+        finish_replay(state);
+    }
+    Command::none()
+}
+
+/// Reapplies the most recently undone file list edit.
+pub fn handle_redo(state: &mut AppState) -> Command<Message> {
+    if state.is_processing {
+        return Command::none();
+    }
+    if let Some(edit) = state.redo_stack.pop() {
+        let edit = replay_edit(state, edit, true);
+        state.undo_stack.push(edit);
+        finish_replay(state);
+    }
+    Command::none()
+}
+
+/// Clears all files from the conversion queue.
+pub fn handle_clear_list(state: &mut AppState) -> Command<Message> {
+    if !state.files.is_empty() {
+        let rows = state.files.drain(..).enumerate().collect();
+        record_edit(state, ListEdit::Removed(rows));
+    }
+    state.selected_indices.clear();
+    state.preview_id = None;
+    state.previews.clear();
+    state.histograms.clear();
+    persist_queue(state);
+    Command::none()
+}
+
+/// Updates output format selection.
+pub fn handle_format_selected(
+    state: &mut AppState,
+    format: crate::state::ImageFormat,
+) -> Command<Message> {
+    let opts = &mut state.options;
+    if format != opts.format {
+        let current = ResizeSettings {
+            resize: opts.resize,
+            target_width: opts.target_width.clone(),
+            target_height: opts.target_height.clone(),
+        };
+        opts.format_resize.insert(opts.format, current);
+        // Formats without saved settings inherit the current ones.
+        if let Some(next) = opts.format_resize.remove(&format) {
+            opts.resize = next.resize;
+            opts.target_width = next.target_width;
+            opts.target_height = next.target_height;
+        }
+        if !opts.quality_customized {
+            opts.quality = format.default_quality();
+            opts.custom_quality = opts.quality;
+        }
+    }
+    opts.format = format;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Adds or removes an output format; unchecking the main format promotes the next one.
+pub fn handle_format_toggled(
+    state: &mut AppState,
+    format: crate::state::ImageFormat,
+    checked: bool,
+) -> Command<Message> {
+    let opts = &mut state.options;
+    if checked {
+        if format != opts.format && !opts.extra_formats.contains(&format) {
+            opts.extra_formats.push(format);
+        }
+    } else if format != opts.format {
+        opts.extra_formats.retain(|&f| f != format);
+    } else if !opts.extra_formats.is_empty() {
+        // At least one format stays selected.
+        let next = opts.extra_formats.remove(0);
+        return handle_format_selected(state, next);
+    }
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles writing each input back in its own format.
+pub fn handle_keep_source_format(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.keep_source_format = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates quality level from slider; it is saved when the slider is released.
+pub fn handle_quality_changed(state: &mut AppState, q: u8) -> Command<Message> {
+    state.options.quality = q;
+    state.options.custom_quality = q;
+    state.options.quality_customized = true;
+    Command::none()
+}
+
+/// Saves the value a settings slider was dragged to.
+pub fn handle_slider_released(state: &mut AppState) -> Command<Message> {
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates quality level from text input.
+pub fn handle_quality_input(state: &mut AppState, value: String) -> Command<Message> {
+    if let Ok(num) = value.parse::<u8>() {
+        state.options.quality = num.min(100);
+        state.options.custom_quality = state.options.quality;
+        state.options.quality_customized = true;
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Applies a quality preset without overwriting the remembered manual value.
+pub fn handle_quality_preset(state: &mut AppState, preset: QualityPreset) -> Command<Message> {
+    state.options.quality = preset.quality(state.options.format, state.options.custom_quality);
+    state.options.quality_customized = true;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Switches between the per-format perceptual slider and raw encoder quality.
+pub fn handle_unified_quality(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.unified_quality = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates the perceptual quality used in unified mode; saved on slider release.
+pub fn handle_perceptual_quality(state: &mut AppState, q: u8) -> Command<Message> {
+    state.options.perceptual_quality = q.min(100);
+    Command::none()
+}
+
+/// Updates the print density; an empty field restores the encoder default.
+pub fn handle_dpi(state: &mut AppState, value: String) -> Command<Message> {
+    if value.trim().is_empty() {
+        state.options.dpi = 0;
+    } else if let Ok(num) = value.trim().parse::<u16>() {
+        state.options.dpi = num;
+    } else {
+        return Command::none();
+    }
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles lossless WebP encoding for every file.
+pub fn handle_webp_lossless(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.webp_lossless = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles encoding animated GIF/WebP inputs as animated WebP.
+pub fn handle_keep_animation(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.keep_animation = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles switching WebP to lossless for screenshot-like images.
+pub fn handle_auto_lossless(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.auto_lossless = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles PNG compression optimization.
+pub fn handle_png_compression(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.png_compressed = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles writing indexed PNG output for indexed PNG sources.
+pub fn handle_keep_png_palette(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.keep_png_palette = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles full bit depth HEIC decoding for PNG output.
+pub fn handle_heic_high_bit_depth(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.heic_high_bit_depth = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets the color space outputs are converted to and tagged with.
+pub fn handle_color_space(state: &mut AppState, space: OutputColorSpace) -> Command<Message> {
+    state.options.color_space = space;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets the color chunks sRGB PNG output is tagged with.
+pub fn handle_png_color_tag(state: &mut AppState, tag: PngColorTag) -> Command<Message> {
+    state.options.png_color_tag = tag;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets how embedded input profiles are handled and re-renders the preview.
+pub fn handle_color_correction(
+    state: &mut AppState,
+    correction: ColorCorrection,
+) -> Command<Message> {
+    state.options.color_correction = correction;
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Toggles image resize option.
+pub fn handle_resize_toggled(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.resize = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates target resize width.
+pub fn handle_width_changed(state: &mut AppState, v: String) -> Command<Message> {
+    if v.chars().all(|c| c.is_numeric()) {
+        state.options.target_width = v;
+        fill_locked_side(state, true);
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Updates target resize height.
+pub fn handle_height_changed(state: &mut AppState, v: String) -> Command<Message> {
+    if v.chars().all(|c| c.is_numeric()) {
+        state.options.target_height = v;
+        fill_locked_side(state, false);
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Switches between exact dimensions and fitting within a longest side.
+pub fn handle_resize_mode(state: &mut AppState, mode: ResizeMode) -> Command<Message> {
+    state.options.resize_mode = mode;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates the longest side limit of the fit-within mode.
+pub fn handle_longest_side(state: &mut AppState, v: String) -> Command<Message> {
+    if v.chars().all(|c| c.is_ascii_digit()) {
+        state.options.longest_side = v;
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Updates the megapixel budget; accepts a blank (off) or decimal number.
+pub fn handle_max_megapixels(state: &mut AppState, v: String) -> Command<Message> {
+    if v.chars().all(|c| c.is_ascii_digit() || c == '.') && v.matches('.').count() <= 1 {
+        state.options.max_megapixels = v;
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Toggles sorting outputs into per-format subfolders.
+pub fn handle_format_subfolders(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.format_subfolders = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles keeping the aspect ratio, filling in the height from the width if one is set.
+pub fn handle_lock_aspect(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.lock_aspect = v;
+    fill_locked_side(state, true);
+    persist_settings(state);
+    Command::none()
+}
+
+/// With the aspect locked, derives the other resize side from the one just typed, using the
+/// first selected file's aspect ratio (or 1:1 when nothing with known size is selected).
+fn fill_locked_side(state: &mut AppState, from_width: bool) {
+    if !state.options.lock_aspect {
+        return;
+    }
+    let (aspect_w, aspect_h) = state
+        .selected_indices
+        .iter()
+        .min()
+        .and_then(|&i| state.files.get(i))
+        .and_then(|f| f.info)
+        .filter(|info| info.width > 0 && info.height > 0)
+        .map_or((1, 1), |info| (info.width, info.height));
+    let options = &mut state.options;
+    let (typed, other, num, den) = if from_width {
+        (
+            &options.target_width,
+            &mut options.target_height,
+            aspect_h,
+            aspect_w,
+        )
+    } else {
+        (
+            &options.target_height,
+            &mut options.target_width,
+            aspect_w,
+            aspect_h,
+        )
+    };
+    if let Some(side) = typed.parse::<u32>().ok().filter(|&side| side > 0) {
+        *other = ((side as f64 * num as f64 / den as f64).round() as u32)
+            .max(1)
+            .to_string();
+    }
+}
+
+/// Toggles padding to the exact resize size instead of stretching.
+pub fn handle_pad_to_fit(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.pad_to_fit = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates the pad color; invalid colors fall back to black when drawn.
+pub fn handle_pad_color(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.pad_color = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles cropping of black letterbox bars.
+pub fn handle_remove_letterbox(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.remove_letterbox = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates how dark a pixel must be to count as part of a letterbox bar; saved on release.
+pub fn handle_letterbox_tolerance(state: &mut AppState, v: u8) -> Command<Message> {
+    state.options.letterbox_tolerance = v;
+    Command::none()
+}
+
+/// Updates filename prefix.
+pub fn handle_prefix_changed(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.prefix = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates find pattern for filename replacement.
+pub fn handle_find_pattern(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.find_pattern = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates replacement string for filename pattern.
+pub fn handle_replace_with(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.replace_with = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Reapplies a prefix and find/replace rule from the naming history.
+pub fn handle_naming_rule_selected(state: &mut AppState, rule: NamingRule) -> Command<Message> {
+    state.options.prefix = rule.prefix;
+    state.options.find_pattern = rule.find;
+    state.options.replace_with = rule.replace;
+    remember_naming_rule(state);
+    Command::none()
+}
+
+/// Moves the current naming rule to the front of the history when it renames anything.
+pub fn remember_naming_rule(state: &mut AppState) {
+    let opts = &mut state.options;
+    let rule = NamingRule {
+        prefix: opts.prefix.clone(),
+        find: opts.find_pattern.clone(),
+        replace: opts.replace_with.clone(),
+    };
+    // Tabs and newlines separate rules in the settings database.
+    let storable = [&rule.prefix, &rule.find, &rule.replace]
+        .iter()
+        .all(|s| !s.contains(['\t', '\n']));
+    if (rule.prefix.is_empty() && rule.find.is_empty()) || !storable {
+        return;
+    }
+    opts.naming_history.retain(|r| r != &rule);
+    opts.naming_history.insert(0, rule);
+    opts.naming_history.truncate(MAX_NAMING_HISTORY);
+    persist_settings(state);
+}
+
+/// Toggles automatic resolution/quality suffix.
+pub fn handle_auto_suffix(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.auto_suffix = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets the letter after the short side in the auto suffix.
+pub fn handle_suffix_size_letter(state: &mut AppState, value: String) -> Command<Message> {
+    state.options.suffix_size_letter = value.replace(['/', '\\'], "");
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets the letter after the quality in the auto suffix.
+pub fn handle_suffix_quality_letter(state: &mut AppState, value: String) -> Command<Message> {
+    state.options.suffix_quality_letter = value.replace(['/', '\\'], "");
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles the resize mode in the auto suffix.
+pub fn handle_suffix_mode(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.suffix_mode = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets what double-clicking a file does.
+pub fn handle_double_click_action(
     state: &mut AppState,
-    paths: Vec<std::path::PathBuf>,
+    action: DoubleClickAction,
 ) -> Command<Message> {
-    for path in paths {
-        if !state.files.iter().any(|f| f.path == path) {
-            state.files.push(FileItem::new(path));
+    state.options.double_click_action = action;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Runs the chosen double-click action on a file, keeping it selected.
+pub fn handle_file_double_clicked(state: &mut AppState, index: usize) -> Command<Message> {
+    let Some(file) = state.files.get(index) else {
+        return Command::none();
+    };
+    let (id, path) = (file.id, file.path.clone());
+    state.selected_indices.insert(index);
+    match state.options.double_click_action {
+        DoubleClickAction::Preview => {
+            state.preview_id = Some(id);
+            refresh_crop_preview(state);
+            request_preview(state, index)
+        }
+        DoubleClickAction::EditExternally => handle_edit_externally(state, id),
+        DoubleClickAction::ShowInFolder => {
+            if let Err(e) = show_in_folder(&path) {
+                log::error!("Could not show {}: {}", path.display(), e);
+            }
+            Command::none()
         }
     }
-    Command::none()
 }
 
-/// Handles files dropped from external file manager.
-pub fn handle_external_files(
+/// Sets which time written outputs are stamped with.
+pub fn handle_output_timestamp(
     state: &mut AppState,
-    paths: Vec<std::path::PathBuf>,
+    timestamp: OutputTimestamp,
 ) -> Command<Message> {
-    for path in paths {
-        if path.is_file() {
-            state.files.push(FileItem::new(path));
-        }
-    }
+    state.options.output_timestamp = timestamp;
+    persist_settings(state);
     Command::none()
 }
 
-/// Initiates drag operation for file reordering.
-pub fn handle_item_drag_started(state: &mut AppState, index: usize) -> Command<Message> {
-    state.dragging_index = Some(index);
+/// Sets the casing of output file extensions.
+pub fn handle_extension_case(state: &mut AppState, case: ExtensionCase) -> Command<Message> {
+    state.options.extension_case = case;
+    persist_settings(state);
     Command::none()
 }
 
-/// Completes drag-drop file reordering.
-pub fn handle_item_dropped(state: &mut AppState) -> Command<Message> {
-    if let Some(from_index) = state.dragging_index {
-        if let Some(to_index) = state.hovered_index {
-            if from_index != to_index
-                && from_index < state.files.len()
-                && to_index < state.files.len()
-            {
-                let item = state.files.remove(from_index);
-                state.files.insert(to_index, item);
-                state.selected_indices.clear();
-            }
-        }
-    }
-    state.dragging_index = None;
-    state.hovered_index = None;
+/// Sets what happens to outputs that already exist.
+pub fn handle_existing_file_policy(
+    state: &mut AppState,
+    policy: ExistingFilePolicy,
+) -> Command<Message> {
+    state.options.existing_file_policy = policy;
+    persist_settings(state);
     Command::none()
 }
 
-/// Updates hover target during drag operation.
-pub fn handle_item_hovered(state: &mut AppState, index_opt: Option<usize>) -> Command<Message> {
-    if state.dragging_index.is_some() {
-        state.hovered_index = index_opt;
-    }
+/// Toggles overwriting existing outputs without the confirmation prompt.
+pub fn handle_overwrite_without_asking(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.overwrite_without_asking = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Toggles file selection state for batch operations.
-pub fn handle_toggle_selection(state: &mut AppState, index: usize) -> Command<Message> {
-    if state.selected_indices.contains(&index) {
-        state.selected_indices.remove(&index);
+/// Sets how many existing outputs are overwritten before the prompt appears.
+pub fn handle_overwrite_threshold(state: &mut AppState, value: String) -> Command<Message> {
+    if value.is_empty() {
+        state.options.overwrite_prompt_threshold = 0;
+    } else if let Ok(num) = value.parse::<usize>() {
+        state.options.overwrite_prompt_threshold = num;
     } else {
-        state.selected_indices.insert(index);
+        return Command::none();
     }
+    persist_settings(state);
     Command::none()
 }
 
-/// Removes all selected files from the queue.
-pub fn handle_delete_selected(state: &mut AppState) -> Command<Message> {
-    let mut indices: Vec<usize> = state.selected_indices.iter().cloned().collect();
-    indices.sort_by(|a, b| b.cmp(a));
-    for idx in indices {
-        if idx < state.files.len() {
-            state.files.remove(idx);
+/// Updates filename template used instead of prefix/suffix naming.
+pub fn handle_filename_template(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.filename_template = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates strftime pattern used by date filename tokens.
+pub fn handle_date_format(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.date_format = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles custom output folder usage.
+pub fn handle_custom_output(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.use_custom_output = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Sets custom output folder path, rejecting paths that name a file.
+pub fn handle_output_selected(
+    state: &mut AppState,
+    path_opt: Option<std::path::PathBuf>,
+) -> Command<Message> {
+    if let Some(path) = path_opt {
+        if path.is_file() {
+            state.settings_warning = Some(format!(
+                "{} is a file; choose a folder for output instead",
+                path.display()
+            ));
+            return Command::none();
         }
+        let recent = &mut state.options.recent_output_dirs;
+        recent.retain(|p| p != &path);
+        recent.insert(0, path.clone());
+        recent.truncate(MAX_RECENT_OUTPUT_DIRS);
+        state.options.custom_output_path = Some(path);
+        persist_settings(state);
     }
-    state.selected_indices.clear();
     Command::none()
 }
 
-/// Clears all files from the conversion queue.
-pub fn handle_clear_list(state: &mut AppState) -> Command<Message> {
-    state.files.clear();
-    state.selected_indices.clear();
+/// Toggles writing the batch into a single ZIP archive.
+pub fn handle_zip_output(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.zip_output = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Updates output format selection.
-pub fn handle_format_selected(
+/// Sets the ZIP archive path chosen in the save dialog.
+pub fn handle_zip_path_selected(
     state: &mut AppState,
-    format: crate::state::ImageFormat,
+    path_opt: Option<std::path::PathBuf>,
 ) -> Command<Message> {
-    state.options.format = format;
-    settings::save_settings(&state.options);
+    if let Some(path) = path_opt {
+        state.options.zip_path = Some(path);
+        persist_settings(state);
+    }
     Command::none()
 }
 
-/// Updates quality level from slider.
-pub fn handle_quality_changed(state: &mut AppState, q: u8) -> Command<Message> {
-    state.options.quality = q;
-    settings::save_settings(&state.options);
+/// Sets or clears the external sRGB profile, rejecting files lcms2 cannot load as RGB.
+pub fn handle_srgb_profile_selected(
+    state: &mut AppState,
+    path_opt: Option<std::path::PathBuf>,
+) -> Command<Message> {
+    if let Some(path) = &path_opt {
+        if let Err(e) = load_rgb_profile(path) {
+            log::warn!("{}: {:#}", path.display(), e);
+            state.settings_warning = Some(format!(
+                "{} is not a usable RGB profile; keeping the current one",
+                path.display()
+            ));
+            return Command::none();
+        }
+    }
+    state.options.custom_srgb_icc = path_opt;
+    persist_settings(state);
     Command::none()
 }
 
-/// Updates quality level from text input.
-pub fn handle_quality_input(state: &mut AppState, value: String) -> Command<Message> {
-    if let Ok(num) = value.parse::<u8>() {
-        state.options.quality = num.min(100);
-        settings::save_settings(&state.options);
+/// Selects which frame of multi-frame inputs is converted.
+pub fn handle_frame_choice(state: &mut AppState, choice: FrameChoice) -> Command<Message> {
+    state.options.frame_choice = choice;
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Updates the 1-based frame number used by the "Frame #" choice.
+pub fn handle_frame_number(state: &mut AppState, value: String) -> Command<Message> {
+    if let Ok(num) = value.parse::<u32>() {
+        state.options.frame_number = num.max(1);
+        persist_settings(state);
+        return refresh_preview(state);
     }
     Command::none()
 }
 
-/// Toggles PNG compression optimization.
-pub fn handle_png_compression(state: &mut AppState, v: bool) -> Command<Message> {
-    state.options.png_compressed = v;
-    settings::save_settings(&state.options);
+/// Sets the brightness adjustment; it is saved and previewed when the slider is released.
+pub fn handle_brightness(state: &mut AppState, v: i32) -> Command<Message> {
+    state.options.adjustments.brightness = v;
     Command::none()
 }
 
-/// Toggles image resize option.
-pub fn handle_resize_toggled(state: &mut AppState, v: bool) -> Command<Message> {
-    state.options.resize = v;
-    settings::save_settings(&state.options);
+/// Sets the contrast adjustment; it is saved and previewed when the slider is released.
+pub fn handle_contrast(state: &mut AppState, v: i32) -> Command<Message> {
+    state.options.adjustments.contrast = v;
     Command::none()
 }
 
-/// Updates target resize width.
-pub fn handle_width_changed(state: &mut AppState, v: String) -> Command<Message> {
-    if v.chars().all(|c| c.is_numeric()) {
-        state.options.target_width = v;
-        settings::save_settings(&state.options);
-    }
+/// Sets the saturation adjustment; it is saved and previewed when the slider is released.
+pub fn handle_saturation(state: &mut AppState, v: i32) -> Command<Message> {
+    state.options.adjustments.saturation = v;
     Command::none()
 }
 
-/// Updates target resize height.
-pub fn handle_height_changed(state: &mut AppState, v: String) -> Command<Message> {
-    if v.chars().all(|c| c.is_numeric()) {
-        state.options.target_height = v;
-        settings::save_settings(&state.options);
+/// Toggles converting outputs to grayscale.
+pub fn handle_grayscale(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.adjustments.grayscale = v;
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Toggles computing grayscale in linear light.
+pub fn handle_linear_grayscale(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.adjustments.linear_grayscale = v;
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Saves the adjustments settled on and re-renders the preview with them.
+pub fn handle_adjustments_released(state: &mut AppState) -> Command<Message> {
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Clears all adjustments.
+pub fn handle_adjustments_reset(state: &mut AppState) -> Command<Message> {
+    state.options.adjustments = Default::default();
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Re-renders the shown preview after an option that changes the decoded frame.
+fn refresh_preview(state: &mut AppState) -> Command<Message> {
+    state.previews.clear();
+    match state
+        .preview_id
+        .and_then(|id| state.files.iter().position(|f| f.id == id))
+    {
+        Some(index) => request_preview(state, index),
+        None => Command::none(),
     }
+}
+
+/// Replaces all options with their defaults, optionally keeping theme, language, density, and list height.
+pub fn handle_reset_settings(state: &mut AppState, keep_layout: bool) -> Command<Message> {
+    let old = std::mem::take(&mut state.options);
+    state.options.quality = state.options.format.default_quality();
+    state.options.custom_quality = state.options.quality;
+    if keep_layout {
+        state.options.is_dark_mode = old.is_dark_mode;
+        state.options.language = old.language;
+        state.options.ui_density = old.ui_density;
+        state.options.file_list_height = old.file_list_height;
+    }
+    if let Err(e) = settings::reset_settings() {
+        log::error!("Failed to clear settings: {}", e);
+    }
+    state.max_file_size_input = None;
+    persist_settings(state);
+    log::info!("Settings reset to defaults");
+    refresh_preview(state)
+}
+
+/// Toggles releasing allocator memory after each batch.
+pub fn handle_release_memory(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.release_memory = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Updates filename prefix.
-pub fn handle_prefix_changed(state: &mut AppState, v: String) -> Command<Message> {
-    state.options.prefix = v;
-    settings::save_settings(&state.options);
+/// Toggles draft mode; it only lasts for the session, so settings are not saved.
+pub fn handle_draft_mode(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.draft_mode = v;
     Command::none()
 }
 
-/// Updates find pattern for filename replacement.
-pub fn handle_find_pattern(state: &mut AppState, v: String) -> Command<Message> {
-    state.options.find_pattern = v;
-    settings::save_settings(&state.options);
+/// Toggles compositing transparent pixels onto white for formats without alpha.
+pub fn handle_flatten_alpha(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.flatten_alpha = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Updates replacement string for filename pattern.
-pub fn handle_replace_with(state: &mut AppState, v: String) -> Command<Message> {
-    state.options.replace_with = v;
-    settings::save_settings(&state.options);
+/// Toggles EXIF metadata preservation.
+pub fn handle_keep_metadata(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.keep_metadata = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Toggles automatic resolution/quality suffix.
-pub fn handle_auto_suffix(state: &mut AppState, v: bool) -> Command<Message> {
-    state.options.auto_suffix = v;
-    settings::save_settings(&state.options);
+/// Toggles rebuilding the EXIF thumbnail instead of stripping the stale one.
+pub fn handle_regenerate_exif_thumbnail(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.regenerate_exif_thumbnail = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Toggles custom output folder usage.
-pub fn handle_custom_output(state: &mut AppState, v: bool) -> Command<Message> {
-    state.options.use_custom_output = v;
-    settings::save_settings(&state.options);
+/// Toggles keeping the source EXIF orientation tag instead of resetting it.
+pub fn handle_keep_exif_orientation(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.keep_exif_orientation = v;
+    persist_settings(state);
     Command::none()
 }
 
-/// Sets custom output folder path.
-pub fn handle_output_selected(
-    state: &mut AppState,
-    path_opt: Option<std::path::PathBuf>,
-) -> Command<Message> {
-    if let Some(path) = path_opt {
-        state.options.custom_output_path = Some(path);
-        settings::save_settings(&state.options);
+/// Toggles ignoring the EXIF orientation tag, for sources tagged with the wrong rotation.
+pub fn handle_ignore_exif_orientation(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.ignore_exif_orientation = v;
+    persist_settings(state);
+    refresh_preview(state)
+}
+
+/// Toggles deleting sources after a successful conversion.
+pub fn handle_delete_originals(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.delete_originals = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Enables deleting sources once the user accepted the warning.
+pub fn handle_delete_originals_confirmed(state: &mut AppState, yes: bool) -> Command<Message> {
+    if yes {
+        state.options.delete_originals_confirmed = true;
+        state.options.delete_originals = true;
+        persist_settings(state);
     }
     Command::none()
 }
 
-/// Toggles EXIF metadata preservation.
-pub fn handle_keep_metadata(state: &mut AppState, v: bool) -> Command<Message> {
-    state.options.keep_metadata = v;
-    settings::save_settings(&state.options);
+/// Toggles re-decoding of written output for verification.
+pub fn handle_verify_output(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.verify_output = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles the alpha preservation check for lossless outputs.
+pub fn handle_verify_alpha(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.verify_alpha = v;
+    persist_settings(state);
     Command::none()
 }
 
 /// Toggles dataset log file generation.
 pub fn handle_generate_log(state: &mut AppState, v: bool) -> Command<Message> {
     state.options.generate_log = v;
-    settings::save_settings(&state.options);
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles writing a caption `.txt` next to each output image.
+pub fn handle_write_captions(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.write_captions = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates the artist stamped into JPEG EXIF.
+pub fn handle_exif_artist(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.exif_artist = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates the copyright notice stamped into JPEG EXIF.
+pub fn handle_exif_copyright(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.exif_copyright = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles copying non-image sidecar files to the output folder unchanged.
+pub fn handle_copy_sidecars(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.copy_sidecars = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Updates the template caption files are filled from.
+pub fn handle_caption_template(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.caption_template = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Composites every queued file into one contact sheet in the background, then reports
+/// where it was saved.
+pub fn handle_contact_sheet(state: &mut AppState) -> Command<Message> {
+    let inputs: Vec<PathBuf> = state.files.iter().map(|f| f.path.clone()).collect();
+    let Some(first) = inputs.first() else {
+        return Command::none();
+    };
+    let dir = get_output_dir(first, &state.options);
+    let options = state.options.clone();
+    state.contact_sheet_running = true;
+    Command::perform(
+        async move {
+            let count = inputs.len();
+            let result =
+                tokio::task::spawn_blocking(move || write_contact_sheet(&inputs, &options))
+                    .await
+                    .map_err(anyhow::Error::from)
+                    .and_then(|r| r);
+            let (level, description) = match result {
+                Ok(output) => {
+                    log::info!(
+                        "Contact sheet of {} files saved to {}",
+                        count,
+                        dir.display()
+                    );
+                    (
+                        rfd::MessageLevel::Info,
+                        format!(
+                            "A contact sheet of {} files ({}x{}, {}) was saved to {}.",
+                            count,
+                            output.dimensions.0,
+                            output.dimensions.1,
+                            crate::view::format_bytes(output.size),
+                            dir.display()
+                        ),
+                    )
+                }
+                Err(e) => {
+                    log::error!("Contact sheet failed: {:#}", e);
+                    (rfd::MessageLevel::Error, format!("{:#}", e))
+                }
+            };
+            rfd::AsyncMessageDialog::new()
+                .set_title("Contact Sheet")
+                .set_description(description)
+                .set_level(level)
+                .show()
+                .await;
+        },
+        |_| Message::ContactSheetDone,
+    )
+}
+
+/// Updates how many thumbnails a contact sheet row holds.
+pub fn handle_contact_sheet_columns(state: &mut AppState, value: String) -> Command<Message> {
+    if let Ok(num) = value.parse::<u32>() {
+        state.options.contact_sheet_columns = num.max(1);
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Updates the contact sheet cell size; cells below the minimum are enlarged when drawn.
+pub fn handle_contact_sheet_cell(state: &mut AppState, value: String) -> Command<Message> {
+    if let Ok(num) = value.parse::<u32>() {
+        state.options.contact_sheet_cell = num.max(1);
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Updates the contact sheet background; invalid colors fall back to white when drawn.
+pub fn handle_contact_sheet_background(state: &mut AppState, v: String) -> Command<Message> {
+    state.options.contact_sheet_background = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Toggles file names under contact sheet thumbnails.
+pub fn handle_contact_sheet_labels(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.contact_sheet_labels = v;
+    persist_settings(state);
     Command::none()
 }
 
 /// Toggles numbering in log file entries.
 pub fn handle_add_numbering(state: &mut AppState, v: bool) -> Command<Message> {
     state.options.add_numbering = v;
-    settings::save_settings(&state.options);
+    persist_settings(state);
+    Command::none()
+}
+
+/// Hides the settings-save warning banner.
+pub fn handle_settings_warning_dismissed(state: &mut AppState) -> Command<Message> {
+    state.settings_warning = None;
+    Command::none()
+}
+
+/// Hides the failed downloads notice.
+pub fn handle_failed_downloads_dismissed(state: &mut AppState) -> Command<Message> {
+    state.failed_downloads.clear();
+    Command::none()
+}
+
+/// Shows or hides the log panel.
+pub fn handle_log_toggled(state: &mut AppState, v: bool) -> Command<Message> {
+    state.show_log = v;
+    Command::none()
+}
+
+/// Shows or hides the About panel with codec diagnostics.
+pub fn handle_about_toggled(state: &mut AppState, v: bool) -> Command<Message> {
+    state.show_about = v;
+    Command::none()
+}
+
+/// Shows or hides the advanced settings section.
+pub fn handle_advanced_toggled(state: &mut AppState, v: bool) -> Command<Message> {
+    state.show_advanced = v;
+    Command::none()
+}
+
+/// Toggles writing failures.log at the end of each batch.
+pub fn handle_failure_log(state: &mut AppState, v: bool) -> Command<Message> {
+    state.options.failure_log = v;
+    persist_settings(state);
+    Command::none()
+}
+
+/// Keeps the max file size as typed; valid entries apply right away and are saved on Enter.
+pub fn handle_max_file_size(state: &mut AppState, value: String) -> Command<Message> {
+    if let Some(mb) = parse_max_file_size(&value) {
+        state.options.max_file_size_mb = mb;
+    }
+    state.max_file_size_input = Some(value);
+    Command::none()
+}
+
+/// Saves a valid max file size and shows it as stored; an invalid entry stays for editing.
+pub fn handle_max_file_size_submitted(state: &mut AppState) -> Command<Message> {
+    let Some(input) = &state.max_file_size_input else {
+        return Command::none();
+    };
+    if let Some(mb) = parse_max_file_size(input) {
+        state.options.max_file_size_mb = mb;
+        state.max_file_size_input = None;
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Updates the worker thread limit; the rayon pool size applies on next launch.
+pub fn handle_thread_limit(state: &mut AppState, value: String) -> Command<Message> {
+    if let Ok(num) = value.parse::<usize>() {
+        state.options.thread_limit = num.max(1);
+        persist_settings(state);
+    }
+    Command::none()
+}
+
+/// Sets the order batch files are dispatched in.
+pub fn handle_conversion_order(state: &mut AppState, order: ConversionOrder) -> Command<Message> {
+    state.options.conversion_order = order;
+    persist_settings(state);
     Command::none()
 }
 
@@ -231,42 +1884,224 @@ pub fn handle_add_numbering(state: &mut AppState, v: bool) -> Command<Message> {
 pub fn handle_file_converted(
     state: &mut AppState,
     id: uuid::Uuid,
-    result: Result<(), String>,
+    generation: u64,
+    result: Result<ConversionReport, String>,
+    elapsed: Duration,
 ) -> Command<Message> {
-    if let Some(file) = state.files.iter_mut().find(|f| f.id == id) {
+    // Tasks already running when the batch was stopped still report back; their files
+    // were returned to pending then.
+    if generation != state.batch_generation.load(Ordering::SeqCst) {
+        return Command::none();
+    }
+    let mut failed = false;
+    let position = state.files.iter().position(|f| f.id == id);
+    if let Some(file) = position.map(|i| &mut state.files[i]) {
+        file.elapsed = Some(elapsed);
+        state.batch_progress.0 += file.input_bytes().unwrap_or(0);
         match result {
-            Ok(_) => file.status = FileStatus::Done,
-            Err(e) => file.status = FileStatus::Error(e),
+            Ok(report) => {
+                file.status = FileStatus::Done;
+                file.verified = report.verified;
+                file.note = report.note.clone();
+                if let Some(output) = &report.path {
+                    log::info!("{} -> {}", file.path.display(), output.display());
+                }
+                let keep_source = report.skipped || report.sidecar;
+                file.report = Some(report);
+                if state.options.delete_originals && !state.options.zip_output && !keep_source {
+                    let report = file.report.as_ref();
+                    let outputs: Vec<PathBuf> = report
+                        .into_iter()
+                        .flat_map(|r| r.path.iter().chain(&r.extra_paths))
+                        .cloned()
+                        .collect();
+                    if outputs_replace_source(file, &outputs) {
+                        delete_original(file);
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("{}: {}", file.path.display(), e);
+                file.status = FileStatus::Error(e);
+                failed = true;
+            }
         }
     }
-    if !state
-        .files
-        .iter()
-        .any(|f| matches!(f.status, FileStatus::Processing))
+    if failed && state.options.fail_fast && state.is_processing {
+        stop_batch(state);
+    }
+    finish_if_idle(state)
+}
+
+/// Cancels the running batch; files not yet converted are left pending.
+pub fn handle_cancel_conversion(state: &mut AppState) -> Command<Message> {
+    if !state.is_processing {
+        return Command::none();
+    }
+    log::info!("Conversion cancelled");
+    stop_batch(state);
+    finish_if_idle(state)
+}
+
+/// Keeps queued files from being dispatched and returns those in flight to pending; tasks
+/// not yet started skip their work and results of running ones are ignored.
+fn stop_batch(state: &mut AppState) {
+    state.batch_generation.fetch_add(1, Ordering::SeqCst);
+    state.conversion_queue.clear();
+    for file in &mut state.files {
+        if matches!(file.status, FileStatus::Processing) {
+            file.status = FileStatus::Pending;
+        }
+    }
+}
+
+/// Returns a cancelled task's file to the pending state.
+pub fn handle_file_skipped(
+    state: &mut AppState,
+    id: uuid::Uuid,
+    generation: u64,
+) -> Command<Message> {
+    if generation != state.batch_generation.load(Ordering::SeqCst) {
+        return Command::none();
+    }
+    if let Some(file) = state.files.iter_mut().find(|f| f.id == id) {
+        if matches!(file.status, FileStatus::Processing) {
+            file.status = FileStatus::Pending;
+        }
+    }
+    finish_if_idle(state)
+}
+
+/// Whether the written `outputs` can stand in for the source: there is at least one, each
+/// exists, and none of them was written over the source itself.
+fn outputs_replace_source(file: &FileItem, outputs: &[PathBuf]) -> bool {
+    if outputs.is_empty() || !outputs.iter().all(|output| output.is_file()) {
+        log::warn!(
+            "{}: outputs not found, not deleting the original",
+            file.path.display()
+        );
+        return false;
+    }
+    let same_file = outputs.iter().any(|output| {
+        match (
+            std::fs::canonicalize(&file.path),
+            std::fs::canonicalize(output),
+        ) {
+            (Ok(input), Ok(output)) => input == output,
+            _ => file.path == *output,
+        }
+    });
+    if same_file {
+        log::warn!(
+            "{}: output replaced the source, not deleting it",
+            file.path.display()
+        );
+    }
+    !same_file
+}
+
+/// Deletes a converted file's source and notes the outcome on the file.
+fn delete_original(file: &mut FileItem) {
+    let remark = match std::fs::remove_file(&file.path) {
+        Ok(()) => "original deleted".to_string(),
+        Err(e) => {
+            log::error!("Could not delete {}: {}", file.path.display(), e);
+            format!("original kept: {}", e)
+        }
+    };
+    file.note = Some(match file.note.take() {
+        Some(note) => format!("{}, {}", note, remark),
+        None => remark,
+    });
+}
+
+/// Once no file of the running batch is still processing, asks for the next batch or emits
+/// `ConversionFinished` when none is left.
+pub fn finish_if_idle(state: &mut AppState) -> Command<Message> {
+    if state.is_processing
+        && !state
+            .files
+            .iter()
+            .any(|f| matches!(f.status, FileStatus::Processing))
     {
+        if !state.conversion_queue.is_empty() {
+            return Command::perform(async {}, |_| Message::NextBatchReady);
+        }
         state.is_processing = false;
         return Command::perform(async {}, |_| Message::ConversionFinished);
     }
     Command::none()
 }
 
-/// Finalizes conversion batch and triggers memory cleanup.
+/// Finalizes conversion batch and optionally returns freed memory to the OS.
 pub fn handle_conversion_finished(state: &mut AppState) -> Command<Message> {
     state.is_processing = false;
-    settings::save_settings(&state.options);
+    let (input, output) = state
+        .files
+        .iter()
+        .filter(|f| state.in_batch(f) && matches!(f.status, FileStatus::Done))
+        .filter_map(|f| Some((f.input_bytes()?, f.report.as_ref()?.size)))
+        .fold((0, 0), |(i, o), (fi, fo)| (i + fi, o + fo));
+    state.batch_sizes = (input > 0).then_some((input, output));
+    if let Some(sink) = state.zip_sink.take() {
+        match finish_zip_sink(&sink) {
+            Ok(()) => {
+                log::info!("Finished ZIP archive");
+                if state.options.delete_originals {
+                    let scope = &state.batch_scope;
+                    for file in &mut state.files {
+                        let in_batch = scope.as_ref().is_none_or(|ids| ids.contains(&file.id));
+                        let sidecar = file.report.as_ref().is_some_and(|r| r.sidecar);
+                        // Entries are only durable once the archive is finished.
+                        if in_batch && !sidecar && matches!(file.status, FileStatus::Done) {
+                            delete_original(file);
+                        }
+                    }
+                }
+            }
+            Err(e) => log::error!("Failed to finish ZIP archive: {:#}", e),
+        }
+    }
+    let count = |done: bool| {
+        state
+            .files
+            .iter()
+            .filter(|f| state.in_batch(f))
+            .filter(|f| match f.status {
+                FileStatus::Done => done,
+                FileStatus::Error(_) => !done,
+                _ => false,
+            })
+            .count()
+    };
+    let (done, failed) = (count(true), count(false));
+    log::info!("Conversion finished: {} done, {} failed", done, failed);
+    settings::save_last_run(&LastRun {
+        files: done + failed,
+        failed,
+        input_bytes: input,
+        output_bytes: output,
+    });
+    persist_settings(state);
     state.files.shrink_to_fit();
     state.selected_indices.shrink_to(0);
+    if state.options.release_memory {
+        release_allocator_memory();
+    }
+
+    Command::none()
+}
 
+/// Asks mimalloc to return freed pages to the OS; debug builds use the system allocator.
+fn release_allocator_memory() {
     #[cfg(not(debug_assertions))]
     unsafe {
         extern "C" {
             fn mi_collect(force: bool);
-            fn mi_option_set(option: i32, value: i64);
         }
-        mi_option_set(1, 0);
-        mi_collect(true);
         mi_collect(true);
     }
-
-    Command::none()
 }
+
+#[cfg(test)]
+mod tests;
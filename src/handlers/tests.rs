@@ -0,0 +1,72 @@
+//! Checks of batch bookkeeping that touches files on disk.
+
+use super::*;
+use crate::convert::convert_each_format;
+
+/// Scratch folder for one test, removed when dropped.
+struct Scratch(PathBuf);
+
+impl Scratch {
+    fn new() -> Self {
+        let dir = std::env::temp_dir()
+            .join("SimpleImageConverterTests")
+            .join(uuid::Uuid::new_v4().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        Scratch(dir)
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+#[test]
+fn sidecar_in_output_folder_survives_delete_originals() {
+    let scratch = Scratch::new();
+    let sidecar = scratch.0.join("notes.txt");
+    std::fs::write(&sidecar, "caption").unwrap();
+
+    let mut state = AppState::default();
+    state.options.copy_sidecars = true;
+    state.options.delete_originals = true;
+    let item = FileItem::new(sidecar.clone());
+    let id = item.id;
+    state.files.push(item);
+
+    // The default output folder is the input's own, so the sidecar is already there.
+    let report = convert_each_format(&sidecar, &state.options, 0, None).unwrap();
+    assert!(report.sidecar && report.skipped);
+
+    let generation = state.batch_generation.load(Ordering::SeqCst);
+    let _ = handle_file_converted(&mut state, id, generation, Ok(report), Duration::ZERO);
+    assert!(matches!(state.files[0].status, FileStatus::Done));
+    assert_eq!(std::fs::read_to_string(&sidecar).unwrap(), "caption");
+}
+
+#[test]
+fn originals_are_deleted_only_when_every_written_output_exists() {
+    let scratch = Scratch::new();
+    let source = scratch.0.join("photo.png");
+    let written = scratch.0.join("photo (1).jpg");
+    let missing = scratch.0.join("photo.webp");
+    std::fs::write(&written, "jpeg").unwrap();
+
+    let mut state = AppState::default();
+    state.options.delete_originals = true;
+    for (extra_paths, deleted) in [(vec![missing.clone()], false), (Vec::new(), true)] {
+        std::fs::write(&source, "png").unwrap();
+        let item = FileItem::new(source.clone());
+        let id = item.id;
+        state.files = vec![item];
+        let report = ConversionReport {
+            path: Some(written.clone()),
+            extra_paths,
+            ..Default::default()
+        };
+        let generation = state.batch_generation.load(Ordering::SeqCst);
+        let _ = handle_file_converted(&mut state, id, generation, Ok(report), Duration::ZERO);
+        assert_eq!(!source.exists(), deleted);
+    }
+}
@@ -1,22 +1,119 @@
 //! HEIC/HEIF image format decoder using libheif.
 
 use anyhow::Result;
-use image::{DynamicImage, ImageBuffer, Rgba};
-use libheif_rs::{ColorSpace, DecodingOptions, HeifContext, LibHeif, RgbChroma};
+use image::{DynamicImage, ImageBuffer, Rgb, Rgba};
+use libheif_rs::{
+    ColorSpace, CompressionFormat, DecodingOptions, HeifContext, HeifError, HeifErrorCode,
+    HeifErrorSubCode, ImageHandle, LibHeif, Plane, RgbChroma,
+};
 use std::path::Path;
+use std::sync::OnceLock;
+
+/// Decoder plugins libheif reported at startup.
+#[derive(Debug, Clone, Copy)]
+pub struct HeifSupport {
+    pub hevc: bool,
+    pub av1: bool,
+    /// Linked libheif version as `[major, minor, patch]`.
+    pub version: [u8; 3],
+}
+
+/// Probes libheif once for HEVC (HEIC) and AV1 (AVIF) decoders.
+pub fn support() -> HeifSupport {
+    static SUPPORT: OnceLock<HeifSupport> = OnceLock::new();
+    *SUPPORT.get_or_init(|| {
+        let lib_heif = LibHeif::new();
+        let has = |format| !lib_heif.decoder_descriptors(1, Some(format)).is_empty();
+        HeifSupport {
+            hevc: has(CompressionFormat::Hevc),
+            av1: has(CompressionFormat::Av1),
+            version: lib_heif.version(),
+        }
+    })
+}
+
+/// Turns a libheif error into a message that says what the user can do about it.
+fn describe_error(err: HeifError) -> anyhow::Error {
+    let reason = match (err.code, err.sub_code) {
+        (HeifErrorCode::DecoderPluginError | HeifErrorCode::PluginLoadingError, _)
+        | (
+            _,
+            HeifErrorSubCode::UnsupportedCodec
+            | HeifErrorSubCode::PluginIsNotLoaded
+            | HeifErrorSubCode::PluginLoadingError,
+        ) => {
+            "no decoder for this codec is installed (HEIC needs libde265, AVIF needs dav1d or aom)"
+        }
+        (_, HeifErrorSubCode::EndOfData | HeifErrorSubCode::InvalidBoxSize) => {
+            "file is truncated or corrupted"
+        }
+        (HeifErrorCode::UnsupportedFileType | HeifErrorCode::UnsupportedFeature, _)
+        | (
+            _,
+            HeifErrorSubCode::UnsupportedBitDepth
+            | HeifErrorSubCode::UnsupportedColorConversion
+            | HeifErrorSubCode::UnsupportedDataVersion,
+        ) => "unsupported HEIF variant",
+        (HeifErrorCode::InvalidInput, _) => "file is not a valid HEIF image",
+        _ => "decoding failed",
+    };
+    anyhow::anyhow!("Failed to load HEIC: {} ({})", reason, err.message)
+}
+
+/// Reads HEIC/HEIF dimensions from the primary image header without decoding.
+pub fn read_heic_dimensions(path: &Path) -> Result<(u32, u32)> {
+    let path_str = path
+        .to_str()
+        .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
+    let ctx = HeifContext::read_from_file(path_str).map_err(describe_error)?;
+    let handle = ctx.primary_image_handle().map_err(describe_error)?;
+    Ok((handle.width(), handle.height()))
+}
+
+/// Reads the primary image's EXIF block as an APP1-style payload starting with `Exif\0\0`.
+pub fn read_heic_exif(path: &Path) -> Option<Vec<u8>> {
+    let ctx = HeifContext::read_from_file(path.to_str()?).ok()?;
+    let handle = ctx.primary_image_handle().ok()?;
+    let mut ids = [0; 1];
+    if handle.metadata_block_ids(&mut ids, b"Exif") == 0 {
+        return None;
+    }
+    let block = handle.metadata(ids[0]).ok()?;
+
+    // HEIF prefixes the TIFF data with a 4-byte big-endian offset to the TIFF header.
+    let offset = u32::from_be_bytes(block.get(..4)?.try_into().ok()?) as usize;
+    let tiff = block.get(4 + offset..)?;
+    let tiff = tiff.strip_prefix(b"Exif\0\0").unwrap_or(tiff);
+    let mut payload = b"Exif\0\0".to_vec();
+    payload.extend_from_slice(tiff);
+    Some(payload)
+}
+
+/// Reports whether the primary image declares an alpha plane.
+pub fn has_alpha(path: &Path) -> bool {
+    path.to_str()
+        .and_then(|p| HeifContext::read_from_file(p).ok())
+        .and_then(|ctx| ctx.primary_image_handle().ok())
+        .is_some_and(|handle| handle.has_alpha_channel())
+}
 
 /// Decodes HEIC/HEIF image file to DynamicImage.
-pub fn load_heic_via_libheif(path: &Path) -> Result<(DynamicImage, Option<Vec<u8>>)> {
+/// With `high_bit_depth`, sources above 8 bits decode to a 16-bit image instead of 8-bit RGBA.
+pub fn load_heic_via_libheif(
+    path: &Path,
+    high_bit_depth: bool,
+) -> Result<(DynamicImage, Option<Vec<u8>>)> {
     let path_str = path
         .to_str()
         .ok_or_else(|| anyhow::anyhow!("Invalid path encoding"))?;
 
     let lib_heif = LibHeif::new();
-    let ctx = HeifContext::read_from_file(path_str)
-        .map_err(|e| anyhow::anyhow!("Failed to read HEIC file: {}", e))?;
-    let handle = ctx
-        .primary_image_handle()
-        .map_err(|e| anyhow::anyhow!("No primary image: {}", e))?;
+    let ctx = HeifContext::read_from_file(path_str).map_err(describe_error)?;
+    let handle = ctx.primary_image_handle().map_err(describe_error)?;
+
+    if high_bit_depth && handle.luma_bits_per_pixel() > 8 {
+        return Ok((decode_high_bit_depth(&lib_heif, &handle)?, None));
+    }
 
     let image = lib_heif
         .decode(
@@ -24,7 +121,7 @@ pub fn load_heic_via_libheif(path: &Path) -> Result<(DynamicImage, Option<Vec<u8
             ColorSpace::Rgb(RgbChroma::Rgba),
             None::<DecodingOptions>,
         )
-        .map_err(|e| anyhow::anyhow!("Decoding failed: {}", e))?;
+        .map_err(describe_error)?;
 
     let width = image.width();
     let height = image.height();
@@ -32,18 +129,95 @@ pub fn load_heic_via_libheif(path: &Path) -> Result<(DynamicImage, Option<Vec<u8
     let interleaved = planes
         .interleaved
         .ok_or_else(|| anyhow::anyhow!("No interleaved plane found"))?;
-
-    let data = interleaved.data;
-    let stride = interleaved.stride;
-    let mut buffer = Vec::with_capacity((width * height * 4) as usize);
-    for y in 0..height {
-        let start = (y as usize) * stride;
-        let end = start + (width as usize) * 4;
-        buffer.extend_from_slice(&data[start..end]);
-    }
+    let buffer = unpadded_rows(&interleaved, width, height, 32)?;
 
     let img_buffer = ImageBuffer::<Rgba<u8>, Vec<u8>>::from_raw(width, height, buffer)
         .ok_or_else(|| anyhow::anyhow!("Failed to create image buffer"))?;
 
     Ok((DynamicImage::ImageRgba8(img_buffer), None))
 }
+
+/// Copies the rows of an interleaved plane without their stride padding. Fails instead of
+/// panicking when the plane is not `storage_bits` per pixel or its data is too short.
+fn unpadded_rows(
+    plane: &Plane<&[u8]>,
+    width: u32,
+    height: u32,
+    storage_bits: u8,
+) -> Result<Vec<u8>> {
+    if plane.storage_bits_per_pixel != storage_bits
+        || plane.width != width
+        || plane.height != height
+    {
+        anyhow::bail!(
+            "Failed to load HEIC: unexpected plane layout ({}x{} at {} bits per pixel, expected {}x{} at {})",
+            plane.width,
+            plane.height,
+            plane.storage_bits_per_pixel,
+            width,
+            height,
+            storage_bits
+        );
+    }
+    let row_len = width as usize * storage_bits as usize / 8;
+    let needed = match height as usize {
+        0 => Some(0),
+        rows => (rows - 1)
+            .checked_mul(plane.stride)
+            .and_then(|n| n.checked_add(row_len)),
+    };
+    if plane.stride == 0 || plane.stride < row_len || needed.is_none_or(|n| plane.data.len() < n) {
+        anyhow::bail!(
+            "Failed to load HEIC: plane data is too short (stride {}, {} bytes for {} rows of {})",
+            plane.stride,
+            plane.data.len(),
+            height,
+            row_len
+        );
+    }
+    let mut buffer = Vec::with_capacity(row_len * height as usize);
+    for row in plane.data.chunks(plane.stride).take(height as usize) {
+        buffer.extend_from_slice(&row[..row_len]);
+    }
+    Ok(buffer)
+}
+
+/// Decodes to little-endian 16-bit interleaved samples, rescaling the source range to full 16 bits.
+fn decode_high_bit_depth(lib_heif: &LibHeif, handle: &ImageHandle) -> Result<DynamicImage> {
+    let alpha = handle.has_alpha_channel();
+    let chroma = if alpha {
+        RgbChroma::HdrRgbaLe
+    } else {
+        RgbChroma::HdrRgbLe
+    };
+    let image = lib_heif
+        .decode(handle, ColorSpace::Rgb(chroma), None::<DecodingOptions>)
+        .map_err(describe_error)?;
+
+    let (width, height) = (image.width(), image.height());
+    let interleaved = image
+        .planes()
+        .interleaved
+        .ok_or_else(|| anyhow::anyhow!("No interleaved plane found"))?;
+    let bits = interleaved.bits_per_pixel.clamp(9, 16) as u32;
+    let max = (1u32 << bits) - 1;
+    let channels: u8 = if alpha { 4 } else { 3 };
+
+    let bytes = unpadded_rows(&interleaved, width, height, channels * 16)?;
+    let buffer: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|b| {
+            let v = u16::from_le_bytes([b[0], b[1]]) as u32;
+            (v.min(max) * 65535 / max) as u16
+        })
+        .collect();
+
+    let img = if alpha {
+        ImageBuffer::<Rgba<u16>, Vec<u16>>::from_raw(width, height, buffer)
+            .map(DynamicImage::ImageRgba16)
+    } else {
+        ImageBuffer::<Rgb<u16>, Vec<u16>>::from_raw(width, height, buffer)
+            .map(DynamicImage::ImageRgb16)
+    };
+    img.ok_or_else(|| anyhow::anyhow!("Failed to create image buffer"))
+}
@@ -0,0 +1,692 @@
+//! Localized UI strings, one static table per language.
+
+use crate::state::Language;
+use std::sync::OnceLock;
+
+/// Every user-visible label of the main window.
+pub struct Strings {
+    pub title: &'static str,
+    pub dark_mode: &'static str,
+    pub simple_view: &'static str,
+    pub language: &'static str,
+    pub select_files: &'static str,
+    pub url_placeholder: &'static str,
+    pub add_url: &'static str,
+    pub import_url_list: &'static str,
+    pub import_file_list: &'static str,
+    pub cancel_conversion: &'static str,
+    pub start_conversion: &'static str,
+    pub convert_selected: &'static str,
+    pub estimate_savings: &'static str,
+    pub draft_mode: &'static str,
+    pub output_settings: &'static str,
+    pub format: &'static str,
+    pub keep_source_format: &'static str,
+    pub optimize_png: &'static str,
+    pub keep_palette: &'static str,
+    pub heic_high_bit_depth: &'static str,
+    pub quality: &'static str,
+    pub near_lossless_jpeg: &'static str,
+    pub unified_quality: &'static str,
+    pub visual_quality: &'static str,
+    pub png_effort: &'static str,
+    pub keep_metadata: &'static str,
+    pub exif_thumbnail: &'static str,
+    pub keep_orientation: &'static str,
+    pub ignore_orientation: &'static str,
+    pub artist: &'static str,
+    pub copyright: &'static str,
+    pub flatten_alpha: &'static str,
+    pub lossless: &'static str,
+    pub auto_lossless: &'static str,
+    pub keep_animation: &'static str,
+    pub dpi: &'static str,
+    pub dpi_default: &'static str,
+    pub color_space: &'static str,
+    pub input_profiles: &'static str,
+    pub png_color_tag: &'static str,
+    pub multi_frame: &'static str,
+    pub filename_options: &'static str,
+    pub naming_history: &'static str,
+    pub prefix: &'static str,
+    pub prefix_placeholder: &'static str,
+    pub find_replace: &'static str,
+    pub find_placeholder: &'static str,
+    pub replace_placeholder: &'static str,
+    pub template_placeholder: &'static str,
+    pub unknown_tokens: &'static str,
+    pub invalid_date_format: &'static str,
+    pub tokens: &'static str,
+    pub auto_suffix: &'static str,
+    pub extension: &'static str,
+    pub size_letter: &'static str,
+    pub quality_letter: &'static str,
+    pub include_resize_mode: &'static str,
+    pub output: &'static str,
+    pub custom_folder: &'static str,
+    pub same_as_input: &'static str,
+    pub select_folder: &'static str,
+    pub recent: &'static str,
+    pub browse: &'static str,
+    pub output_to_zip: &'static str,
+    pub format_subfolders: &'static str,
+    pub default_zip: &'static str,
+    pub choose: &'static str,
+    pub if_file_exists: &'static str,
+    pub dont_ask: &'static str,
+    pub ask_above: &'static str,
+    pub verify_output: &'static str,
+    pub verify_alpha: &'static str,
+    pub delete_originals: &'static str,
+    pub resize: &'static str,
+    pub enable: &'static str,
+    pub original_size: &'static str,
+    pub max_megapixels: &'static str,
+    pub lock_aspect: &'static str,
+    pub longest_side: &'static str,
+    pub pad_to_fit: &'static str,
+    pub remove_letterbox: &'static str,
+    pub letterbox_tolerance: &'static str,
+    pub adjustments: &'static str,
+    pub reset: &'static str,
+    pub brightness: &'static str,
+    pub contrast: &'static str,
+    pub saturation: &'static str,
+    pub grayscale: &'static str,
+    pub linear_grayscale: &'static str,
+    pub generate_list: &'static str,
+    pub numbering: &'static str,
+    pub generate: &'static str,
+    pub caption_files: &'static str,
+    pub caption_placeholder: &'static str,
+    pub copy_sidecars: &'static str,
+    pub contact_sheet: &'static str,
+    pub columns: &'static str,
+    pub cell_size: &'static str,
+    pub background: &'static str,
+    pub labels: &'static str,
+    pub advanced: &'static str,
+    pub show: &'static str,
+    pub max_file_size: &'static str,
+    pub invalid_max_file_size: &'static str,
+    pub srgb_profile: &'static str,
+    pub bundled_profile: &'static str,
+    pub clear: &'static str,
+    pub threads: &'static str,
+    pub threads_hint: &'static str,
+    pub conversion_order: &'static str,
+    pub output_timestamp: &'static str,
+    pub double_click: &'static str,
+    pub restore_queue: &'static str,
+    pub fail_fast: &'static str,
+    pub release_memory: &'static str,
+    pub failure_log: &'static str,
+    pub copy_cli: &'static str,
+    pub reset_defaults: &'static str,
+    pub files: fn(usize) -> String,
+    pub files_selected: fn(usize, usize) -> String,
+    pub compact: &'static str,
+    pub delete: &'static str,
+    pub clear_all: &'static str,
+    pub drop_hint: &'static str,
+    pub filter_all: &'static str,
+    pub filter_pending: &'static str,
+    pub filter_done: &'static str,
+    pub filter_error: &'static str,
+    pub filter_skipped: &'static str,
+    pub no_matching_files: &'static str,
+    pub preview_unavailable: &'static str,
+    pub crop: &'static str,
+    pub crop_hint: &'static str,
+    pub clear_crop: &'static str,
+    pub edit_externally: &'static str,
+    pub quality_sweep: &'static str,
+    pub sweep_size: &'static str,
+    pub loading_preview: &'static str,
+    pub processing_progress: fn(usize, usize) -> String,
+    pub last_run: fn(usize, usize, &str) -> String,
+    pub saved: &'static str,
+    pub grew: &'static str,
+    pub add_files_first: &'static str,
+    pub ready: &'static str,
+    pub files_ready: fn(usize) -> String,
+    pub dismiss: &'static str,
+    pub downloads_failed: fn(usize) -> String,
+    pub log: &'static str,
+    pub copy_log: &'static str,
+    pub no_events: &'static str,
+    pub about: &'static str,
+    pub copy_diagnostics: &'static str,
+    pub frames: &'static str,
+    pub ok: &'static str,
+    pub verified: &'static str,
+    pub error: &'static str,
+    pub upscale: &'static str,
+    pub not_resized: &'static str,
+}
+
+/// Returns the strings for `language`, resolving `System` from the OS locale.
+pub fn strings(language: Language) -> &'static Strings {
+    match language {
+        Language::System => strings(system_language()),
+        Language::English => &ENGLISH,
+        Language::Indonesian => &INDONESIAN,
+        Language::Spanish => &SPANISH,
+    }
+}
+
+/// Maps the OS locale (e.g. "id-ID" or "es_ES.UTF-8") to a translated language, else English.
+fn system_language() -> Language {
+    static DETECTED: OnceLock<Language> = OnceLock::new();
+    *DETECTED.get_or_init(|| {
+        let locale = sys_locale::get_locale().unwrap_or_default().to_lowercase();
+        match locale.get(..2) {
+            Some("id") | Some("in") => Language::Indonesian,
+            Some("es") => Language::Spanish,
+            _ => Language::English,
+        }
+    })
+}
+
+static ENGLISH: Strings = Strings {
+    title: "Image Converter",
+    dark_mode: "Dark Mode",
+    simple_view: "Simple View",
+    language: "Language",
+    select_files: "+ Select Files",
+    url_placeholder: "Paste image URL...",
+    add_url: "Add URL",
+    import_url_list: "Import URL List",
+    import_file_list: "Import File List",
+    cancel_conversion: "Cancel Conversion",
+    start_conversion: "Start Conversion",
+    convert_selected: "Convert Selected",
+    estimate_savings: "Estimate Savings",
+    draft_mode: "Draft mode (fast, lower quality)",
+    output_settings: "Output Settings",
+    format: "Format",
+    keep_source_format: "Keep source format (JPEG/PNG/WebP)",
+    optimize_png: "Optimize PNG",
+    keep_palette: "Keep palette",
+    heic_high_bit_depth: "16-bit from 10/12-bit HEIC",
+    quality: "Quality",
+    near_lossless_jpeg: "100 = near-lossless JPEG (4:4:4, no smoothing), much larger files",
+    unified_quality: "Unified quality (same look across formats)",
+    visual_quality: "Visual quality",
+    png_effort: "PNG effort",
+    keep_metadata: "Keep Metadata",
+    exif_thumbnail: "Rebuild thumbnail",
+    keep_orientation: "Keep orientation tag",
+    ignore_orientation: "Ignore EXIF orientation",
+    artist: "Artist",
+    copyright: "Copyright",
+    flatten_alpha: "Flatten transparency onto white",
+    lossless: "Lossless",
+    auto_lossless: "Auto lossless for screenshots",
+    keep_animation: "Keep animation",
+    dpi: "DPI",
+    dpi_default: "Default",
+    color_space: "Color space",
+    input_profiles: "Input profiles",
+    png_color_tag: "PNG tag",
+    multi_frame: "Multi-frame files",
+    filename_options: "Filename Options",
+    naming_history: "Recent rules",
+    prefix: "Prefix",
+    prefix_placeholder: "e.g., converted_",
+    find_replace: "Find & Replace",
+    find_placeholder: "Text to find...",
+    replace_placeholder: "Replace with...",
+    template_placeholder: "Template, e.g. {date}_{stem}_{width}x{height} (optional)",
+    unknown_tokens: "Unknown tokens",
+    invalid_date_format: "Invalid date format, using %Y%m%d",
+    tokens: "Tokens",
+    auto_suffix: "Auto Suffix (resolution + quality)",
+    extension: "Extension",
+    size_letter: "Size letter",
+    quality_letter: "Quality letter",
+    include_resize_mode: "Include resize mode",
+    output: "Output",
+    custom_folder: "Custom folder",
+    same_as_input: "Same as input folder",
+    select_folder: "Select folder...",
+    recent: "Recent",
+    browse: "Browse",
+    output_to_zip: "Output to ZIP",
+    format_subfolders: "Sort into format folders (jpg/, webp/)",
+    default_zip: "converted.zip in output folder",
+    choose: "Choose",
+    if_file_exists: "If file exists",
+    dont_ask: "Don't ask",
+    ask_above: "Ask above",
+    verify_output: "Verify output after writing",
+    verify_alpha: "Check alpha on lossless output",
+    delete_originals: "Delete originals on success",
+    resize: "Resize",
+    enable: "Enable",
+    original_size: "Original size",
+    max_megapixels: "Limit to megapixels",
+    lock_aspect: "Lock aspect",
+    longest_side: "Longest side",
+    pad_to_fit: "Pad (letterbox)",
+    remove_letterbox: "Remove black letterbox bars",
+    letterbox_tolerance: "Black tolerance",
+    adjustments: "Adjustments",
+    reset: "Reset",
+    brightness: "Brightness",
+    contrast: "Contrast",
+    saturation: "Saturation",
+    grayscale: "Grayscale",
+    linear_grayscale: "Linear light",
+    generate_list: "Generate list file",
+    numbering: "# Numbering",
+    generate: "Generate",
+    caption_files: "Caption .txt per image",
+    caption_placeholder:
+        "Caption template, e.g. a photo of {stem} (from {original}); blank = empty file",
+    copy_sidecars: "Copy other files (.txt, .json) to the output folder unchanged",
+    contact_sheet: "Contact sheet",
+    columns: "Columns",
+    cell_size: "Cell px",
+    background: "Background",
+    labels: "Labels",
+    advanced: "Advanced",
+    show: "Show",
+    max_file_size: "Max file size (MB)",
+    invalid_max_file_size: "Enter a whole number of MB, at least 1",
+    srgb_profile: "sRGB profile",
+    bundled_profile: "Bundled",
+    clear: "Clear",
+    threads: "Threads",
+    threads_hint: "PNG optimizer threads update after restart",
+    conversion_order: "Conversion order",
+    output_timestamp: "Output file date",
+    double_click: "Double-click a file to",
+    restore_queue: "Restore queue on next launch",
+    fail_fast: "Stop on first error",
+    release_memory: "Release memory after each batch",
+    failure_log: "Write failures.log after each batch",
+    copy_cli: "Copy as CLI command",
+    reset_defaults: "Reset to defaults",
+    files: |total| format!("Files ({})", total),
+    files_selected: |selected, total| format!("Files ({} of {} selected)", selected, total),
+    compact: "Compact",
+    delete: "Delete",
+    clear_all: "Clear All",
+    drop_hint: "Drop files here or click Select Files",
+    filter_all: "All",
+    filter_pending: "Pending",
+    filter_done: "Done",
+    filter_error: "Error",
+    filter_skipped: "Skipped",
+    no_matching_files: "No files match this filter",
+    preview_unavailable: "Preview unavailable",
+    crop: "Crop",
+    crop_hint: "Drag on the preview to crop",
+    clear_crop: "Clear Crop",
+    edit_externally: "Edit Externally",
+    quality_sweep: "Quality sweep",
+    sweep_size: "Size",
+    loading_preview: "Loading preview...",
+    processing_progress: |done, total| format!("Processing {} of {}...", done, total),
+    last_run: |files, failed, saved| {
+        format!(
+            "Last run: {} files, {} failed, saved {}",
+            files, failed, saved
+        )
+    },
+    saved: "Saved",
+    grew: "Grew",
+    add_files_first: "Add files first",
+    ready: "Ready - add files to begin",
+    files_ready: |total| format!("{} files ready", total),
+    dismiss: "Dismiss",
+    downloads_failed: |count| format!("{} download(s) failed:", count),
+    log: "Log",
+    copy_log: "Copy Log",
+    no_events: "No events yet",
+    about: "About",
+    copy_diagnostics: "Copy Diagnostics",
+    frames: "frames",
+    ok: "OK",
+    verified: "verified",
+    error: "ERR",
+    upscale: "Upscale",
+    not_resized: "not resized",
+};
+
+static INDONESIAN: Strings = Strings {
+    title: "Konverter Gambar",
+    dark_mode: "Mode Gelap",
+    simple_view: "Tampilan Sederhana",
+    language: "Bahasa",
+    select_files: "+ Pilih Berkas",
+    url_placeholder: "Tempel URL gambar...",
+    add_url: "Tambah URL",
+    import_url_list: "Impor Daftar URL",
+    import_file_list: "Impor Daftar Berkas",
+    cancel_conversion: "Batalkan Konversi",
+    start_conversion: "Mulai Konversi",
+    convert_selected: "Konversi Terpilih",
+    estimate_savings: "Perkirakan Penghematan",
+    draft_mode: "Mode draf (cepat, kualitas lebih rendah)",
+    output_settings: "Pengaturan Keluaran",
+    format: "Format",
+    keep_source_format: "Pertahankan format asal (JPEG/PNG/WebP)",
+    optimize_png: "Optimalkan PNG",
+    keep_palette: "Pertahankan palet",
+    heic_high_bit_depth: "16-bit dari HEIC 10/12-bit",
+    quality: "Kualitas",
+    near_lossless_jpeg:
+        "100 = JPEG nyaris lossless (4:4:4, tanpa penghalusan), berkas jauh lebih besar",
+    unified_quality: "Kualitas terpadu (tampilan sama antarformat)",
+    visual_quality: "Kualitas visual",
+    png_effort: "Upaya PNG",
+    keep_metadata: "Pertahankan Metadata",
+    exif_thumbnail: "Buat ulang thumbnail",
+    keep_orientation: "Pertahankan tag orientasi",
+    ignore_orientation: "Abaikan orientasi EXIF",
+    artist: "Artis",
+    copyright: "Hak cipta",
+    flatten_alpha: "Ratakan transparansi ke putih",
+    lossless: "Lossless",
+    auto_lossless: "Lossless otomatis untuk tangkapan layar",
+    keep_animation: "Pertahankan animasi",
+    dpi: "DPI",
+    dpi_default: "Bawaan",
+    color_space: "Ruang warna",
+    input_profiles: "Profil masukan",
+    png_color_tag: "Tag PNG",
+    multi_frame: "Berkas multi-bingkai",
+    filename_options: "Opsi Nama Berkas",
+    naming_history: "Aturan terakhir",
+    prefix: "Awalan",
+    prefix_placeholder: "mis., converted_",
+    find_replace: "Cari & Ganti",
+    find_placeholder: "Teks yang dicari...",
+    replace_placeholder: "Ganti dengan...",
+    template_placeholder: "Templat, mis. {date}_{stem}_{width}x{height} (opsional)",
+    unknown_tokens: "Token tidak dikenal",
+    invalid_date_format: "Format tanggal tidak valid, memakai %Y%m%d",
+    tokens: "Token",
+    auto_suffix: "Akhiran Otomatis (resolusi + kualitas)",
+    extension: "Ekstensi",
+    size_letter: "Huruf ukuran",
+    quality_letter: "Huruf kualitas",
+    include_resize_mode: "Sertakan mode ubah ukuran",
+    output: "Keluaran",
+    custom_folder: "Folder khusus",
+    same_as_input: "Sama dengan folder masukan",
+    select_folder: "Pilih folder...",
+    recent: "Terakhir",
+    browse: "Telusuri",
+    output_to_zip: "Keluaran ke ZIP",
+    format_subfolders: "Pisahkan ke folder per format (jpg/, webp/)",
+    default_zip: "converted.zip di folder keluaran",
+    choose: "Pilih",
+    if_file_exists: "Jika berkas ada",
+    dont_ask: "Jangan tanya",
+    ask_above: "Tanya di atas",
+    verify_output: "Verifikasi keluaran setelah ditulis",
+    verify_alpha: "Periksa alfa pada keluaran lossless",
+    delete_originals: "Hapus berkas asli jika berhasil",
+    resize: "Ubah Ukuran",
+    enable: "Aktifkan",
+    original_size: "Ukuran asli",
+    max_megapixels: "Batas megapiksel",
+    lock_aspect: "Kunci rasio",
+    longest_side: "Sisi terpanjang",
+    pad_to_fit: "Isi tepi (letterbox)",
+    remove_letterbox: "Hapus bilah hitam letterbox",
+    letterbox_tolerance: "Toleransi hitam",
+    adjustments: "Penyesuaian",
+    reset: "Atur Ulang",
+    brightness: "Kecerahan",
+    contrast: "Kontras",
+    saturation: "Saturasi",
+    grayscale: "Skala abu-abu",
+    linear_grayscale: "Cahaya linear",
+    generate_list: "Buat berkas daftar",
+    numbering: "# Penomoran",
+    generate: "Buat",
+    caption_files: "Keterangan .txt per gambar",
+    caption_placeholder:
+        "Templat keterangan, mis. foto {stem} (dari {original}); kosong = berkas kosong",
+    copy_sidecars: "Salin berkas lain (.txt, .json) ke folder keluaran tanpa diubah",
+    contact_sheet: "Lembar kontak",
+    columns: "Kolom",
+    cell_size: "Sel px",
+    background: "Latar",
+    labels: "Label",
+    advanced: "Lanjutan",
+    show: "Tampilkan",
+    max_file_size: "Ukuran berkas maks (MB)",
+    invalid_max_file_size: "Masukkan bilangan bulat MB, minimal 1",
+    srgb_profile: "Profil sRGB",
+    bundled_profile: "Bawaan aplikasi",
+    clear: "Hapus",
+    threads: "Thread",
+    threads_hint: "Thread pengoptimal PNG berlaku setelah mulai ulang",
+    conversion_order: "Urutan konversi",
+    output_timestamp: "Tanggal file hasil",
+    double_click: "Klik ganda berkas untuk",
+    restore_queue: "Pulihkan antrean saat dibuka lagi",
+    fail_fast: "Berhenti pada galat pertama",
+    release_memory: "Lepaskan memori setelah tiap batch",
+    failure_log: "Tulis failures.log setelah tiap batch",
+    copy_cli: "Salin sebagai perintah CLI",
+    reset_defaults: "Kembalikan ke bawaan",
+    files: |total| format!("Berkas ({})", total),
+    files_selected: |selected, total| format!("Berkas ({} dari {} dipilih)", selected, total),
+    compact: "Ringkas",
+    delete: "Hapus",
+    clear_all: "Bersihkan Semua",
+    drop_hint: "Letakkan berkas di sini atau klik Pilih Berkas",
+    filter_all: "Semua",
+    filter_pending: "Menunggu",
+    filter_done: "Selesai",
+    filter_error: "Galat",
+    filter_skipped: "Dilewati",
+    no_matching_files: "Tidak ada berkas yang cocok dengan filter ini",
+    preview_unavailable: "Pratinjau tidak tersedia",
+    crop: "Potong",
+    crop_hint: "Seret pada pratinjau untuk memotong",
+    clear_crop: "Hapus Potongan",
+    edit_externally: "Edit di Aplikasi Lain",
+    quality_sweep: "Sapuan kualitas",
+    sweep_size: "Ukuran",
+    loading_preview: "Memuat pratinjau...",
+    processing_progress: |done, total| format!("Memproses {} dari {}...", done, total),
+    last_run: |files, failed, saved| {
+        format!(
+            "Terakhir: {} berkas, {} gagal, hemat {}",
+            files, failed, saved
+        )
+    },
+    saved: "Hemat",
+    grew: "Bertambah",
+    add_files_first: "Tambahkan berkas dulu",
+    ready: "Siap - tambahkan berkas untuk mulai",
+    files_ready: |total| format!("{} berkas siap", total),
+    dismiss: "Tutup",
+    downloads_failed: |count| format!("{} unduhan gagal:", count),
+    log: "Log",
+    copy_log: "Salin Log",
+    no_events: "Belum ada kejadian",
+    about: "Tentang",
+    copy_diagnostics: "Salin Diagnostik",
+    frames: "bingkai",
+    ok: "OK",
+    verified: "terverifikasi",
+    error: "GALAT",
+    upscale: "Diperbesar",
+    not_resized: "tidak diubah ukurannya",
+};
+
+static SPANISH: Strings = Strings {
+    title: "Conversor de Imágenes",
+    dark_mode: "Modo Oscuro",
+    simple_view: "Vista Simple",
+    language: "Idioma",
+    select_files: "+ Seleccionar Archivos",
+    url_placeholder: "Pegar URL de imagen...",
+    add_url: "Añadir URL",
+    import_url_list: "Importar Lista de URL",
+    import_file_list: "Importar Lista de Archivos",
+    cancel_conversion: "Cancelar Conversión",
+    start_conversion: "Iniciar Conversión",
+    convert_selected: "Convertir Selección",
+    estimate_savings: "Estimar Ahorro",
+    draft_mode: "Modo borrador (rápido, menor calidad)",
+    output_settings: "Ajustes de Salida",
+    format: "Formato",
+    keep_source_format: "Conservar formato de origen (JPEG/PNG/WebP)",
+    optimize_png: "Optimizar PNG",
+    keep_palette: "Conservar paleta",
+    heic_high_bit_depth: "16 bits desde HEIC de 10/12 bits",
+    quality: "Calidad",
+    near_lossless_jpeg:
+        "100 = JPEG casi sin pérdida (4:4:4, sin suavizado), archivos mucho más grandes",
+    unified_quality: "Calidad unificada (mismo aspecto entre formatos)",
+    visual_quality: "Calidad visual",
+    png_effort: "Esfuerzo PNG",
+    keep_metadata: "Conservar Metadatos",
+    exif_thumbnail: "Regenerar miniatura",
+    keep_orientation: "Conservar etiqueta de orientación",
+    ignore_orientation: "Ignorar orientación EXIF",
+    artist: "Artista",
+    copyright: "Derechos de autor",
+    flatten_alpha: "Aplanar transparencia sobre blanco",
+    lossless: "Sin pérdida",
+    auto_lossless: "Sin pérdida automático para capturas",
+    keep_animation: "Conservar animación",
+    dpi: "PPP",
+    dpi_default: "Predeterminado",
+    color_space: "Espacio de color",
+    input_profiles: "Perfiles de entrada",
+    png_color_tag: "Etiqueta PNG",
+    multi_frame: "Archivos de varios fotogramas",
+    filename_options: "Opciones de Nombre",
+    naming_history: "Reglas recientes",
+    prefix: "Prefijo",
+    prefix_placeholder: "p. ej., converted_",
+    find_replace: "Buscar y Reemplazar",
+    find_placeholder: "Texto a buscar...",
+    replace_placeholder: "Reemplazar con...",
+    template_placeholder: "Plantilla, p. ej. {date}_{stem}_{width}x{height} (opcional)",
+    unknown_tokens: "Marcadores desconocidos",
+    invalid_date_format: "Formato de fecha no válido, se usa %Y%m%d",
+    tokens: "Marcadores",
+    auto_suffix: "Sufijo Automático (resolución + calidad)",
+    extension: "Extensión",
+    size_letter: "Letra de tamaño",
+    quality_letter: "Letra de calidad",
+    include_resize_mode: "Incluir modo de redimensión",
+    output: "Salida",
+    custom_folder: "Carpeta personalizada",
+    same_as_input: "Misma carpeta que la entrada",
+    select_folder: "Seleccionar carpeta...",
+    recent: "Recientes",
+    browse: "Examinar",
+    output_to_zip: "Salida a ZIP",
+    format_subfolders: "Separar en carpetas por formato (jpg/, webp/)",
+    default_zip: "converted.zip en la carpeta de salida",
+    choose: "Elegir",
+    if_file_exists: "Si el archivo existe",
+    dont_ask: "No preguntar",
+    ask_above: "Preguntar a partir de",
+    verify_output: "Verificar la salida tras escribir",
+    verify_alpha: "Comprobar alfa en salida sin pérdida",
+    delete_originals: "Eliminar originales si tiene éxito",
+    resize: "Redimensionar",
+    enable: "Activar",
+    original_size: "Tamaño original",
+    max_megapixels: "Límite de megapíxeles",
+    lock_aspect: "Bloquear proporción",
+    longest_side: "Lado más largo",
+    pad_to_fit: "Rellenar (letterbox)",
+    remove_letterbox: "Quitar franjas negras (letterbox)",
+    letterbox_tolerance: "Tolerancia de negro",
+    adjustments: "Ajustes de Imagen",
+    reset: "Restablecer",
+    brightness: "Brillo",
+    contrast: "Contraste",
+    saturation: "Saturación",
+    grayscale: "Escala de grises",
+    linear_grayscale: "Luz lineal",
+    generate_list: "Generar archivo de lista",
+    numbering: "# Numeración",
+    generate: "Generar",
+    caption_files: "Descripción .txt por imagen",
+    caption_placeholder:
+        "Plantilla de descripción, p. ej. foto de {stem} (de {original}); vacía = archivo vacío",
+    copy_sidecars: "Copiar otros archivos (.txt, .json) sin cambios a la carpeta de salida",
+    contact_sheet: "Hoja de contactos",
+    columns: "Columnas",
+    cell_size: "Celda px",
+    background: "Fondo",
+    labels: "Etiquetas",
+    advanced: "Avanzado",
+    show: "Mostrar",
+    max_file_size: "Tamaño máx. de archivo (MB)",
+    invalid_max_file_size: "Introduce un número entero de MB, mínimo 1",
+    srgb_profile: "Perfil sRGB",
+    bundled_profile: "Incluido",
+    clear: "Quitar",
+    threads: "Hilos",
+    threads_hint: "Los hilos del optimizador PNG se aplican al reiniciar",
+    conversion_order: "Orden de conversión",
+    output_timestamp: "Fecha del archivo de salida",
+    double_click: "Doble clic en un archivo para",
+    restore_queue: "Restaurar la cola al volver a abrir",
+    fail_fast: "Detener en el primer error",
+    release_memory: "Liberar memoria tras cada lote",
+    failure_log: "Escribir failures.log tras cada lote",
+    copy_cli: "Copiar como comando CLI",
+    reset_defaults: "Restablecer valores predeterminados",
+    files: |total| format!("Archivos ({})", total),
+    files_selected: |selected, total| format!("Archivos ({} de {} seleccionados)", selected, total),
+    compact: "Compacto",
+    delete: "Eliminar",
+    clear_all: "Vaciar Todo",
+    drop_hint: "Suelta archivos aquí o pulsa Seleccionar Archivos",
+    filter_all: "Todos",
+    filter_pending: "Pendientes",
+    filter_done: "Listos",
+    filter_error: "Error",
+    filter_skipped: "Omitidos",
+    no_matching_files: "Ningún archivo coincide con este filtro",
+    preview_unavailable: "Vista previa no disponible",
+    crop: "Recorte",
+    crop_hint: "Arrastra sobre la vista previa para recortar",
+    clear_crop: "Quitar Recorte",
+    edit_externally: "Editar Externamente",
+    quality_sweep: "Barrido de calidad",
+    sweep_size: "Tamaño",
+    loading_preview: "Cargando vista previa...",
+    processing_progress: |done, total| format!("Procesando {} de {}...", done, total),
+    last_run: |files, failed, saved| {
+        format!(
+            "Última ejecución: {} archivos, {} fallidos, ahorrado {}",
+            files, failed, saved
+        )
+    },
+    saved: "Ahorrado",
+    grew: "Aumentó",
+    add_files_first: "Añade archivos primero",
+    ready: "Listo - añade archivos para empezar",
+    files_ready: |total| format!("{} archivos listos", total),
+    dismiss: "Descartar",
+    downloads_failed: |count| format!("{} descarga(s) fallida(s):", count),
+    log: "Registro",
+    copy_log: "Copiar Registro",
+    no_events: "Aún no hay eventos",
+    about: "Acerca de",
+    copy_diagnostics: "Copiar Diagnóstico",
+    frames: "fotogramas",
+    ok: "OK",
+    verified: "verificado",
+    error: "ERR",
+    upscale: "Ampliada",
+    not_resized: "sin redimensionar",
+};
@@ -0,0 +1,53 @@
+//! In-memory ring buffer behind the `log` facade, shown in the log panel.
+
+use crate::constants::LOG_CAPACITY;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+static BUFFER: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+/// Keeps the most recent app log lines; dependency logs (iced, wgpu) are ignored.
+struct RingLogger;
+
+impl Log for RingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= Level::Info && metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        let line = format!(
+            "{} {:<5} {}",
+            chrono::Local::now().format("%H:%M:%S"),
+            record.level(),
+            record.args()
+        );
+        if let Ok(mut buffer) = BUFFER.lock() {
+            if buffer.len() == LOG_CAPACITY {
+                buffer.pop_front();
+            }
+            buffer.push_back(line);
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the ring buffer as the global logger.
+pub fn init() {
+    static LOGGER: RingLogger = RingLogger;
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(LevelFilter::Info);
+    }
+}
+
+/// Returns the buffered lines, oldest first.
+pub fn lines() -> Vec<String> {
+    BUFFER
+        .lock()
+        .map(|b| b.iter().cloned().collect())
+        .unwrap_or_default()
+}
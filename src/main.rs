@@ -2,31 +2,67 @@
 
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod cli;
 mod constants;
 mod convert;
+mod diagnostics;
 mod handlers;
 mod heic;
+mod i18n;
+mod logging;
+mod manifest;
 mod message;
+mod remote;
 mod settings;
 mod state;
 mod theme;
 mod view;
 
-use crate::convert::{convert_image, get_target_filename};
+use crate::constants::{AUTO_SCROLL_TICK_MS, PREFLIGHT_SAMPLES, SUPPORTED_EXTENSIONS};
+use crate::convert::{
+    convert_each_format, convert_image, create_zip_sink, estimate_output_size, format_subfolder,
+    get_output_dir, get_target_filename, has_transparency, max_file_bytes, options_per_output,
+    output_format, output_root_dir, zip_output_path, OutputTarget, SourceFormat,
+};
 use crate::message::Message;
-use crate::state::{AppState, FileStatus};
+use crate::state::{
+    AppState, ConversionOptions, ConversionOrder, ExistingFilePolicy, FileItem, FileStatus,
+};
 use crate::view::view;
 use iced::{executor, Application, Command, Element, Settings, Subscription, Theme};
 
 #[cfg(not(debug_assertions))]
 #[global_allocator]
 static GLOBAL: mimalloc::MiMalloc = mimalloc::MiMalloc;
+use std::collections::HashSet;
 use std::io::Write;
 use std::path::PathBuf;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::Instant;
+
+const FLATTEN_LABEL: &str = "Flatten";
+const KEEP_LABEL: &str = "Convert Anyway";
+const RESET_ALL_LABEL: &str = "Reset All";
+const KEEP_LAYOUT_LABEL: &str = "Keep Layout";
+const PROCEED_LABEL: &str = "Proceed";
 
 /// Application entry point.
 pub fn main() -> iced::Result {
-    ImageConverterApp::run(Settings::default())
+    logging::init();
+
+    // The global pool also drives oxipng, so sizing it caps PNG optimization threads too.
+    let _ = rayon::ThreadPoolBuilder::new()
+        .num_threads(settings::load_settings().thread_limit)
+        .build_global();
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(code) = cli::run(&args) {
+        std::process::exit(code);
+    }
+    let result = ImageConverterApp::run(Settings::with_flags(cli::gui_inputs(&args)));
+    remote::remove_all_downloads();
+    result
 }
 
 struct ImageConverterApp {
@@ -37,13 +73,57 @@ impl Application for ImageConverterApp {
     type Executor = executor::Default;
     type Message = Message;
     type Theme = Theme;
-    type Flags = ();
+    type Flags = Vec<PathBuf>;
 
-    /// Initializes application with saved settings.
-    fn new(_flags: ()) -> (Self, Command<Message>) {
+    /// Initializes application with saved settings, queueing any files it was opened with.
+    fn new(opened: Vec<PathBuf>) -> (Self, Command<Message>) {
         let mut state = AppState::default();
         state.options = settings::load_settings();
-        (ImageConverterApp { state }, Command::none())
+        state.last_run = settings::load_last_run();
+        let heif = heic::support();
+        if !heif.hevc || !heif.av1 {
+            log::warn!(
+                "libheif decoders: HEVC {}, AV1 {}",
+                if heif.hevc { "available" } else { "missing" },
+                if heif.av1 { "available" } else { "missing" }
+            );
+        }
+
+        let saved_queue = if state.options.restore_queue {
+            settings::load_queue()
+        } else {
+            Vec::new()
+        };
+        let command = if saved_queue.is_empty() {
+            Command::none()
+        } else {
+            Command::perform(
+                async move {
+                    let res = rfd::AsyncMessageDialog::new()
+                        .set_title("Restore Queue")
+                        .set_description(format!(
+                            "Restore {} files from the last session?",
+                            saved_queue.len()
+                        ))
+                        .set_buttons(rfd::MessageButtons::YesNo)
+                        .show()
+                        .await;
+                    if res == rfd::MessageDialogResult::Yes {
+                        saved_queue
+                    } else {
+                        Vec::new()
+                    }
+                },
+                // Already confirmed, so large queues skip the size prompt.
+                Message::AddFilesConfirmed,
+            )
+        };
+        let command = if opened.is_empty() {
+            command
+        } else {
+            Command::batch([command, handlers::handle_files_selected(opened)])
+        };
+        (ImageConverterApp { state }, command)
     }
 
     /// Returns window title.
@@ -62,12 +142,31 @@ impl Application for ImageConverterApp {
 
     /// Routes messages to appropriate handlers.
     fn update(&mut self, message: Message) -> Command<Message> {
+        // Pick lists and sliders cannot be disabled, so option edits are also dropped here.
+        if self.state.is_processing && message.edits_options() {
+            return Command::none();
+        }
         match message {
             Message::WindowResized => Command::none(),
             Message::DarkThemeToggled(v) => handlers::handle_dark_theme(&mut self.state, v),
+            Message::SimpleViewToggled(v) => handlers::handle_simple_view(&mut self.state, v),
+            Message::LanguageSelected(v) => handlers::handle_language(&mut self.state, v),
+            Message::CompactListToggled(v) => handlers::handle_compact_list(&mut self.state, v),
             Message::AddFilesClicked => {
-                let dialog = rfd::AsyncFileDialog::new()
-                    .add_filter("Images", &["jpg", "jpeg", "png", "webp", "heic", "heif"]);
+                let extensions: Vec<&str> = SUPPORTED_EXTENSIONS
+                    .iter()
+                    .copied()
+                    .filter(|ext| {
+                        SourceFormat::from_extension(ext).is_some_and(|f| f.is_decodable())
+                    })
+                    .collect();
+                // Content is sniffed on load, so files without an extension can be picked too.
+                let mut dialog = rfd::AsyncFileDialog::new()
+                    .add_filter("Images", &extensions)
+                    .add_filter("All files", &["*"]);
+                if let Some(dir) = &self.state.options.last_input_dir {
+                    dialog = dialog.set_directory(dir);
+                }
                 Command::perform(async move { dialog.pick_files().await }, |files_opt| {
                     Message::FilesSelected(
                         files_opt
@@ -76,34 +175,182 @@ impl Application for ImageConverterApp {
                     )
                 })
             }
-            Message::FilesSelected(paths) => {
-                handlers::handle_files_selected(&mut self.state, paths)
+            Message::FilesSelected(paths) => handlers::handle_files_selected(paths),
+            Message::FileDropped(path) => handlers::handle_file_dropped(&mut self.state, path),
+            Message::DroppedFilesFlushed => handlers::handle_dropped_files_flushed(&mut self.state),
+            Message::AddFilesConfirmed(paths) => handlers::prepare_files(paths),
+            Message::FilesPrepared(items, rest) => {
+                handlers::handle_files_prepared(&mut self.state, items, rest)
             }
-            Message::ExternalFilesDropped(paths) => {
-                handlers::handle_external_files(&mut self.state, paths)
+            Message::UrlInputChanged(v) => handlers::handle_url_input(&mut self.state, v),
+            Message::AddUrlClicked => {
+                let url = std::mem::take(&mut self.state.url_input);
+                self.download_urls(vec![url])
+            }
+            Message::ImportUrlListClicked => {
+                let dialog = rfd::AsyncFileDialog::new().add_filter("URL list", &["txt"]);
+                Command::perform(async move { dialog.pick_file().await }, |h| {
+                    Message::UrlListSelected(h.map(|f| f.path().to_path_buf()))
+                })
+            }
+            Message::UrlListSelected(p) => {
+                let urls = p
+                    .and_then(|p| std::fs::read_to_string(p).ok())
+                    .map(|c| remote::parse_url_list(&c))
+                    .unwrap_or_default();
+                self.download_urls(urls)
+            }
+            Message::ImportFileListClicked => {
+                let dialog = rfd::AsyncFileDialog::new().add_filter("File list", &["txt", "csv"]);
+                Command::perform(async move { dialog.pick_file().await }, |h| {
+                    Message::FileListSelected(h.map(|f| f.path().to_path_buf()))
+                })
+            }
+            Message::FileListSelected(p) => handlers::handle_file_list_selected(p),
+            Message::FileListRead(items, unresolved) => {
+                handlers::handle_file_list_read(&mut self.state, items, unresolved)
+            }
+            Message::FileListReported => Command::none(),
+            Message::UrlDownloaded(url, res) => {
+                handlers::handle_url_downloaded(&mut self.state, url, res)
             }
             Message::ItemDragStarted(i) => handlers::handle_item_drag_started(&mut self.state, i),
-            Message::ItemDropped => handlers::handle_item_dropped(&mut self.state),
+            Message::ItemDropped(id) => handlers::handle_item_dropped(&mut self.state, id),
+            Message::MouseReleased => Command::batch([
+                handlers::handle_list_resize_ended(&mut self.state),
+                handlers::handle_drag_cancelled(&mut self.state),
+                handlers::handle_crop_drag_ended(&mut self.state),
+            ]),
+            Message::CropPointerMoved(x, y) => {
+                handlers::handle_crop_pointer_moved(&mut self.state, x, y)
+            }
+            Message::CropDragStarted => handlers::handle_crop_drag_started(&mut self.state),
+            Message::CropDragEnded => handlers::handle_crop_drag_ended(&mut self.state),
+            Message::CropCleared => handlers::handle_crop_cleared(&mut self.state),
+            Message::EditExternallyClicked(id) => {
+                handlers::handle_edit_externally(&mut self.state, id)
+            }
+            Message::WindowFocused => handlers::handle_window_focused(&mut self.state),
+            Message::CursorMoved(y) => handlers::handle_cursor_moved(&mut self.state, y),
+            Message::FileListScrolled(y, max) => {
+                handlers::handle_file_list_scrolled(&mut self.state, y, max)
+            }
+            Message::FileListPointerMoved(y) => {
+                handlers::handle_file_list_pointer_moved(&mut self.state, y)
+            }
+            Message::DragAutoScrollTick => handlers::handle_drag_auto_scroll(&mut self.state),
+            Message::ListResizeStarted => handlers::handle_list_resize_started(&mut self.state),
             Message::ItemHovered(i) => handlers::handle_item_hovered(&mut self.state, i),
             Message::ToggleSelection(i) => handlers::handle_toggle_selection(&mut self.state, i),
+            Message::FileDoubleClicked(i) => {
+                handlers::handle_file_double_clicked(&mut self.state, i)
+            }
+            Message::PreviewReady(id, preview) => {
+                handlers::handle_preview_ready(&mut self.state, id, preview)
+            }
+            Message::FileInfoReady(id, info) => {
+                handlers::handle_file_info_ready(&mut self.state, id, info)
+            }
             Message::DeleteSelected => handlers::handle_delete_selected(&mut self.state),
             Message::ClearList => handlers::handle_clear_list(&mut self.state),
-            Message::FormatSelected(f) => handlers::handle_format_selected(&mut self.state, f),
+            Message::Undo => handlers::handle_undo(&mut self.state),
+            Message::Redo => handlers::handle_redo(&mut self.state),
+            Message::FormatToggled(f, v) => handlers::handle_format_toggled(&mut self.state, f, v),
+            Message::ToggleKeepSourceFormat(v) => {
+                handlers::handle_keep_source_format(&mut self.state, v)
+            }
             Message::QualityChanged(q) => handlers::handle_quality_changed(&mut self.state, q),
+            Message::SliderReleased => handlers::handle_slider_released(&mut self.state),
             Message::QualityInputChanged(v) => handlers::handle_quality_input(&mut self.state, v),
+            Message::QualityPresetSelected(p) => {
+                handlers::handle_quality_preset(&mut self.state, p)
+            }
+            Message::ToggleUnifiedQuality(v) => {
+                handlers::handle_unified_quality(&mut self.state, v)
+            }
+            Message::PerceptualQualityChanged(q) => {
+                handlers::handle_perceptual_quality(&mut self.state, q)
+            }
             Message::PngCompressionToggled(v) => {
                 handlers::handle_png_compression(&mut self.state, v)
             }
+            Message::ColorSpaceSelected(c) => handlers::handle_color_space(&mut self.state, c),
+            Message::ColorCorrectionSelected(c) => {
+                handlers::handle_color_correction(&mut self.state, c)
+            }
+            Message::PngColorTagSelected(t) => handlers::handle_png_color_tag(&mut self.state, t),
+            Message::KeepPngPaletteToggled(v) => {
+                handlers::handle_keep_png_palette(&mut self.state, v)
+            }
+            Message::HeicHighBitDepthToggled(v) => {
+                handlers::handle_heic_high_bit_depth(&mut self.state, v)
+            }
+            Message::WebpLosslessToggled(v) => handlers::handle_webp_lossless(&mut self.state, v),
+            Message::KeepAnimationToggled(v) => handlers::handle_keep_animation(&mut self.state, v),
+            Message::DpiChanged(v) => handlers::handle_dpi(&mut self.state, v),
+            Message::AutoLosslessToggled(v) => handlers::handle_auto_lossless(&mut self.state, v),
             Message::ResizeToggled(v) => handlers::handle_resize_toggled(&mut self.state, v),
             Message::WidthChanged(v) => handlers::handle_width_changed(&mut self.state, v),
             Message::HeightChanged(v) => handlers::handle_height_changed(&mut self.state, v),
+            Message::ResizeModeSelected(m) => handlers::handle_resize_mode(&mut self.state, m),
+            Message::LongestSideChanged(v) => handlers::handle_longest_side(&mut self.state, v),
+            Message::MaxMegapixelsChanged(v) => handlers::handle_max_megapixels(&mut self.state, v),
+            Message::FormatSubfoldersToggled(v) => {
+                handlers::handle_format_subfolders(&mut self.state, v)
+            }
+            Message::LockAspectToggled(v) => handlers::handle_lock_aspect(&mut self.state, v),
+            Message::PadToFitToggled(v) => handlers::handle_pad_to_fit(&mut self.state, v),
+            Message::PadColorChanged(v) => handlers::handle_pad_color(&mut self.state, v),
+            Message::RemoveLetterboxToggled(v) => {
+                handlers::handle_remove_letterbox(&mut self.state, v)
+            }
+            Message::LetterboxToleranceChanged(v) => {
+                handlers::handle_letterbox_tolerance(&mut self.state, v)
+            }
             Message::PrefixChanged(v) => handlers::handle_prefix_changed(&mut self.state, v),
             Message::FindPatternChanged(v) => handlers::handle_find_pattern(&mut self.state, v),
             Message::ReplaceWithChanged(v) => handlers::handle_replace_with(&mut self.state, v),
             Message::AutoSuffixToggled(v) => handlers::handle_auto_suffix(&mut self.state, v),
+            Message::SuffixSizeLetterChanged(v) => {
+                handlers::handle_suffix_size_letter(&mut self.state, v)
+            }
+            Message::SuffixQualityLetterChanged(v) => {
+                handlers::handle_suffix_quality_letter(&mut self.state, v)
+            }
+            Message::SuffixModeToggled(v) => handlers::handle_suffix_mode(&mut self.state, v),
+            Message::ExistingFilePolicySelected(p) => {
+                handlers::handle_existing_file_policy(&mut self.state, p)
+            }
+            Message::OverwriteWithoutAskingToggled(v) => {
+                handlers::handle_overwrite_without_asking(&mut self.state, v)
+            }
+            Message::OverwriteThresholdChanged(v) => {
+                handlers::handle_overwrite_threshold(&mut self.state, v)
+            }
+            Message::ExtensionCaseSelected(c) => {
+                handlers::handle_extension_case(&mut self.state, c)
+            }
+            Message::OutputTimestampSelected(t) => {
+                handlers::handle_output_timestamp(&mut self.state, t)
+            }
+            Message::DoubleClickActionSelected(a) => {
+                handlers::handle_double_click_action(&mut self.state, a)
+            }
+            Message::FilenameTemplateChanged(v) => {
+                handlers::handle_filename_template(&mut self.state, v)
+            }
+            Message::DateFormatChanged(v) => handlers::handle_date_format(&mut self.state, v),
             Message::ToggleCustomOutput(v) => handlers::handle_custom_output(&mut self.state, v),
             Message::BrowseOutputClicked => {
-                let dialog = rfd::AsyncFileDialog::new();
+                let mut dialog = rfd::AsyncFileDialog::new();
+                let start_dir = self.state.options.custom_output_path.as_ref().or(self
+                    .state
+                    .options
+                    .last_input_dir
+                    .as_ref());
+                if let Some(dir) = start_dir {
+                    dialog = dialog.set_directory(dir);
+                }
                 Command::perform(async move { dialog.pick_folder().await }, |h| {
                     Message::OutputFolderSelected(h.map(|f| f.path().to_path_buf()))
                 })
@@ -111,23 +358,243 @@ impl Application for ImageConverterApp {
             Message::OutputFolderSelected(p) => {
                 handlers::handle_output_selected(&mut self.state, p)
             }
+            Message::BrowseSrgbProfileClicked => {
+                let dialog = rfd::AsyncFileDialog::new().add_filter("ICC profile", &["icc", "icm"]);
+                Command::perform(async move { dialog.pick_file().await }, |h| {
+                    Message::SrgbProfileSelected(h.map(|f| f.path().to_path_buf()))
+                })
+            }
+            Message::SrgbProfileSelected(p) => {
+                handlers::handle_srgb_profile_selected(&mut self.state, p)
+            }
+            Message::SrgbProfileCleared => {
+                handlers::handle_srgb_profile_selected(&mut self.state, None)
+            }
+            Message::ToggleZipOutput(v) => handlers::handle_zip_output(&mut self.state, v),
+            Message::BrowseZipClicked => {
+                let dialog = rfd::AsyncFileDialog::new()
+                    .add_filter("ZIP archive", &["zip"])
+                    .set_file_name("converted.zip");
+                Command::perform(async move { dialog.save_file().await }, |h| {
+                    Message::ZipPathSelected(h.map(|f| f.path().to_path_buf()))
+                })
+            }
+            Message::ZipPathSelected(p) => handlers::handle_zip_path_selected(&mut self.state, p),
+            Message::RecentOutputSelected(p) => {
+                handlers::handle_output_selected(&mut self.state, Some(p))
+            }
+            Message::NamingRuleSelected(rule) => {
+                handlers::handle_naming_rule_selected(&mut self.state, rule)
+            }
             Message::ToggleKeepMetadata(v) => handlers::handle_keep_metadata(&mut self.state, v),
+            Message::ToggleDeleteOriginals(true)
+                if !self.state.options.delete_originals_confirmed =>
+            {
+                Command::perform(
+                    async {
+                        rfd::AsyncMessageDialog::new()
+                            .set_title("Delete Originals")
+                            .set_description(
+                                "Source files will be permanently deleted after their output \
+                                 is written. Turn on Verify Output to only delete once the \
+                                 output decodes. Continue?",
+                            )
+                            .set_level(rfd::MessageLevel::Warning)
+                            .set_buttons(rfd::MessageButtons::YesNo)
+                            .show()
+                            .await
+                            == rfd::MessageDialogResult::Yes
+                    },
+                    Message::DeleteOriginalsConfirmed,
+                )
+            }
+            Message::ToggleDeleteOriginals(v) => {
+                handlers::handle_delete_originals(&mut self.state, v)
+            }
+            Message::DeleteOriginalsConfirmed(yes) => {
+                handlers::handle_delete_originals_confirmed(&mut self.state, yes)
+            }
+            Message::ToggleRegenerateExifThumbnail(v) => {
+                handlers::handle_regenerate_exif_thumbnail(&mut self.state, v)
+            }
+            Message::ToggleKeepExifOrientation(v) => {
+                handlers::handle_keep_exif_orientation(&mut self.state, v)
+            }
+            Message::ToggleIgnoreExifOrientation(v) => {
+                handlers::handle_ignore_exif_orientation(&mut self.state, v)
+            }
+            Message::ToggleVerifyOutput(v) => handlers::handle_verify_output(&mut self.state, v),
+            Message::ToggleVerifyAlpha(v) => handlers::handle_verify_alpha(&mut self.state, v),
             Message::ToggleGenerateLog(v) => handlers::handle_generate_log(&mut self.state, v),
+            Message::ToggleWriteCaptions(v) => handlers::handle_write_captions(&mut self.state, v),
+            Message::CaptionTemplateChanged(v) => {
+                handlers::handle_caption_template(&mut self.state, v)
+            }
+            Message::StatusFilterSelected(filter) => {
+                handlers::handle_status_filter(&mut self.state, filter)
+            }
+            Message::CopySidecarsToggled(v) => handlers::handle_copy_sidecars(&mut self.state, v),
+            Message::ContactSheetClicked => handlers::handle_contact_sheet(&mut self.state),
+            Message::ContactSheetDone => {
+                self.state.contact_sheet_running = false;
+                Command::none()
+            }
+            Message::ContactSheetColumnsChanged(v) => {
+                handlers::handle_contact_sheet_columns(&mut self.state, v)
+            }
+            Message::ContactSheetCellChanged(v) => {
+                handlers::handle_contact_sheet_cell(&mut self.state, v)
+            }
+            Message::ContactSheetBackgroundChanged(v) => {
+                handlers::handle_contact_sheet_background(&mut self.state, v)
+            }
+            Message::ContactSheetLabelsToggled(v) => {
+                handlers::handle_contact_sheet_labels(&mut self.state, v)
+            }
+            Message::ExifArtistChanged(v) => handlers::handle_exif_artist(&mut self.state, v),
+            Message::ExifCopyrightChanged(v) => handlers::handle_exif_copyright(&mut self.state, v),
+            Message::ToggleFailureLog(v) => handlers::handle_failure_log(&mut self.state, v),
             Message::AddNumberingToggled(v) => handlers::handle_add_numbering(&mut self.state, v),
             Message::ManualGenerateLogClicked => {
                 self.generate_log_file();
                 Command::none()
             }
-            Message::ConvertClicked => self.start_conversion(),
-            Message::OverwriteDecision(proceed) => self.process_conversion(proceed),
-            Message::FileConverted(id, res) => {
-                handlers::handle_file_converted(&mut self.state, id, res)
+            Message::AdvancedToggled(v) => handlers::handle_advanced_toggled(&mut self.state, v),
+            Message::LogToggled(v) => handlers::handle_log_toggled(&mut self.state, v),
+            Message::SettingsWarningDismissed => {
+                handlers::handle_settings_warning_dismissed(&mut self.state)
+            }
+            Message::FailedDownloadsDismissed => {
+                handlers::handle_failed_downloads_dismissed(&mut self.state)
+            }
+            Message::CopyLogClicked => iced::clipboard::write(logging::lines().join("\n")),
+            Message::AboutToggled(v) => handlers::handle_about_toggled(&mut self.state, v),
+            Message::CopyDiagnosticsClicked => {
+                iced::clipboard::write(diagnostics::report().join("\n"))
+            }
+            Message::CopyCliClicked => {
+                iced::clipboard::write(cli::command_line(&self.state.options))
+            }
+            Message::ResetSettingsClicked => Command::perform(
+                async {
+                    let res = rfd::AsyncMessageDialog::new()
+                        .set_title("Reset Settings")
+                        .set_description(
+                            "Reset all settings to their defaults? Keep Layout preserves the \
+                             theme, density, and file list height.",
+                        )
+                        .set_level(rfd::MessageLevel::Warning)
+                        .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+                            RESET_ALL_LABEL.to_string(),
+                            KEEP_LAYOUT_LABEL.to_string(),
+                            "Cancel".to_string(),
+                        ))
+                        .show()
+                        .await;
+                    match res {
+                        rfd::MessageDialogResult::Yes => Some(false),
+                        rfd::MessageDialogResult::No => Some(true),
+                        rfd::MessageDialogResult::Custom(label) if label == RESET_ALL_LABEL => {
+                            Some(false)
+                        }
+                        rfd::MessageDialogResult::Custom(label) if label == KEEP_LAYOUT_LABEL => {
+                            Some(true)
+                        }
+                        _ => None,
+                    }
+                },
+                Message::ResetSettingsDecision,
+            ),
+            Message::ResetSettingsDecision(decision) => match decision {
+                Some(keep_layout) => handlers::handle_reset_settings(&mut self.state, keep_layout),
+                None => Command::none(),
+            },
+            Message::ToggleRestoreQueue(v) => handlers::handle_restore_queue(&mut self.state, v),
+            Message::ToggleReleaseMemory(v) => handlers::handle_release_memory(&mut self.state, v),
+            Message::DraftModeToggled(v) => handlers::handle_draft_mode(&mut self.state, v),
+            Message::ToggleFailFast(v) => handlers::handle_fail_fast(&mut self.state, v),
+            Message::MaxFileSizeChanged(v) => handlers::handle_max_file_size(&mut self.state, v),
+            Message::MaxFileSizeSubmitted => {
+                handlers::handle_max_file_size_submitted(&mut self.state)
             }
+            Message::ThreadLimitChanged(v) => handlers::handle_thread_limit(&mut self.state, v),
+            Message::ConversionOrderSelected(o) => {
+                handlers::handle_conversion_order(&mut self.state, o)
+            }
+            Message::ConvertClicked => {
+                if self.state.files.is_empty() {
+                    self.state.empty_queue_hint = true;
+                    return Command::none();
+                }
+                self.state.batch_scope = None;
+                self.start_conversion()
+            }
+            Message::CancelConversion => handlers::handle_cancel_conversion(&mut self.state),
+            Message::ConvertSelectedClicked => {
+                let files = &self.state.files;
+                let ids = self
+                    .state
+                    .selected_indices
+                    .iter()
+                    .filter_map(|&i| files.get(i).map(|f| f.id))
+                    .collect();
+                self.state.batch_scope = Some(ids);
+                self.start_conversion()
+            }
+            Message::QualitySweepClicked => handlers::handle_quality_sweep(&mut self.state),
+            Message::QualitySweepReady(id, result) => {
+                handlers::handle_quality_sweep_ready(&mut self.state, id, result)
+            }
+            Message::PreflightClicked => {
+                self.state.batch_scope = None;
+                self.preflight()
+            }
+            Message::PreflightDecision(proceed) => {
+                self.state.preflight_running = false;
+                if !proceed || self.state.is_processing {
+                    return Command::none();
+                }
+                self.start_conversion()
+            }
+            Message::AlphaDecision(decision) => match decision {
+                Some(flatten) => {
+                    if flatten {
+                        let _ = handlers::handle_flatten_alpha(&mut self.state, true);
+                    }
+                    self.confirm_outputs()
+                }
+                None => Command::none(),
+            },
+            Message::ToggleFlattenAlpha(v) => handlers::handle_flatten_alpha(&mut self.state, v),
+            Message::FrameChoiceSelected(c) => handlers::handle_frame_choice(&mut self.state, c),
+            Message::FrameNumberChanged(v) => handlers::handle_frame_number(&mut self.state, v),
+            Message::BrightnessChanged(v) => handlers::handle_brightness(&mut self.state, v),
+            Message::ContrastChanged(v) => handlers::handle_contrast(&mut self.state, v),
+            Message::SaturationChanged(v) => handlers::handle_saturation(&mut self.state, v),
+            Message::GrayscaleToggled(v) => handlers::handle_grayscale(&mut self.state, v),
+            Message::LinearGrayscaleToggled(v) => {
+                handlers::handle_linear_grayscale(&mut self.state, v)
+            }
+            Message::AdjustmentsReleased => handlers::handle_adjustments_released(&mut self.state),
+            Message::AdjustmentsReset => handlers::handle_adjustments_reset(&mut self.state),
+            Message::OverwriteDecision(proceed) => self.process_conversion(proceed),
+            Message::FileConverted(id, generation, res, elapsed) => Command::batch([
+                handlers::handle_file_converted(&mut self.state, id, generation, res, elapsed),
+                handlers::pin_file_list_scroll(&self.state),
+            ]),
+            Message::FileSkipped(id, generation) => Command::batch([
+                handlers::handle_file_skipped(&mut self.state, id, generation),
+                handlers::pin_file_list_scroll(&self.state),
+            ]),
+            Message::NextBatchReady => self.dispatch_next_batch(),
             Message::ConversionFinished => {
                 let cmd = handlers::handle_conversion_finished(&mut self.state);
                 if self.state.options.generate_log {
                     self.generate_log_file();
                 }
+                if self.state.options.failure_log {
+                    self.write_failure_log();
+                }
                 cmd
             }
         }
@@ -140,48 +607,264 @@ impl Application for ImageConverterApp {
 
     /// Subscribes to window events for drag-drop and keyboard.
     fn subscription(&self) -> Subscription<Message> {
-        iced::event::listen().map(|event| match event {
+        // Ticks only while a row is dragged, to scroll the list when it nears an edge.
+        let auto_scroll = if self.state.dragging_id.is_some() {
+            iced::time::every(std::time::Duration::from_millis(AUTO_SCROLL_TICK_MS))
+                .map(|_| Message::DragAutoScrollTick)
+        } else {
+            Subscription::none()
+        };
+        let events = iced::event::listen().map(|event| match event {
             iced::Event::Window(_, iced::window::Event::FileDropped(path)) => {
-                Message::ExternalFilesDropped(vec![path])
+                Message::FileDropped(path)
             }
+            iced::Event::Window(_, iced::window::Event::Focused) => Message::WindowFocused,
             iced::Event::Mouse(iced::mouse::Event::ButtonReleased(iced::mouse::Button::Left)) => {
-                Message::ItemDropped
+                Message::MouseReleased
+            }
+            iced::Event::Mouse(iced::mouse::Event::CursorMoved { position }) => {
+                Message::CursorMoved(position.y)
             }
             iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
                 key: iced::keyboard::Key::Named(iced::keyboard::key::Named::Delete),
                 ..
             }) => Message::DeleteSelected,
+            iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key: iced::keyboard::Key::Character(c),
+                modifiers,
+                ..
+            }) if modifiers.command() && c.eq_ignore_ascii_case("z") => {
+                if modifiers.shift() {
+                    Message::Redo
+                } else {
+                    Message::Undo
+                }
+            }
+            iced::Event::Keyboard(iced::keyboard::Event::KeyPressed {
+                key: iced::keyboard::Key::Character(c),
+                modifiers,
+                ..
+            }) if modifiers.command() && c.eq_ignore_ascii_case("y") => Message::Redo,
             _ => Message::WindowResized,
-        })
+        });
+        Subscription::batch([events, auto_scroll])
     }
 }
 
 impl ImageConverterApp {
-    /// Checks for file collisions and prompts user before conversion.
+    /// Warns when transparent inputs would lose their alpha, then runs the output checks.
     fn start_conversion(&self) -> Command<Message> {
-        let mut collision_count = 0;
-        for file_item in &self.state.files {
-            let target_name = get_target_filename(&file_item.path, &self.state.options);
-            let parent = if self.state.options.use_custom_output {
-                self.state
-                    .options
-                    .custom_output_path
-                    .as_ref()
-                    .filter(|p| p.exists())
-                    .map(|p| p.as_path())
-                    .unwrap_or_else(|| file_item.path.parent().unwrap_or(std::path::Path::new(".")))
-            } else {
-                file_item.path.parent().unwrap_or(std::path::Path::new("."))
-            };
-            if parent.join(&target_name).exists() {
-                collision_count += 1;
-            }
+        let options = &self.state.options;
+        let all_alpha = std::iter::once(options.format)
+            .chain(options.extra_formats.iter().copied())
+            .all(|f| f.supports_alpha());
+        if (all_alpha && !options.keep_source_format) || options.flatten_alpha {
+            return self.confirm_outputs();
+        }
+        let paths: Vec<PathBuf> = self
+            .state
+            .files
+            .iter()
+            .filter(|f| self.state.in_batch(f))
+            .map(|f| f.path.clone())
+            .collect();
+        let format = options.format;
+        let options = options.clone();
+        Command::perform(
+            async move {
+                let transparent = tokio::task::spawn_blocking(move || {
+                    paths
+                        .iter()
+                        .filter(|p| {
+                            options_per_output(p, &options)
+                                .iter()
+                                .any(|o| !output_format(p, o).supports_alpha())
+                        })
+                        .filter(|p| has_transparency(p))
+                        .count()
+                })
+                .await
+                .unwrap_or(0);
+                if transparent == 0 {
+                    return Some(false);
+                }
+                let res = rfd::AsyncMessageDialog::new()
+                    .set_title("Transparency Will Be Lost")
+                    .set_description(format!(
+                        "{} files have transparency, which {} cannot store. \
+                         Flatten them onto white, or cancel to pick a different format.",
+                        transparent, format
+                    ))
+                    .set_level(rfd::MessageLevel::Warning)
+                    .set_buttons(rfd::MessageButtons::YesNoCancelCustom(
+                        FLATTEN_LABEL.to_string(),
+                        KEEP_LABEL.to_string(),
+                        "Cancel".to_string(),
+                    ))
+                    .show()
+                    .await;
+                match res {
+                    rfd::MessageDialogResult::Yes => Some(true),
+                    rfd::MessageDialogResult::No => Some(false),
+                    rfd::MessageDialogResult::Custom(label) if label == FLATTEN_LABEL => Some(true),
+                    rfd::MessageDialogResult::Custom(label) if label == KEEP_LABEL => Some(false),
+                    _ => None,
+                }
+            },
+            Message::AlphaDecision,
+        )
+    }
+
+    /// Converts a few representative files in memory, extrapolates the queue's output size,
+    /// and asks whether to start the batch.
+    fn preflight(&mut self) -> Command<Message> {
+        let mut files: Vec<(usize, PathBuf, ConversionOptions, u64)> = self
+            .state
+            .files
+            .iter()
+            .enumerate()
+            .filter_map(|(index, file)| {
+                let options = ConversionOptions {
+                    crop: file.crop,
+                    ..self.state.options.clone()
+                };
+                Some((index, file.path.clone(), options, file.input_bytes()?))
+            })
+            .collect();
+        if files.is_empty() {
+            return Command::none();
         }
+        self.state.preflight_running = true;
+        let total_files = files.len();
+        let total_input: u64 = files.iter().map(|f| f.3).sum();
+        // Spread the samples across the size range so a few huge files don't skew the ratio.
+        files.sort_by_key(|f| f.3);
+        let count = PREFLIGHT_SAMPLES.min(total_files);
+        let samples: Vec<_> = (0..count)
+            .map(|i| files[(2 * i + 1) * total_files / (2 * count)].clone())
+            .collect();
 
-        if collision_count > 0 {
-            Command::perform(
-                async move {
+        Command::perform(
+            async move {
+                let sampled = tokio::task::spawn_blocking(move || {
+                    samples
+                        .iter()
+                        .filter_map(|(index, path, options, input)| {
+                            let mut sink = std::io::sink();
+                            match convert_image(
+                                path,
+                                options,
+                                *index,
+                                OutputTarget::Stream(&mut sink),
+                            ) {
+                                Ok(output) => Some((*input, output.size)),
+                                Err(e) => {
+                                    log::warn!("Preflight skipped {}: {:#}", path.display(), e);
+                                    None
+                                }
+                            }
+                        })
+                        .fold((0, 0_u64, 0_u64), |(n, i, o), (input, output)| {
+                            (n + 1, i + input, o + output)
+                        })
+                })
+                .await
+                .unwrap_or((0, 0, 0));
+                let (converted, sample_input, sample_output) = sampled;
+                if converted == 0 || sample_input == 0 {
                     rfd::AsyncMessageDialog::new()
+                        .set_title("Estimated Savings")
+                        .set_description("None of the sampled files could be converted.")
+                        .set_level(rfd::MessageLevel::Warning)
+                        .show()
+                        .await;
+                    return false;
+                }
+                let estimated =
+                    (total_input as f64 * sample_output as f64 / sample_input as f64) as u64;
+                let change = if estimated <= total_input {
+                    format!(
+                        "saving {} ({:.0}%)",
+                        view::format_bytes(total_input - estimated),
+                        (total_input - estimated) as f64 * 100.0 / total_input as f64
+                    )
+                } else {
+                    format!(
+                        "{} larger than the sources",
+                        view::format_bytes(estimated - total_input)
+                    )
+                };
+                let res = rfd::AsyncMessageDialog::new()
+                    .set_title("Estimated Savings")
+                    .set_description(format!(
+                        "Converted {} of {} files in memory at the current settings.\n\n\
+                         Estimated output: {} from {}, {}.",
+                        converted,
+                        total_files,
+                        view::format_bytes(estimated),
+                        view::format_bytes(total_input),
+                        change
+                    ))
+                    .set_buttons(rfd::MessageButtons::OkCancelCustom(
+                        PROCEED_LABEL.to_string(),
+                        "Cancel".to_string(),
+                    ))
+                    .show()
+                    .await;
+                match res {
+                    rfd::MessageDialogResult::Ok => true,
+                    rfd::MessageDialogResult::Custom(label) => label == PROCEED_LABEL,
+                    _ => false,
+                }
+            },
+            Message::PreflightDecision,
+        )
+    }
+
+    /// Checks for file collisions and free disk space off the UI thread, since naming and
+    /// size estimates read every input, then prompts before conversion.
+    fn confirm_outputs(&self) -> Command<Message> {
+        let inputs: Vec<(usize, PathBuf)> = self
+            .state
+            .files
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| self.state.in_batch(f))
+            .map(|(index, f)| (index, f.path.clone()))
+            .collect();
+        let options = self.state.options.clone();
+        Command::perform(
+            async move {
+                let checks = tokio::task::spawn_blocking(move || output_checks(&inputs, &options))
+                    .await
+                    .unwrap_or_default();
+                let (collision_count, disk_warning, duplicate_warning) = checks;
+                if let Some(description) = disk_warning {
+                    let res = rfd::AsyncMessageDialog::new()
+                        .set_title("Low Disk Space")
+                        .set_description(&description)
+                        .set_level(rfd::MessageLevel::Warning)
+                        .set_buttons(rfd::MessageButtons::YesNo)
+                        .show()
+                        .await;
+                    if res != rfd::MessageDialogResult::Yes {
+                        return false;
+                    }
+                }
+                if let Some(description) = duplicate_warning {
+                    let res = rfd::AsyncMessageDialog::new()
+                        .set_title("Duplicate Output Names")
+                        .set_description(&description)
+                        .set_level(rfd::MessageLevel::Warning)
+                        .set_buttons(rfd::MessageButtons::YesNo)
+                        .show()
+                        .await;
+                    if res != rfd::MessageDialogResult::Yes {
+                        return false;
+                    }
+                }
+                if collision_count > 0 {
+                    let res = rfd::AsyncMessageDialog::new()
                         .set_title("Confirm Overwrite")
                         .set_description(&format!(
                             "{} files already exist. Overwrite?",
@@ -189,57 +872,219 @@ impl ImageConverterApp {
                         ))
                         .set_buttons(rfd::MessageButtons::YesNo)
                         .show()
-                        .await
-                },
-                |res| Message::OverwriteDecision(res == rfd::MessageDialogResult::Yes),
-            )
-        } else {
-            Command::perform(async {}, |_| Message::OverwriteDecision(true))
-        }
+                        .await;
+                    return res == rfd::MessageDialogResult::Yes;
+                }
+                true
+            },
+            Message::OverwriteDecision,
+        )
     }
 
-    /// Spawns async conversion tasks for all files.
+    /// Spawns async conversion tasks for the files in the current batch.
     fn process_conversion(&mut self, proceed: bool) -> Command<Message> {
-        if !proceed {
+        // The output checks run in the background, so a second click can finish after the first.
+        if !proceed || self.state.is_processing {
             return Command::none();
         }
 
+        self.state.zip_sink = None;
+        let first_in_batch = self.state.files.iter().find(|f| self.state.in_batch(f));
+        if let (true, Some(first)) = (self.state.options.zip_output, first_in_batch) {
+            let zip_path = zip_output_path(&self.state.options, &first.path);
+            match create_zip_sink(&zip_path) {
+                Ok(sink) => self.state.zip_sink = Some(Arc::new(sink)),
+                Err(e) => {
+                    let message = format!("{:#}", e);
+                    log::error!("{}", message);
+                    let scope = &self.state.batch_scope;
+                    for file in &mut self.state.files {
+                        if scope.as_ref().is_some_and(|ids| !ids.contains(&file.id)) {
+                            continue;
+                        }
+                        file.status = FileStatus::Error(message.clone());
+                    }
+                    return Command::none();
+                }
+            }
+        }
+
         self.state.is_processing = true;
+        handlers::remember_naming_rule(&mut self.state);
+        let scope = &self.state.batch_scope;
         for file in &mut self.state.files {
-            file.status = FileStatus::Processing;
+            if scope.as_ref().is_some_and(|ids| !ids.contains(&file.id)) {
+                continue;
+            }
+            file.status = FileStatus::Pending;
+            file.report = None;
+            file.elapsed = None;
+            file.verified = false;
+            file.note = None;
         }
-
-        let options = self.state.options.clone();
-        let batch_size = self
+        log::info!(
+            "Converting {} files to {}",
+            self.state
+                .files
+                .iter()
+                .filter(|f| self.state.in_batch(f))
+                .count(),
+            self.state.options.format
+        );
+        self.state.batch_sizes = None;
+        self.state.last_run = None;
+        let total_bytes = self
             .state
-            .options
-            .max_batch_size
-            .min(self.state.files.len());
+            .files
+            .iter()
+            .filter(|f| self.state.in_batch(f))
+            .filter_map(|f| f.input_bytes())
+            .sum();
+        self.state.batch_progress = (0, total_bytes);
 
-        let commands: Vec<Command<Message>> = self
+        // Only dispatch is reordered; `index` keeps the listed position for `{index}` names.
+        let mut queue: Vec<&FileItem> = self
             .state
             .files
             .iter()
-            .take(batch_size)
-            .map(|file| {
-                let id = file.id;
+            .filter(|file| self.state.in_batch(file))
+            .collect();
+        let size = |file: &FileItem| file.info.map(|info| info.size_bytes);
+        match self.state.options.conversion_order {
+            ConversionOrder::AsListed => {}
+            ConversionOrder::SmallestFirst => {
+                queue.sort_by_key(|file| size(file).unwrap_or(u64::MAX))
+            }
+            ConversionOrder::LargestFirst => {
+                queue.sort_by_key(|file| std::cmp::Reverse(size(file).unwrap_or(0)))
+            }
+        }
+        self.state.conversion_queue = queue.into_iter().map(|file| file.id).collect();
+        self.dispatch_next_batch()
+    }
+
+    /// Starts converting the next `max_batch_size` queued files; the batch after it is
+    /// dispatched once all of these have finished.
+    fn dispatch_next_batch(&mut self) -> Command<Message> {
+        let options = self.state.options.clone();
+        let zip_sink = self.state.zip_sink.clone();
+        let generation = self.state.batch_generation.clone();
+        let spawned_at = generation.load(Ordering::SeqCst);
+        let permits = Arc::new(tokio::sync::Semaphore::new(options.thread_limit.max(1)));
+        let batch_size = options
+            .max_batch_size
+            .max(1)
+            .min(self.state.conversion_queue.len());
+        let ids: Vec<uuid::Uuid> = self.state.conversion_queue.drain(..batch_size).collect();
+
+        let commands: Vec<Command<Message>> = ids
+            .into_iter()
+            .filter_map(|id| {
+                // Files removed from the list since the run started are dropped.
+                let index = self.state.files.iter().position(|f| f.id == id)?;
+                let file = &mut self.state.files[index];
+                file.status = FileStatus::Processing;
                 let path = file.path.clone();
-                let opts = options.clone();
+                let opts = ConversionOptions {
+                    crop: file.crop,
+                    ..options.clone()
+                };
+                let zip_sink = zip_sink.clone();
+                let generation = generation.clone();
+                let permits = permits.clone();
+                Some(Command::perform(
+                    async move {
+                        let _permit = permits.acquire_owned().await.ok();
+                        let started = Instant::now();
+                        tokio::task::spawn_blocking(move || {
+                            if generation.load(Ordering::SeqCst) != spawned_at {
+                                return None;
+                            }
+                            Some(convert_each_format(
+                                &path,
+                                &opts,
+                                index,
+                                zip_sink.as_deref(),
+                            ))
+                        })
+                        .await
+                        .unwrap_or_else(|e| Some(Err(anyhow::anyhow!(panic_message(e)))))
+                        .map(|res| (res.map_err(|e| format!("{:#}", e)), started.elapsed()))
+                    },
+                    move |res| match res {
+                        Some((res, elapsed)) => {
+                            Message::FileConverted(id, spawned_at, res, elapsed)
+                        }
+                        None => Message::FileSkipped(id, spawned_at),
+                    },
+                ))
+            })
+            .collect();
+
+        // The rows just switched to Processing; keep the list where the user left it.
+        Command::batch(commands.into_iter().chain([
+            handlers::pin_file_list_scroll(&self.state),
+            handlers::finish_if_idle(&mut self.state),
+        ]))
+    }
+
+    /// Spawns downloads for each URL, enqueuing results as they complete.
+    fn download_urls(&self, urls: Vec<String>) -> Command<Message> {
+        let max_bytes = max_file_bytes(&self.state.options);
+        let commands: Vec<Command<Message>> = urls
+            .into_iter()
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .map(|url| {
                 Command::perform(
                     async move {
-                        let res = tokio::task::spawn_blocking(move || convert_image(&path, &opts))
-                            .await
-                            .expect("Task panicked");
-                        (id, res.map_err(|e| e.to_string()))
+                        let res = remote::download_image(url.clone(), max_bytes).await;
+                        (url, res.map_err(|e| format!("{:#}", e)))
                     },
-                    |(id, res)| Message::FileConverted(id, res),
+                    |(url, res)| Message::UrlDownloaded(url, res),
                 )
             })
             .collect();
-
         Command::batch(commands)
     }
 
+    /// Writes `failures.log` (input path and error, tab-separated) for scripts watching the output.
+    fn write_failure_log(&self) {
+        // Errors left over from earlier batches are not this batch's failures.
+        let batch: Vec<&FileItem> = self
+            .state
+            .files
+            .iter()
+            .filter(|f| self.state.in_batch(f))
+            .collect();
+        let failures: Vec<(&PathBuf, &String)> = batch
+            .iter()
+            .filter_map(|f| match &f.status {
+                FileStatus::Error(e) => Some((&f.path, e)),
+                _ => None,
+            })
+            .collect();
+        let Some(first) = failures
+            .first()
+            .map(|(p, _)| *p)
+            .or_else(|| batch.first().map(|f| &f.path))
+        else {
+            return;
+        };
+        let log_path = output_root_dir(first, &self.state.options).join("failures.log");
+
+        // A stale log from an earlier batch would report failures that no longer exist.
+        if failures.is_empty() {
+            let _ = std::fs::remove_file(log_path);
+            return;
+        }
+        if let Ok(mut file) = std::fs::File::create(log_path) {
+            for (path, error) in failures {
+                let _ = writeln!(file, "{}\t{}", path.display(), error);
+            }
+        }
+    }
+
     /// Generates dataset log file with converted filenames.
     fn generate_log_file(&self) {
         let target_dir = if self.state.options.use_custom_output {
@@ -252,16 +1097,139 @@ impl ImageConverterApp {
             PathBuf::from(".")
         };
 
-        if let Ok(mut file) = std::fs::File::create(target_dir.join("dataset_log.txt")) {
-            for (i, file_item) in self.state.files.iter().enumerate() {
-                let target_name = get_target_filename(&file_item.path, &self.state.options);
-                let line = if self.state.options.add_numbering {
-                    format!("{}. {}", i + 1, target_name)
-                } else {
-                    target_name
-                };
-                let _ = writeln!(file, "{}", line);
+        let log_path = target_dir.join("dataset_log.txt");
+        let mut file = match std::fs::File::create(&log_path) {
+            Ok(file) => file,
+            Err(e) => {
+                log::error!("Could not write {}: {}", log_path.display(), e);
+                return;
             }
+        };
+        for (i, file_item) in self.state.files.iter().enumerate() {
+            let target_name = get_target_filename(&file_item.path, &self.state.options, i);
+            let line = if self.state.options.add_numbering {
+                format!("{}. {}", i + 1, target_name)
+            } else {
+                target_name
+            };
+            if let Err(e) = writeln!(file, "{}", line) {
+                log::error!("Could not write {}: {}", log_path.display(), e);
+                return;
+            }
+        }
+        log::info!("Wrote {}", log_path.display());
+    }
+}
+
+/// Counts existing files a batch would replace and builds the low-disk and duplicate-name
+/// warnings. Reads every input, so it runs on a blocking thread.
+fn output_checks(
+    inputs: &[(usize, PathBuf)],
+    options: &ConversionOptions,
+) -> (usize, Option<String>, Option<String>) {
+    let mut collision_count = 0;
+    let mut duplicate_count = 0;
+    let mut batch_targets = HashSet::new();
+    let mut estimated_bytes: u64 = 0;
+    let mut output_dirs: Vec<PathBuf> = Vec::new();
+    for (index, path) in inputs {
+        for output_options in options_per_output(path, options) {
+            let target_name = get_target_filename(path, &output_options, *index);
+            let parent = get_output_dir(path, &output_options);
+            let entry = if options.zip_output {
+                let folder = format_subfolder(path, &output_options);
+                folder
+                    .map_or_else(PathBuf::new, PathBuf::from)
+                    .join(&target_name)
+            } else {
+                parent.join(&target_name)
+            };
+            // Windows and macOS file systems ignore case, so `a.webp` and `A.webp` collide there.
+            let entry = if cfg!(any(windows, target_os = "macos")) {
+                entry.to_string_lossy().to_lowercase()
+            } else {
+                entry.to_string_lossy().to_string()
+            };
+            if !batch_targets.insert(entry) {
+                duplicate_count += 1;
+            }
+            let target = if options.zip_output {
+                zip_output_path(options, path)
+            } else {
+                parent.join(&target_name)
+            };
+            // Skip and Rename never replace loose files, but the archive is always replaced.
+            let replaces =
+                options.zip_output || options.existing_file_policy == ExistingFilePolicy::Overwrite;
+            if output_dirs.is_empty() || !options.zip_output {
+                if replaces && target.exists() {
+                    collision_count += 1;
+                }
+                let dir = target.parent().map(PathBuf::from).unwrap_or(parent);
+                if !output_dirs.contains(&dir) {
+                    output_dirs.push(dir);
+                }
+            }
+            estimated_bytes += estimate_output_size(path, &output_options);
         }
     }
+
+    // Warn when any destination volume has less than ~10% headroom over the estimate.
+    let disk_warning = output_dirs.iter().find_map(|dir| {
+        let available = fs2::available_space(dir).ok()?;
+        (available < estimated_bytes + estimated_bytes / 10).then(|| {
+            format!(
+                "Estimated output is {} MB but only {} MB is free on {}. Continue anyway?",
+                estimated_bytes / (1024 * 1024),
+                available / (1024 * 1024),
+                dir.display()
+            )
+        })
+    });
+
+    if options.overwrite_without_asking || collision_count <= options.overwrite_prompt_threshold {
+        collision_count = 0;
+    }
+
+    // Rename numbers the extra copies on its own; the other policies would lose output.
+    if duplicate_count > 0 {
+        log::warn!(
+            "{} files in this batch map to an output name used by another file",
+            duplicate_count
+        );
+    }
+    let duplicate_outcome = match options.existing_file_policy {
+        ExistingFilePolicy::Rename => None,
+        ExistingFilePolicy::Overwrite if !options.zip_output => {
+            Some("the last one written replaces the others")
+        }
+        ExistingFilePolicy::Overwrite | ExistingFilePolicy::Skip => {
+            Some("only the first one written is kept")
+        }
+    };
+    let duplicate_warning = duplicate_outcome
+        .filter(|_| duplicate_count > 0)
+        .map(|outcome| {
+            format!(
+                "{} files in this batch map to the same output name as another file, so {}. \
+                 Choose \"Keep both\" under \"If file exists\" to number them instead. \
+                 Continue anyway?",
+                duplicate_count, outcome
+            )
+        });
+    (collision_count, disk_warning, duplicate_warning)
+}
+
+/// Describes a failed conversion task, including the panic payload when there is one.
+fn panic_message(err: tokio::task::JoinError) -> String {
+    if !err.is_panic() {
+        return "Conversion task was cancelled".to_string();
+    }
+    let payload = err.into_panic();
+    let detail = payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown error".to_string());
+    format!("Conversion crashed: {}", detail)
 }
@@ -0,0 +1,133 @@
+//! Queue file lists: plain text with one path per line, or CSV with per-file overrides.
+
+use crate::convert::detect_source_format;
+use crate::state::{CropRect, FileItem};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+
+/// Files read from a list, in order, and the lines that could not be used.
+#[derive(Debug, Default)]
+pub struct FileList {
+    pub items: Vec<FileItem>,
+    pub unresolved: Vec<String>,
+}
+
+/// Reads a file list. Blank lines and `#` comments are skipped, and relative paths are
+/// resolved against the list's own folder. A first row starting with a `path` column marks
+/// a CSV whose `crop_x`, `crop_y`, `crop_width`, and `crop_height` columns (normalized 0 to 1)
+/// preset each file's crop.
+pub fn read_file_list(list_path: &Path) -> Result<FileList> {
+    let contents = std::fs::read_to_string(list_path).context("Could not read file list")?;
+    let base = list_path.parent().unwrap_or(Path::new(""));
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .peekable();
+
+    let header = lines
+        .peek()
+        .map(|(_, line)| split_csv(line))
+        .filter(|fields| {
+            fields
+                .first()
+                .is_some_and(|f| f.eq_ignore_ascii_case("path"))
+        });
+    let column = |name: &str| {
+        header
+            .as_ref()
+            .and_then(|h| h.iter().position(|f| f.eq_ignore_ascii_case(name)))
+    };
+    let crop_columns = [
+        column("crop_x"),
+        column("crop_y"),
+        column("crop_width"),
+        column("crop_height"),
+    ];
+    if header.is_some() {
+        lines.next();
+    }
+
+    let mut list = FileList::default();
+    for (number, line) in lines {
+        let fields = if header.is_some() {
+            split_csv(line)
+        } else {
+            vec![line.to_string()]
+        };
+        let raw = fields.first().map(String::as_str).unwrap_or_default();
+        let path = base.join(PathBuf::from(raw));
+        if !path.is_file() {
+            list.unresolved
+                .push(format!("line {}: {} not found", number, raw));
+            continue;
+        }
+        if !detect_source_format(&path).is_ok_and(|f| f.is_decodable()) {
+            list.unresolved
+                .push(format!("line {}: {} is not a supported image", number, raw));
+            continue;
+        }
+        let crop = match read_crop(&fields, crop_columns) {
+            Ok(crop) => crop,
+            Err(reason) => {
+                list.unresolved.push(format!("line {}: {}", number, reason));
+                continue;
+            }
+        };
+        let mut item = FileItem::new(path);
+        item.crop = crop;
+        list.items.push(item);
+    }
+    Ok(list)
+}
+
+/// Reads a crop from the given columns; a row with all of them empty has no crop.
+fn read_crop(
+    fields: &[String],
+    columns: [Option<usize>; 4],
+) -> std::result::Result<Option<CropRect>, String> {
+    let values: Vec<&str> = columns
+        .iter()
+        .map(|c| c.and_then(|c| fields.get(c)).map_or("", |f| f.as_str()))
+        .collect();
+    if values.iter().all(|v| v.is_empty()) {
+        return Ok(None);
+    }
+    let parsed: Vec<f32> = values
+        .iter()
+        .filter_map(|v| v.parse::<f32>().ok())
+        .filter(|v| (0.0..=1.0).contains(v))
+        .collect();
+    let [x, y, width, height] = parsed[..] else {
+        return Err("crop needs four values from 0 to 1".to_string());
+    };
+    if width <= 0.0 || height <= 0.0 || x + width > 1.0 || y + height > 1.0 {
+        return Err("crop lies outside the image".to_string());
+    }
+    Ok(Some(CropRect {
+        x,
+        y,
+        width,
+        height,
+    }))
+}
+
+/// Splits one CSV row, honoring double-quoted fields so paths may contain commas.
+fn split_csv(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                chars.next();
+                fields.last_mut().unwrap().push('"');
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields.into_iter().map(|f| f.trim().to_string()).collect()
+}
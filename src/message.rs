@@ -8,34 +8,274 @@ pub enum Message {
     WindowResized,
     AddFilesClicked,
     FilesSelected(Vec<PathBuf>),
-    ExternalFilesDropped(Vec<PathBuf>),
-    ItemDragStarted(usize),
-    ItemDropped,
-    ItemHovered(Option<usize>),
-    FormatSelected(ImageFormat),
+    FileDropped(PathBuf),
+    DroppedFilesFlushed,
+    AddFilesConfirmed(Vec<PathBuf>),
+    FilesPrepared(Vec<crate::state::FileItem>, Vec<PathBuf>),
+    UrlInputChanged(String),
+    AddUrlClicked,
+    ImportUrlListClicked,
+    UrlListSelected(Option<PathBuf>),
+    UrlDownloaded(String, Result<PathBuf, String>),
+    ImportFileListClicked,
+    FileListSelected(Option<PathBuf>),
+    FileListRead(Vec<crate::state::FileItem>, Vec<String>),
+    FileListReported,
+    ItemDragStarted(uuid::Uuid),
+    ItemDropped(uuid::Uuid),
+    MouseReleased,
+    ItemHovered(Option<uuid::Uuid>),
+    CropPointerMoved(f32, f32),
+    CropDragStarted,
+    CropDragEnded,
+    CropCleared,
+    EditExternallyClicked(uuid::Uuid),
+    WindowFocused,
+    CursorMoved(f32),
+    ListResizeStarted,
+    FileListScrolled(f32, f32),
+    FileListPointerMoved(Option<f32>),
+    DragAutoScrollTick,
+    FormatToggled(ImageFormat, bool),
+    ToggleKeepSourceFormat(bool),
     QualityChanged(u8),
+    SliderReleased,
     QualityInputChanged(String),
+    QualityPresetSelected(crate::state::QualityPreset),
+    ToggleUnifiedQuality(bool),
+    PerceptualQualityChanged(u8),
     PngCompressionToggled(bool),
+    HeicHighBitDepthToggled(bool),
+    KeepPngPaletteToggled(bool),
+    ColorSpaceSelected(crate::state::OutputColorSpace),
+    ColorCorrectionSelected(crate::state::ColorCorrection),
+    PngColorTagSelected(crate::state::PngColorTag),
+    BrowseSrgbProfileClicked,
+    SrgbProfileSelected(Option<PathBuf>),
+    SrgbProfileCleared,
+    WebpLosslessToggled(bool),
+    KeepAnimationToggled(bool),
+    DpiChanged(String),
+    AutoLosslessToggled(bool),
     ResizeToggled(bool),
     WidthChanged(String),
     HeightChanged(String),
+    ResizeModeSelected(crate::state::ResizeMode),
+    LongestSideChanged(String),
+    MaxMegapixelsChanged(String),
+    LockAspectToggled(bool),
+    PadToFitToggled(bool),
+    PadColorChanged(String),
+    RemoveLetterboxToggled(bool),
+    LetterboxToleranceChanged(u8),
     PrefixChanged(String),
     FindPatternChanged(String),
     ReplaceWithChanged(String),
     AutoSuffixToggled(bool),
+    SuffixSizeLetterChanged(String),
+    SuffixQualityLetterChanged(String),
+    SuffixModeToggled(bool),
+    ExtensionCaseSelected(crate::state::ExtensionCase),
+    OutputTimestampSelected(crate::state::OutputTimestamp),
+    DoubleClickActionSelected(crate::state::DoubleClickAction),
+    ExistingFilePolicySelected(crate::state::ExistingFilePolicy),
+    OverwriteWithoutAskingToggled(bool),
+    OverwriteThresholdChanged(String),
+    FilenameTemplateChanged(String),
+    DateFormatChanged(String),
     ToggleSelection(usize),
+    StatusFilterSelected(crate::state::StatusFilter),
+    FileDoubleClicked(usize),
+    PreviewReady(
+        uuid::Uuid,
+        Option<(crate::state::Preview, crate::state::Histogram)>,
+    ),
+    FileInfoReady(uuid::Uuid, Option<crate::state::FileInfo>),
     DeleteSelected,
     ClearList,
+    /// Steps back through file list edits (Ctrl+Z).
+    Undo,
+    /// Reapplies an undone file list edit (Ctrl+Y or Ctrl+Shift+Z).
+    Redo,
     ToggleCustomOutput(bool),
     BrowseOutputClicked,
     OutputFolderSelected(Option<PathBuf>),
+    ToggleZipOutput(bool),
+    FormatSubfoldersToggled(bool),
+    BrowseZipClicked,
+    ZipPathSelected(Option<PathBuf>),
+    RecentOutputSelected(PathBuf),
+    NamingRuleSelected(crate::state::NamingRule),
     ToggleKeepMetadata(bool),
+    ToggleDeleteOriginals(bool),
+    DeleteOriginalsConfirmed(bool),
+    ToggleRegenerateExifThumbnail(bool),
+    ToggleKeepExifOrientation(bool),
+    ToggleIgnoreExifOrientation(bool),
+    ToggleFlattenAlpha(bool),
+    FrameChoiceSelected(crate::state::FrameChoice),
+    FrameNumberChanged(String),
+    BrightnessChanged(i32),
+    ContrastChanged(i32),
+    SaturationChanged(i32),
+    GrayscaleToggled(bool),
+    LinearGrayscaleToggled(bool),
+    AdjustmentsReleased,
+    AdjustmentsReset,
+    ToggleVerifyOutput(bool),
+    ToggleVerifyAlpha(bool),
     ToggleGenerateLog(bool),
+    ToggleWriteCaptions(bool),
+    CopySidecarsToggled(bool),
+    CaptionTemplateChanged(String),
+    ContactSheetClicked,
+    ContactSheetDone,
+    ContactSheetColumnsChanged(String),
+    ContactSheetCellChanged(String),
+    ContactSheetBackgroundChanged(String),
+    ContactSheetLabelsToggled(bool),
+    ExifArtistChanged(String),
+    ExifCopyrightChanged(String),
+    ToggleFailureLog(bool),
     AddNumberingToggled(bool),
     ManualGenerateLogClicked,
     DarkThemeToggled(bool),
+    SimpleViewToggled(bool),
+    LanguageSelected(crate::state::Language),
+    CompactListToggled(bool),
+    AdvancedToggled(bool),
+    LogToggled(bool),
+    CopyLogClicked,
+    AboutToggled(bool),
+    CopyDiagnosticsClicked,
+    CopyCliClicked,
+    SettingsWarningDismissed,
+    FailedDownloadsDismissed,
+    ResetSettingsClicked,
+    ResetSettingsDecision(Option<bool>),
+    MaxFileSizeChanged(String),
+    MaxFileSizeSubmitted,
+    ThreadLimitChanged(String),
+    ConversionOrderSelected(crate::state::ConversionOrder),
+    ToggleRestoreQueue(bool),
+    ToggleFailFast(bool),
+    ToggleReleaseMemory(bool),
+    DraftModeToggled(bool),
     ConvertClicked,
+    ConvertSelectedClicked,
+    CancelConversion,
+    PreflightClicked,
+    PreflightDecision(bool),
+    QualitySweepClicked,
+    QualitySweepReady(uuid::Uuid, Result<Vec<crate::state::SweepPoint>, String>),
+    AlphaDecision(Option<bool>),
     OverwriteDecision(bool),
-    FileConverted(uuid::Uuid, Result<(), String>),
+    /// A finished task, tagged with the batch generation it was spawned in.
+    FileConverted(
+        uuid::Uuid,
+        u64,
+        Result<crate::convert::ConversionReport, String>,
+        std::time::Duration,
+    ),
+    FileSkipped(uuid::Uuid, u64),
+    NextBatchReady,
     ConversionFinished,
 }
+
+impl Message {
+    /// Whether the message edits conversion options, which stay locked while a batch runs.
+    pub fn edits_options(&self) -> bool {
+        matches!(
+            self,
+            Message::FormatToggled(..)
+                | Message::ToggleKeepSourceFormat(_)
+                | Message::QualityChanged(_)
+                | Message::SliderReleased
+                | Message::QualityInputChanged(_)
+                | Message::QualityPresetSelected(_)
+                | Message::ToggleUnifiedQuality(_)
+                | Message::PerceptualQualityChanged(_)
+                | Message::PngCompressionToggled(_)
+                | Message::HeicHighBitDepthToggled(_)
+                | Message::KeepPngPaletteToggled(_)
+                | Message::ColorSpaceSelected(_)
+                | Message::ColorCorrectionSelected(_)
+                | Message::PngColorTagSelected(_)
+                | Message::BrowseSrgbProfileClicked
+                | Message::SrgbProfileSelected(_)
+                | Message::SrgbProfileCleared
+                | Message::WebpLosslessToggled(_)
+                | Message::KeepAnimationToggled(_)
+                | Message::DpiChanged(_)
+                | Message::AutoLosslessToggled(_)
+                | Message::ResizeToggled(_)
+                | Message::WidthChanged(_)
+                | Message::HeightChanged(_)
+                | Message::ResizeModeSelected(_)
+                | Message::LongestSideChanged(_)
+                | Message::MaxMegapixelsChanged(_)
+                | Message::LockAspectToggled(_)
+                | Message::PadToFitToggled(_)
+                | Message::PadColorChanged(_)
+                | Message::RemoveLetterboxToggled(_)
+                | Message::LetterboxToleranceChanged(_)
+                | Message::PrefixChanged(_)
+                | Message::FindPatternChanged(_)
+                | Message::ReplaceWithChanged(_)
+                | Message::AutoSuffixToggled(_)
+                | Message::SuffixSizeLetterChanged(_)
+                | Message::SuffixQualityLetterChanged(_)
+                | Message::SuffixModeToggled(_)
+                | Message::ExtensionCaseSelected(_)
+                | Message::OutputTimestampSelected(_)
+                | Message::ExistingFilePolicySelected(_)
+                | Message::OverwriteWithoutAskingToggled(_)
+                | Message::OverwriteThresholdChanged(_)
+                | Message::FilenameTemplateChanged(_)
+                | Message::DateFormatChanged(_)
+                | Message::ToggleCustomOutput(_)
+                | Message::BrowseOutputClicked
+                | Message::OutputFolderSelected(_)
+                | Message::ToggleZipOutput(_)
+                | Message::FormatSubfoldersToggled(_)
+                | Message::BrowseZipClicked
+                | Message::ZipPathSelected(_)
+                | Message::RecentOutputSelected(_)
+                | Message::NamingRuleSelected(_)
+                | Message::ToggleKeepMetadata(_)
+                | Message::ToggleDeleteOriginals(_)
+                | Message::DeleteOriginalsConfirmed(_)
+                | Message::ToggleRegenerateExifThumbnail(_)
+                | Message::ToggleKeepExifOrientation(_)
+                | Message::ToggleIgnoreExifOrientation(_)
+                | Message::ToggleFlattenAlpha(_)
+                | Message::DraftModeToggled(_)
+                | Message::FrameChoiceSelected(_)
+                | Message::FrameNumberChanged(_)
+                | Message::BrightnessChanged(_)
+                | Message::ContrastChanged(_)
+                | Message::SaturationChanged(_)
+                | Message::GrayscaleToggled(_)
+                | Message::LinearGrayscaleToggled(_)
+                | Message::AdjustmentsReleased
+                | Message::AdjustmentsReset
+                | Message::ToggleVerifyOutput(_)
+                | Message::ToggleVerifyAlpha(_)
+                | Message::ToggleGenerateLog(_)
+                | Message::ToggleWriteCaptions(_)
+                | Message::CaptionTemplateChanged(_)
+                | Message::CopySidecarsToggled(_)
+                | Message::ExifArtistChanged(_)
+                | Message::ExifCopyrightChanged(_)
+                | Message::ToggleFailureLog(_)
+                | Message::AddNumberingToggled(_)
+                | Message::MaxFileSizeChanged(_)
+                | Message::MaxFileSizeSubmitted
+                | Message::ThreadLimitChanged(_)
+                | Message::ConversionOrderSelected(_)
+                | Message::ToggleFailFast(_)
+                | Message::ResetSettingsClicked
+                | Message::ResetSettingsDecision(_)
+        )
+    }
+}
@@ -0,0 +1,145 @@
+//! Remote image fetching for URL-based queue entries.
+
+use crate::constants::{DOWNLOAD_CONNECT_TIMEOUT_SECS, DOWNLOAD_READ_TIMEOUT_SECS};
+use crate::convert::{detect_source_format, SourceFormat};
+use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// Returns directory holding images downloaded from URLs.
+pub fn download_dir() -> PathBuf {
+    std::env::temp_dir()
+        .join("SimpleImageConverter")
+        .join("downloads")
+}
+
+/// Returns true if the path points to an image fetched from a URL.
+pub fn is_downloaded(path: &Path) -> bool {
+    path.starts_with(download_dir())
+}
+
+/// Deletes a downloaded image together with the folder it was downloaded into.
+pub fn remove_download(path: &Path) {
+    let Some(dir) = path
+        .parent()
+        .filter(|d| d.parent() == Some(download_dir().as_path()))
+    else {
+        return;
+    };
+    if let Err(e) = std::fs::remove_dir_all(dir) {
+        log::warn!("Could not remove download {}: {}", dir.display(), e);
+    }
+}
+
+/// Deletes every downloaded image, for when the app exits.
+pub fn remove_all_downloads() {
+    let dir = download_dir();
+    if dir.exists() {
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            log::warn!("Could not remove downloads in {}: {}", dir.display(), e);
+        }
+    }
+}
+
+/// Shared HTTP client, so a stalled server fails the download instead of hanging it.
+fn client() -> &'static reqwest::Client {
+    static CLIENT: OnceLock<reqwest::Client> = OnceLock::new();
+    CLIENT.get_or_init(|| {
+        reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(DOWNLOAD_CONNECT_TIMEOUT_SECS))
+            .read_timeout(Duration::from_secs(DOWNLOAD_READ_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_default()
+    })
+}
+
+/// Extracts URLs from a text list, skipping blank lines and `#` comments.
+pub fn parse_url_list(contents: &str) -> Vec<String> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with('#'))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Downloads an image to a temp file, enforcing the size cap and validating content.
+pub async fn download_image(url: String, max_bytes: u64) -> Result<PathBuf> {
+    let response = client()
+        .get(&url)
+        .send()
+        .await
+        .context("Download failed")?
+        .error_for_status()
+        .context("Download failed")?;
+
+    if let Some(content_type) = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+    {
+        if !content_type.starts_with("image/") && content_type != "application/octet-stream" {
+            anyhow::bail!("Not an image (content-type: {})", content_type);
+        }
+    }
+
+    let limit_mb = max_bytes / (1024 * 1024);
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        anyhow::bail!("File too large (max {}MB)", limit_mb);
+    }
+
+    // Name after the final URL so redirects to the real asset are reflected.
+    let name = response
+        .url()
+        .path_segments()
+        .and_then(|mut s| s.next_back())
+        .filter(|s| !s.is_empty())
+        .unwrap_or("download")
+        .to_string();
+
+    let dir = download_dir().join(uuid::Uuid::new_v4().to_string());
+    tokio::fs::create_dir_all(&dir).await?;
+    let result = save_download(response, &dir, &name, max_bytes).await;
+    if result.is_err() {
+        // Leave no partial file behind, whichever step failed.
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+    result
+}
+
+/// Streams the response body into `dir`, then checks it is an image and fixes its extension.
+async fn save_download(
+    mut response: reqwest::Response,
+    dir: &Path,
+    name: &str,
+    max_bytes: u64,
+) -> Result<PathBuf> {
+    let path = dir.join(name);
+    let mut file = tokio::fs::File::create(&path).await?;
+    let mut written: u64 = 0;
+    while let Some(chunk) = response.chunk().await.context("Download interrupted")? {
+        written += chunk.len() as u64;
+        if written > max_bytes {
+            anyhow::bail!("File too large (max {}MB)", max_bytes / (1024 * 1024));
+        }
+        file.write_all(&chunk).await?;
+    }
+    file.flush().await?;
+    drop(file);
+
+    let sniffed = path.clone();
+    let format = tokio::task::spawn_blocking(move || detect_source_format(&sniffed))
+        .await?
+        .context("Downloaded file is not a supported image")?;
+
+    // Give the file an extension matching its content so later stages agree.
+    let ext = path.extension().unwrap_or_default().to_string_lossy();
+    if SourceFormat::from_extension(&ext) != Some(format) {
+        let renamed = path.with_extension(format.extension());
+        tokio::fs::rename(&path, &renamed).await?;
+        return Ok(renamed);
+    }
+    Ok(path)
+}
@@ -1,8 +1,18 @@
 //! Settings persistence using SQLite in platform-specific config directory.
 
-use crate::state::{ConversionOptions, ImageFormat};
+use crate::constants::{DEFAULT_MAX_FILE_SIZE_MB, MAX_FILE_SIZE_LIMIT_MB};
+use crate::state::{
+    default_thread_limit, ColorCorrection, ConversionOptions, ConversionOrder, DoubleClickAction,
+    ExistingFilePolicy, ExtensionCase, FrameChoice, ImageFormat, Language, LastRun, NamingRule,
+    OutputColorSpace, OutputTimestamp, PngColorTag, ResizeMode, ResizeSettings, UiDensity,
+};
+use crate::theme::dimensions::{FILE_LIST_HEIGHT, FILE_LIST_MAX_HEIGHT, FILE_LIST_MIN_HEIGHT};
 use rusqlite::{Connection, Result as SqlResult};
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Set once the config directory proved unwritable; later reads and writes use the fallback.
+static USE_FALLBACK: AtomicBool = AtomicBool::new(false);
 
 /// Returns cross-platform application config directory.
 fn get_app_data_dir() -> PathBuf {
@@ -16,8 +26,20 @@ fn get_app_data_dir() -> PathBuf {
         .join("SimpleImageConverter")
 }
 
+/// Settings database next to the executable, used when the config directory is not writable.
+fn fallback_db_path() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|p| p.parent().map(|d| d.join("settings.db")))
+}
+
 /// Returns full path to settings database file.
 fn get_db_path() -> PathBuf {
+    if USE_FALLBACK.load(Ordering::Relaxed) {
+        if let Some(path) = fallback_db_path() {
+            return path;
+        }
+    }
     let dir = get_app_data_dir();
     let _ = std::fs::create_dir_all(&dir);
     dir.join("settings.db")
@@ -35,6 +57,10 @@ pub fn init_db() -> SqlResult<Connection> {
 
 /// Loads all settings from database into ConversionOptions.
 pub fn load_settings() -> ConversionOptions {
+    // A fallback database only exists if an earlier session could not write the config folder.
+    if fallback_db_path().is_some_and(|p| p.is_file()) {
+        USE_FALLBACK.store(true, Ordering::Relaxed);
+    }
     let conn = match init_db() {
         Ok(c) => c,
         Err(_) => return ConversionOptions::default(),
@@ -49,12 +75,90 @@ pub fn load_settings() -> ConversionOptions {
             _ => ImageFormat::Jpeg,
         };
     }
+    if let Ok(v) = get_value(&conn, "extra_formats") {
+        opts.extra_formats = v
+            .split(',')
+            .filter_map(|f| match f {
+                "Jpeg" => Some(ImageFormat::Jpeg),
+                "Png" => Some(ImageFormat::Png),
+                "WebP" => Some(ImageFormat::WebP),
+                _ => None,
+            })
+            .filter(|&f| f != opts.format)
+            .collect();
+    }
+    if let Ok(v) = get_value(&conn, "keep_source_format") {
+        opts.keep_source_format = v == "true";
+    }
     if let Ok(v) = get_value(&conn, "quality") {
         opts.quality = v.parse().unwrap_or(80);
     }
+    if let Ok(v) = get_value(&conn, "dpi") {
+        opts.dpi = v.parse().unwrap_or(0);
+    }
+    if let Ok(v) = get_value(&conn, "custom_quality") {
+        opts.custom_quality = v.parse().unwrap_or(opts.quality);
+    }
+    // Installs from before seeding already chose a quality, so only a fresh database follows
+    // the format defaults.
+    opts.quality_customized = match get_value(&conn, "quality_customized") {
+        Ok(v) => v == "true",
+        Err(_) => get_value(&conn, "quality").is_ok(),
+    };
+    if !opts.quality_customized {
+        opts.quality = opts.format.default_quality();
+        opts.custom_quality = opts.quality;
+    }
+    if let Ok(v) = get_value(&conn, "unified_quality") {
+        opts.unified_quality = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "perceptual_quality") {
+        opts.perceptual_quality = v.parse::<u8>().unwrap_or(75).min(100);
+    }
     if let Ok(v) = get_value(&conn, "png_compressed") {
         opts.png_compressed = v == "true";
     }
+    if let Ok(v) = get_value(&conn, "keep_png_palette") {
+        opts.keep_png_palette = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "heic_high_bit_depth") {
+        opts.heic_high_bit_depth = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "color_space") {
+        opts.color_space = match v.as_str() {
+            "DisplayP3" => OutputColorSpace::DisplayP3,
+            "AdobeRgb" => OutputColorSpace::AdobeRgb,
+            _ => OutputColorSpace::Srgb,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "color_correction") {
+        opts.color_correction = match v.as_str() {
+            "Always" => ColorCorrection::Always,
+            "Off" => ColorCorrection::Off,
+            _ => ColorCorrection::SkipSrgb,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "png_color_tag") {
+        opts.png_color_tag = match v.as_str() {
+            "Linear" => PngColorTag::Linear,
+            "None" => PngColorTag::None,
+            _ => PngColorTag::Srgb,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "custom_srgb_icc") {
+        if !v.is_empty() {
+            opts.custom_srgb_icc = Some(PathBuf::from(v));
+        }
+    }
+    if let Ok(v) = get_value(&conn, "webp_lossless") {
+        opts.webp_lossless = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "keep_animation") {
+        opts.keep_animation = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "auto_lossless") {
+        opts.auto_lossless = v == "true";
+    }
     if let Ok(v) = get_value(&conn, "resize") {
         opts.resize = v == "true";
     }
@@ -64,74 +168,658 @@ pub fn load_settings() -> ConversionOptions {
     if let Ok(v) = get_value(&conn, "target_height") {
         opts.target_height = v;
     }
+    if let Ok(v) = get_value(&conn, "resize_mode") {
+        opts.resize_mode = match v.as_str() {
+            "FitWithin" => ResizeMode::FitWithin,
+            _ => ResizeMode::Exact,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "longest_side") {
+        opts.longest_side = v;
+    }
+    if let Ok(v) = get_value(&conn, "max_megapixels") {
+        opts.max_megapixels = v;
+    }
+    if let Ok(v) = get_value(&conn, "lock_aspect") {
+        opts.lock_aspect = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "pad_to_fit") {
+        opts.pad_to_fit = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "pad_color") {
+        opts.pad_color = v;
+    }
+    if let Ok(v) = get_value(&conn, "remove_letterbox") {
+        opts.remove_letterbox = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "letterbox_tolerance") {
+        opts.letterbox_tolerance = v.parse().unwrap_or(16);
+    }
+    for format in ImageFormat::ALL {
+        let ext = format.extension();
+        if let Ok(v) = get_value(&conn, &format!("resize_{}", ext)) {
+            opts.format_resize.insert(
+                format,
+                ResizeSettings {
+                    resize: v == "true",
+                    target_width: get_value(&conn, &format!("target_width_{}", ext))
+                        .unwrap_or_default(),
+                    target_height: get_value(&conn, &format!("target_height_{}", ext))
+                        .unwrap_or_default(),
+                },
+            );
+        }
+    }
     if let Ok(v) = get_value(&conn, "prefix") {
         opts.prefix = v;
     }
     if let Ok(v) = get_value(&conn, "auto_suffix") {
         opts.auto_suffix = v == "true";
     }
+    if let Ok(v) = get_value(&conn, "suffix_mode") {
+        opts.suffix_mode = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "suffix_size_letter") {
+        opts.suffix_size_letter = v;
+    }
+    if let Ok(v) = get_value(&conn, "suffix_quality_letter") {
+        opts.suffix_quality_letter = v;
+    }
+    if let Ok(v) = get_value(&conn, "extension_case") {
+        opts.extension_case = match v.as_str() {
+            "Upper" => ExtensionCase::Upper,
+            "MatchInput" => ExtensionCase::MatchInput,
+            _ => ExtensionCase::Lower,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "output_timestamp") {
+        opts.output_timestamp = match v.as_str() {
+            "SourceModified" => OutputTimestamp::SourceModified,
+            "CaptureDate" => OutputTimestamp::CaptureDate,
+            _ => OutputTimestamp::Conversion,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "double_click_action") {
+        opts.double_click_action = match v.as_str() {
+            "EditExternally" => DoubleClickAction::EditExternally,
+            "ShowInFolder" => DoubleClickAction::ShowInFolder,
+            _ => DoubleClickAction::Preview,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "existing_file_policy") {
+        opts.existing_file_policy = match v.as_str() {
+            "Skip" => ExistingFilePolicy::Skip,
+            "Rename" => ExistingFilePolicy::Rename,
+            _ => ExistingFilePolicy::Overwrite,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "overwrite_without_asking") {
+        opts.overwrite_without_asking = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "overwrite_prompt_threshold") {
+        opts.overwrite_prompt_threshold = v.parse().unwrap_or(0);
+    }
+    if let Ok(v) = get_value(&conn, "filename_template") {
+        opts.filename_template = v;
+    }
+    if let Ok(v) = get_value(&conn, "caption_template") {
+        opts.caption_template = v;
+    }
+    if let Ok(v) = get_value(&conn, "exif_artist") {
+        opts.exif_artist = v;
+    }
+    if let Ok(v) = get_value(&conn, "exif_copyright") {
+        opts.exif_copyright = v;
+    }
+    if let Ok(v) = get_value(&conn, "date_format") {
+        opts.date_format = v;
+    }
     if let Ok(v) = get_value(&conn, "keep_metadata") {
         opts.keep_metadata = v == "true";
     }
+    if let Ok(v) = get_value(&conn, "regenerate_exif_thumbnail") {
+        opts.regenerate_exif_thumbnail = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "keep_exif_orientation") {
+        opts.keep_exif_orientation = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "ignore_exif_orientation") {
+        opts.ignore_exif_orientation = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "flatten_alpha") {
+        opts.flatten_alpha = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "verify_output") {
+        opts.verify_output = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "verify_alpha") {
+        opts.verify_alpha = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "format_subfolders") {
+        opts.format_subfolders = v == "true";
+    }
     if let Ok(v) = get_value(&conn, "use_custom_output") {
         opts.use_custom_output = v == "true";
     }
     if let Ok(v) = get_value(&conn, "custom_output_path") {
         if !v.is_empty() {
-            opts.custom_output_path = Some(PathBuf::from(v));
+            let path = PathBuf::from(v);
+            // A file here (e.g. from hand-edited settings) would be joined with output names.
+            opts.custom_output_path = if path.is_file() {
+                log::warn!(
+                    "Output folder {} is a file; using its parent folder",
+                    path.display()
+                );
+                path.parent().map(PathBuf::from)
+            } else {
+                Some(path)
+            };
         }
     }
+    if let Ok(v) = get_value(&conn, "zip_output") {
+        opts.zip_output = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "zip_path") {
+        if !v.is_empty() {
+            opts.zip_path = Some(PathBuf::from(v));
+        }
+    }
+    if let Ok(v) = get_value(&conn, "last_input_dir") {
+        if !v.is_empty() {
+            opts.last_input_dir = Some(PathBuf::from(v));
+        }
+    }
+    if let Ok(v) = get_value(&conn, "recent_output_dirs") {
+        opts.recent_output_dirs = v
+            .lines()
+            .map(PathBuf::from)
+            .filter(|p| p.is_dir())
+            .collect();
+    }
+    if let Ok(v) = get_value(&conn, "naming_history") {
+        opts.naming_history = v
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split('\t').map(String::from);
+                Some(NamingRule {
+                    prefix: fields.next()?,
+                    find: fields.next()?,
+                    replace: fields.next()?,
+                })
+            })
+            .collect();
+    }
     if let Ok(v) = get_value(&conn, "generate_log") {
         opts.generate_log = v == "true";
     }
+    if let Ok(v) = get_value(&conn, "write_captions") {
+        opts.write_captions = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "copy_sidecars") {
+        opts.copy_sidecars = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "contact_sheet_columns") {
+        opts.contact_sheet_columns = v.parse().unwrap_or(6);
+    }
+    if let Ok(v) = get_value(&conn, "contact_sheet_cell") {
+        opts.contact_sheet_cell = v.parse().unwrap_or(256);
+    }
+    if let Ok(v) = get_value(&conn, "contact_sheet_background") {
+        opts.contact_sheet_background = v;
+    }
+    if let Ok(v) = get_value(&conn, "contact_sheet_labels") {
+        opts.contact_sheet_labels = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "failure_log") {
+        opts.failure_log = v == "true";
+    }
     if let Ok(v) = get_value(&conn, "add_numbering") {
         opts.add_numbering = v == "true";
     }
     if let Ok(v) = get_value(&conn, "is_dark_mode") {
         opts.is_dark_mode = v == "true";
     }
+    if let Ok(v) = get_value(&conn, "simple_view") {
+        opts.simple_view = v == "true";
+    } else if get_value(&conn, "format").is_ok() {
+        // Settings saved before the simple view existed keep showing every card.
+        opts.simple_view = false;
+    }
+    if let Ok(v) = get_value(&conn, "frame_choice") {
+        opts.frame_choice = match v.as_str() {
+            "Last" => FrameChoice::Last,
+            "Number" => FrameChoice::Number,
+            _ => FrameChoice::First,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "frame_number") {
+        opts.frame_number = v.parse().unwrap_or(1).max(1);
+    }
+    if let Ok(v) = get_value(&conn, "brightness") {
+        opts.adjustments.brightness = v.parse().unwrap_or(0).clamp(-100, 100);
+    }
+    if let Ok(v) = get_value(&conn, "contrast") {
+        opts.adjustments.contrast = v.parse().unwrap_or(0).clamp(-100, 100);
+    }
+    if let Ok(v) = get_value(&conn, "saturation") {
+        opts.adjustments.saturation = v.parse().unwrap_or(0).clamp(-100, 100);
+    }
+    if let Ok(v) = get_value(&conn, "grayscale") {
+        opts.adjustments.grayscale = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "linear_grayscale") {
+        opts.adjustments.linear_grayscale = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "language") {
+        opts.language = match v.as_str() {
+            "English" => Language::English,
+            "Indonesian" => Language::Indonesian,
+            "Spanish" => Language::Spanish,
+            _ => Language::System,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "ui_density") {
+        opts.ui_density = match v.as_str() {
+            "Compact" => UiDensity::Compact,
+            _ => UiDensity::Comfortable,
+        };
+    }
+    if let Ok(v) = get_value(&conn, "file_list_height") {
+        opts.file_list_height = v
+            .parse()
+            .unwrap_or(FILE_LIST_HEIGHT)
+            .clamp(FILE_LIST_MIN_HEIGHT, FILE_LIST_MAX_HEIGHT);
+    }
     if let Ok(v) = get_value(&conn, "max_batch_size") {
         opts.max_batch_size = v.parse().unwrap_or(10);
     }
+    if let Ok(v) = get_value(&conn, "restore_queue") {
+        opts.restore_queue = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "fail_fast") {
+        opts.fail_fast = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "delete_originals") {
+        opts.delete_originals = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "delete_originals_confirmed") {
+        opts.delete_originals_confirmed = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "release_memory") {
+        opts.release_memory = v == "true";
+    }
+    if let Ok(v) = get_value(&conn, "max_file_size_mb") {
+        opts.max_file_size_mb = v.parse().map_or(DEFAULT_MAX_FILE_SIZE_MB, |mb: u64| {
+            mb.clamp(1, MAX_FILE_SIZE_LIMIT_MB)
+        });
+    }
+    if let Ok(v) = get_value(&conn, "thread_limit") {
+        opts.thread_limit = v.parse().unwrap_or_else(|_| default_thread_limit());
+    }
+    if let Ok(v) = get_value(&conn, "conversion_order") {
+        opts.conversion_order = match v.as_str() {
+            "SmallestFirst" => ConversionOrder::SmallestFirst,
+            "LargestFirst" => ConversionOrder::LargestFirst,
+            _ => ConversionOrder::AsListed,
+        };
+    }
 
     opts
 }
 
-/// Saves all settings from ConversionOptions to database.
-pub fn save_settings(opts: &ConversionOptions) {
-    let conn = match init_db() {
-        Ok(c) => c,
-        Err(_) => return,
+/// Saves all settings, retrying next to the executable if the config folder cannot be written.
+/// Returns a message for the user when settings did not land where they were expected.
+pub fn save_settings(opts: &ConversionOptions) -> Option<String> {
+    let err = match write_settings(opts) {
+        Ok(()) => return None,
+        Err(e) => e,
     };
+    log::error!(
+        "Failed to save settings to {}: {}",
+        get_db_path().display(),
+        err
+    );
+    if USE_FALLBACK.swap(true, Ordering::Relaxed) {
+        return Some(format!("Settings could not be saved: {}", err));
+    }
+    match write_settings(opts) {
+        Ok(()) => Some(format!(
+            "Settings could not be saved to the config folder ({}), so they are now kept in {}.",
+            err,
+            get_db_path().display()
+        )),
+        Err(e) => {
+            log::error!(
+                "Failed to save settings to {}: {}",
+                get_db_path().display(),
+                e
+            );
+            Some(format!(
+                "Settings could not be saved ({}); changes will be lost on exit.",
+                e
+            ))
+        }
+    }
+}
+
+/// Writes all settings from ConversionOptions to the database.
+fn write_settings(opts: &ConversionOptions) -> SqlResult<()> {
+    // One transaction, so a save is a single disk sync instead of one per key.
+    let mut db = init_db()?;
+    let conn = db.transaction()?;
 
-    let format_str = match opts.format {
+    let format_str = |format: ImageFormat| match format {
         ImageFormat::Jpeg => "Jpeg",
         ImageFormat::Png => "Png",
         ImageFormat::WebP => "WebP",
     };
 
-    let _ = set_value(&conn, "format", format_str);
-    let _ = set_value(&conn, "quality", &opts.quality.to_string());
-    let _ = set_value(
+    set_value(&conn, "format", format_str(opts.format))?;
+    let extra_formats: Vec<&str> = opts.extra_formats.iter().map(|&f| format_str(f)).collect();
+    set_value(&conn, "extra_formats", &extra_formats.join(","))?;
+    set_value(
+        &conn,
+        "keep_source_format",
+        if opts.keep_source_format {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(&conn, "quality", &opts.quality.to_string())?;
+    set_value(&conn, "custom_quality", &opts.custom_quality.to_string())?;
+    set_value(
+        &conn,
+        "quality_customized",
+        if opts.quality_customized {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "unified_quality",
+        if opts.unified_quality {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "perceptual_quality",
+        &opts.perceptual_quality.to_string(),
+    )?;
+    set_value(&conn, "dpi", &opts.dpi.to_string())?;
+    set_value(
         &conn,
         "png_compressed",
         if opts.png_compressed { "true" } else { "false" },
-    );
-    let _ = set_value(&conn, "resize", if opts.resize { "true" } else { "false" });
-    let _ = set_value(&conn, "target_width", &opts.target_width);
-    let _ = set_value(&conn, "target_height", &opts.target_height);
-    let _ = set_value(&conn, "prefix", &opts.prefix);
-    let _ = set_value(
+    )?;
+    set_value(
+        &conn,
+        "keep_png_palette",
+        if opts.keep_png_palette {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "heic_high_bit_depth",
+        if opts.heic_high_bit_depth {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "color_space",
+        match opts.color_space {
+            OutputColorSpace::Srgb => "Srgb",
+            OutputColorSpace::DisplayP3 => "DisplayP3",
+            OutputColorSpace::AdobeRgb => "AdobeRgb",
+        },
+    )?;
+    set_value(
+        &conn,
+        "color_correction",
+        match opts.color_correction {
+            ColorCorrection::SkipSrgb => "SkipSrgb",
+            ColorCorrection::Always => "Always",
+            ColorCorrection::Off => "Off",
+        },
+    )?;
+    set_value(
+        &conn,
+        "png_color_tag",
+        match opts.png_color_tag {
+            PngColorTag::Srgb => "Srgb",
+            PngColorTag::Linear => "Linear",
+            PngColorTag::None => "None",
+        },
+    )?;
+    set_value(
+        &conn,
+        "custom_srgb_icc",
+        opts.custom_srgb_icc
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .as_str(),
+    )?;
+    set_value(
+        &conn,
+        "webp_lossless",
+        if opts.webp_lossless { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "keep_animation",
+        if opts.keep_animation { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "auto_lossless",
+        if opts.auto_lossless { "true" } else { "false" },
+    )?;
+    set_value(&conn, "resize", if opts.resize { "true" } else { "false" })?;
+    set_value(&conn, "target_width", &opts.target_width)?;
+    set_value(&conn, "target_height", &opts.target_height)?;
+    set_value(
+        &conn,
+        "resize_mode",
+        match opts.resize_mode {
+            ResizeMode::Exact => "Exact",
+            ResizeMode::FitWithin => "FitWithin",
+        },
+    )?;
+    set_value(&conn, "longest_side", &opts.longest_side)?;
+    set_value(&conn, "max_megapixels", &opts.max_megapixels)?;
+    set_value(
+        &conn,
+        "lock_aspect",
+        if opts.lock_aspect { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "pad_to_fit",
+        if opts.pad_to_fit { "true" } else { "false" },
+    )?;
+    set_value(&conn, "pad_color", &opts.pad_color)?;
+    set_value(
+        &conn,
+        "remove_letterbox",
+        if opts.remove_letterbox {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "letterbox_tolerance",
+        &opts.letterbox_tolerance.to_string(),
+    )?;
+    for format in ImageFormat::ALL {
+        let current;
+        let resize = if format == opts.format {
+            current = ResizeSettings {
+                resize: opts.resize,
+                target_width: opts.target_width.clone(),
+                target_height: opts.target_height.clone(),
+            };
+            &current
+        } else if let Some(r) = opts.format_resize.get(&format) {
+            r
+        } else {
+            continue;
+        };
+        let ext = format.extension();
+        set_value(
+            &conn,
+            &format!("resize_{}", ext),
+            if resize.resize { "true" } else { "false" },
+        )?;
+        set_value(
+            &conn,
+            &format!("target_width_{}", ext),
+            &resize.target_width,
+        )?;
+        set_value(
+            &conn,
+            &format!("target_height_{}", ext),
+            &resize.target_height,
+        )?;
+    }
+    set_value(&conn, "prefix", &opts.prefix)?;
+    set_value(
         &conn,
         "auto_suffix",
         if opts.auto_suffix { "true" } else { "false" },
-    );
-    let _ = set_value(
+    )?;
+    set_value(
+        &conn,
+        "suffix_mode",
+        if opts.suffix_mode { "true" } else { "false" },
+    )?;
+    set_value(&conn, "suffix_size_letter", &opts.suffix_size_letter)?;
+    set_value(&conn, "suffix_quality_letter", &opts.suffix_quality_letter)?;
+    set_value(
+        &conn,
+        "extension_case",
+        match opts.extension_case {
+            ExtensionCase::Lower => "Lower",
+            ExtensionCase::Upper => "Upper",
+            ExtensionCase::MatchInput => "MatchInput",
+        },
+    )?;
+    set_value(
+        &conn,
+        "output_timestamp",
+        match opts.output_timestamp {
+            OutputTimestamp::Conversion => "Conversion",
+            OutputTimestamp::SourceModified => "SourceModified",
+            OutputTimestamp::CaptureDate => "CaptureDate",
+        },
+    )?;
+    set_value(
+        &conn,
+        "double_click_action",
+        match opts.double_click_action {
+            DoubleClickAction::Preview => "Preview",
+            DoubleClickAction::EditExternally => "EditExternally",
+            DoubleClickAction::ShowInFolder => "ShowInFolder",
+        },
+    )?;
+    set_value(
+        &conn,
+        "existing_file_policy",
+        match opts.existing_file_policy {
+            ExistingFilePolicy::Overwrite => "Overwrite",
+            ExistingFilePolicy::Skip => "Skip",
+            ExistingFilePolicy::Rename => "Rename",
+        },
+    )?;
+    set_value(
+        &conn,
+        "overwrite_without_asking",
+        if opts.overwrite_without_asking {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "overwrite_prompt_threshold",
+        &opts.overwrite_prompt_threshold.to_string(),
+    )?;
+    set_value(&conn, "filename_template", &opts.filename_template)?;
+    set_value(&conn, "caption_template", &opts.caption_template)?;
+    set_value(&conn, "exif_artist", &opts.exif_artist)?;
+    set_value(&conn, "exif_copyright", &opts.exif_copyright)?;
+    set_value(&conn, "date_format", &opts.date_format)?;
+    set_value(
         &conn,
         "keep_metadata",
         if opts.keep_metadata { "true" } else { "false" },
-    );
-    let _ = set_value(
+    )?;
+    set_value(
+        &conn,
+        "regenerate_exif_thumbnail",
+        if opts.regenerate_exif_thumbnail {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "keep_exif_orientation",
+        if opts.keep_exif_orientation {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "ignore_exif_orientation",
+        if opts.ignore_exif_orientation {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "flatten_alpha",
+        if opts.flatten_alpha { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "verify_output",
+        if opts.verify_output { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "verify_alpha",
+        if opts.verify_alpha { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "format_subfolders",
+        if opts.format_subfolders {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
         &conn,
         "use_custom_output",
         if opts.use_custom_output {
@@ -139,8 +827,8 @@ pub fn save_settings(opts: &ConversionOptions) {
         } else {
             "false"
         },
-    );
-    let _ = set_value(
+    )?;
+    set_value(
         &conn,
         "custom_output_path",
         opts.custom_output_path
@@ -148,23 +836,270 @@ pub fn save_settings(opts: &ConversionOptions) {
             .map(|p| p.to_string_lossy().to_string())
             .unwrap_or_default()
             .as_str(),
-    );
-    let _ = set_value(
+    )?;
+    set_value(
+        &conn,
+        "zip_output",
+        if opts.zip_output { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "zip_path",
+        opts.zip_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .as_str(),
+    )?;
+    set_value(
+        &conn,
+        "last_input_dir",
+        opts.last_input_dir
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default()
+            .as_str(),
+    )?;
+    set_value(
+        &conn,
+        "recent_output_dirs",
+        &opts
+            .recent_output_dirs
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )?;
+    set_value(
+        &conn,
+        "naming_history",
+        &opts
+            .naming_history
+            .iter()
+            .map(|r| format!("{}\t{}\t{}", r.prefix, r.find, r.replace))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    )?;
+    set_value(
         &conn,
         "generate_log",
         if opts.generate_log { "true" } else { "false" },
-    );
-    let _ = set_value(
+    )?;
+    set_value(
+        &conn,
+        "write_captions",
+        if opts.write_captions { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "copy_sidecars",
+        if opts.copy_sidecars { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "contact_sheet_columns",
+        &opts.contact_sheet_columns.to_string(),
+    )?;
+    set_value(
+        &conn,
+        "contact_sheet_cell",
+        &opts.contact_sheet_cell.to_string(),
+    )?;
+    set_value(
+        &conn,
+        "contact_sheet_background",
+        &opts.contact_sheet_background,
+    )?;
+    set_value(
+        &conn,
+        "contact_sheet_labels",
+        if opts.contact_sheet_labels {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "failure_log",
+        if opts.failure_log { "true" } else { "false" },
+    )?;
+    set_value(
         &conn,
         "add_numbering",
         if opts.add_numbering { "true" } else { "false" },
-    );
-    let _ = set_value(
+    )?;
+    set_value(
         &conn,
         "is_dark_mode",
         if opts.is_dark_mode { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "simple_view",
+        if opts.simple_view { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "frame_choice",
+        match opts.frame_choice {
+            FrameChoice::First => "First",
+            FrameChoice::Last => "Last",
+            FrameChoice::Number => "Number",
+        },
+    )?;
+    set_value(&conn, "frame_number", &opts.frame_number.to_string())?;
+    let adjustments = &opts.adjustments;
+    set_value(&conn, "brightness", &adjustments.brightness.to_string())?;
+    set_value(&conn, "contrast", &adjustments.contrast.to_string())?;
+    set_value(&conn, "saturation", &adjustments.saturation.to_string())?;
+    set_value(&conn, "grayscale", &adjustments.grayscale.to_string())?;
+    set_value(
+        &conn,
+        "linear_grayscale",
+        &adjustments.linear_grayscale.to_string(),
+    )?;
+    set_value(
+        &conn,
+        "language",
+        match opts.language {
+            Language::System => "System",
+            Language::English => "English",
+            Language::Indonesian => "Indonesian",
+            Language::Spanish => "Spanish",
+        },
+    )?;
+    set_value(
+        &conn,
+        "ui_density",
+        match opts.ui_density {
+            UiDensity::Comfortable => "Comfortable",
+            UiDensity::Compact => "Compact",
+        },
+    )?;
+    set_value(
+        &conn,
+        "file_list_height",
+        &opts.file_list_height.to_string(),
+    )?;
+    set_value(&conn, "max_batch_size", &opts.max_batch_size.to_string())?;
+    set_value(
+        &conn,
+        "max_file_size_mb",
+        &opts.max_file_size_mb.to_string(),
+    )?;
+    set_value(&conn, "thread_limit", &opts.thread_limit.to_string())?;
+    set_value(
+        &conn,
+        "conversion_order",
+        match opts.conversion_order {
+            ConversionOrder::AsListed => "AsListed",
+            ConversionOrder::SmallestFirst => "SmallestFirst",
+            ConversionOrder::LargestFirst => "LargestFirst",
+        },
+    )?;
+    set_value(
+        &conn,
+        "restore_queue",
+        if opts.restore_queue { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "fail_fast",
+        if opts.fail_fast { "true" } else { "false" },
+    )?;
+    set_value(
+        &conn,
+        "delete_originals",
+        if opts.delete_originals {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "delete_originals_confirmed",
+        if opts.delete_originals_confirmed {
+            "true"
+        } else {
+            "false"
+        },
+    )?;
+    set_value(
+        &conn,
+        "release_memory",
+        if opts.release_memory { "true" } else { "false" },
+    )?;
+    conn.commit()
+}
+
+/// Deletes every stored setting except the saved file queue.
+pub fn reset_settings() -> SqlResult<()> {
+    let conn = init_db()?;
+    conn.execute("DELETE FROM settings WHERE key != 'queue'", [])?;
+    Ok(())
+}
+
+/// Loads the saved file queue, skipping files that no longer exist.
+pub fn load_queue() -> Vec<PathBuf> {
+    init_db()
+        .and_then(|conn| get_value(&conn, "queue"))
+        .map(|v| {
+            v.lines()
+                .map(PathBuf::from)
+                .filter(|p| p.is_file())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Saves file queue paths in display order.
+pub fn save_queue(paths: &[PathBuf]) {
+    let conn = match init_db() {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Cannot open settings database: {}", e);
+            return;
+        }
+    };
+    let joined = paths
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let _ = set_value(&conn, "queue", &joined);
+}
+
+/// Loads the summary of the last finished batch, if one was saved.
+pub fn load_last_run() -> Option<LastRun> {
+    let value = init_db()
+        .and_then(|conn| get_value(&conn, "last_run"))
+        .ok()?;
+    let mut fields = value.split(',').map(|f| f.parse::<u64>().ok());
+    let mut next = || fields.next().flatten();
+    Some(LastRun {
+        files: next()? as usize,
+        failed: next()? as usize,
+        input_bytes: next()?,
+        output_bytes: next()?,
+    })
+}
+
+/// Saves the summary of a finished batch, replacing the previous one.
+pub fn save_last_run(run: &LastRun) {
+    let conn = match init_db() {
+        Ok(c) => c,
+        Err(e) => {
+            log::error!("Cannot open settings database: {}", e);
+            return;
+        }
+    };
+    let value = format!(
+        "{},{},{},{}",
+        run.files, run.failed, run.input_bytes, run.output_bytes
     );
-    let _ = set_value(&conn, "max_batch_size", &opts.max_batch_size.to_string());
+    let _ = set_value(&conn, "last_run", &value);
 }
 
 /// Retrieves a single setting value by key.
@@ -179,6 +1114,10 @@ fn set_value(conn: &Connection, key: &str, value: &str) -> SqlResult<()> {
     conn.execute(
         "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
         [key, value],
-    )?;
+    )
+    .map_err(|e| {
+        log::warn!("Failed to save setting {}: {}", key, e);
+        e
+    })?;
     Ok(())
 }
@@ -1,10 +1,15 @@
 //! Application state and data structures for conversion options and file management.
 
-use std::collections::HashSet;
+use crate::constants::{DEFAULT_DATE_FORMAT, DEFAULT_MAX_FILE_SIZE_MB};
+use crate::convert::ZipSink;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::path::PathBuf;
+use std::sync::atomic::AtomicU64;
+use std::sync::Arc;
+use std::time::Duration;
 
 /// Supported output image formats.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ImageFormat {
     Jpeg,
     Png,
@@ -13,6 +18,38 @@ pub enum ImageFormat {
 
 impl ImageFormat {
     pub const ALL: [ImageFormat; 3] = [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP];
+
+    /// Returns whether the format can store transparency.
+    pub fn supports_alpha(&self) -> bool {
+        !matches!(self, ImageFormat::Jpeg)
+    }
+
+    /// Returns the file extension written for this format.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::WebP => "webp",
+        }
+    }
+
+    /// Quality seeded for new users: high for archival JPEG, lower where WebP looks the same.
+    pub fn default_quality(&self) -> u8 {
+        match self {
+            ImageFormat::Jpeg => 90,
+            ImageFormat::Png => 80,
+            ImageFormat::WebP => 75,
+        }
+    }
+
+    /// Returns the largest width or height the encoder can write.
+    pub fn max_dimension(&self) -> u32 {
+        match self {
+            ImageFormat::Jpeg => 65_535,
+            ImageFormat::Png => i32::MAX as u32,
+            ImageFormat::WebP => 16_383,
+        }
+    }
 }
 
 impl Default for ImageFormat {
@@ -35,12 +72,560 @@ impl std::fmt::Display for ImageFormat {
     }
 }
 
+/// Quick-set quality levels; `Custom` restores the last manually chosen value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityPreset {
+    Web,
+    Balanced,
+    Max,
+    Custom,
+}
+
+impl QualityPreset {
+    pub const ALL: [QualityPreset; 4] = [
+        QualityPreset::Web,
+        QualityPreset::Balanced,
+        QualityPreset::Max,
+        QualityPreset::Custom,
+    ];
+
+    /// Returns the button label for this preset.
+    pub fn label(self) -> &'static str {
+        match self {
+            QualityPreset::Web => "Web",
+            QualityPreset::Balanced => "Balanced",
+            QualityPreset::Max => "Max",
+            QualityPreset::Custom => "Custom",
+        }
+    }
+
+    /// Returns the quality this preset selects for the given format.
+    pub fn quality(self, format: ImageFormat, custom: u8) -> u8 {
+        match (self, format) {
+            (QualityPreset::Custom, _) => custom,
+            (QualityPreset::Web, ImageFormat::WebP) => 65,
+            (QualityPreset::Balanced, ImageFormat::WebP) => 78,
+            (QualityPreset::Max, ImageFormat::WebP) => 90,
+            (QualityPreset::Web, _) => 70,
+            (QualityPreset::Balanced, _) => 82,
+            (QualityPreset::Max, _) => 92,
+        }
+    }
+}
+
+/// Casing of the output file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExtensionCase {
+    #[default]
+    Lower,
+    Upper,
+    MatchInput,
+}
+
+impl ExtensionCase {
+    pub const ALL: [ExtensionCase; 3] = [
+        ExtensionCase::Lower,
+        ExtensionCase::Upper,
+        ExtensionCase::MatchInput,
+    ];
+}
+
+impl std::fmt::Display for ExtensionCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ExtensionCase::Lower => ".jpg (lowercase)",
+                ExtensionCase::Upper => ".JPG (uppercase)",
+                ExtensionCase::MatchInput => "Match input",
+            }
+        )
+    }
+}
+
+/// Modification time given to written output files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputTimestamp {
+    #[default]
+    Conversion,
+    SourceModified,
+    /// EXIF DateTimeOriginal, falling back to the source modification time.
+    CaptureDate,
+}
+
+impl OutputTimestamp {
+    pub const ALL: [OutputTimestamp; 3] = [
+        OutputTimestamp::Conversion,
+        OutputTimestamp::SourceModified,
+        OutputTimestamp::CaptureDate,
+    ];
+}
+
+impl std::fmt::Display for OutputTimestamp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OutputTimestamp::Conversion => "Time of conversion",
+                OutputTimestamp::SourceModified => "Source modified time",
+                OutputTimestamp::CaptureDate => "Capture date (EXIF)",
+            }
+        )
+    }
+}
+
+/// What double-clicking a row in the file list does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DoubleClickAction {
+    #[default]
+    Preview,
+    EditExternally,
+    ShowInFolder,
+}
+
+impl DoubleClickAction {
+    pub const ALL: [DoubleClickAction; 3] = [
+        DoubleClickAction::Preview,
+        DoubleClickAction::EditExternally,
+        DoubleClickAction::ShowInFolder,
+    ];
+}
+
+impl std::fmt::Display for DoubleClickAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                DoubleClickAction::Preview => "Show preview",
+                DoubleClickAction::EditExternally => "Edit externally",
+                DoubleClickAction::ShowInFolder => "Show in folder",
+            }
+        )
+    }
+}
+
+/// Which files the list shows; the queue itself is unaffected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFilter {
+    #[default]
+    All,
+    /// Not finished yet, including files being converted.
+    Pending,
+    Done,
+    Error,
+    /// Done without writing because the output already existed.
+    Skipped,
+}
+
+impl StatusFilter {
+    pub const ALL: [StatusFilter; 5] = [
+        StatusFilter::All,
+        StatusFilter::Pending,
+        StatusFilter::Done,
+        StatusFilter::Error,
+        StatusFilter::Skipped,
+    ];
+
+    pub fn matches(self, file: &FileItem) -> bool {
+        let skipped = file.report.as_ref().is_some_and(|r| r.skipped);
+        match self {
+            StatusFilter::All => true,
+            StatusFilter::Pending => {
+                matches!(file.status, FileStatus::Pending | FileStatus::Processing)
+            }
+            StatusFilter::Done => matches!(file.status, FileStatus::Done) && !skipped,
+            StatusFilter::Error => matches!(file.status, FileStatus::Error(_)),
+            StatusFilter::Skipped => matches!(file.status, FileStatus::Done) && skipped,
+        }
+    }
+}
+
+/// What to do when an output file already exists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExistingFilePolicy {
+    #[default]
+    Overwrite,
+    Skip,
+    Rename,
+}
+
+impl ExistingFilePolicy {
+    pub const ALL: [ExistingFilePolicy; 3] = [
+        ExistingFilePolicy::Overwrite,
+        ExistingFilePolicy::Skip,
+        ExistingFilePolicy::Rename,
+    ];
+}
+
+impl std::fmt::Display for ExistingFilePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ExistingFilePolicy::Overwrite => "Overwrite",
+                ExistingFilePolicy::Skip => "Skip",
+                ExistingFilePolicy::Rename => "Keep both",
+            }
+        )
+    }
+}
+
+/// Prefix and find/replace rule remembered in the naming history.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamingRule {
+    pub prefix: String,
+    pub find: String,
+    pub replace: String,
+}
+
+impl std::fmt::Display for NamingRule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut parts = Vec::new();
+        if !self.prefix.is_empty() {
+            parts.push(format!("{}…", self.prefix));
+        }
+        if !self.find.is_empty() {
+            parts.push(format!("{} → {}", self.find, self.replace));
+        }
+        write!(f, "{}", parts.join(" · "))
+    }
+}
+
+/// Order in which batch files are handed to the converter; the list itself is not reordered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ConversionOrder {
+    #[default]
+    AsListed,
+    SmallestFirst,
+    LargestFirst,
+}
+
+impl ConversionOrder {
+    pub const ALL: [ConversionOrder; 3] = [
+        ConversionOrder::AsListed,
+        ConversionOrder::SmallestFirst,
+        ConversionOrder::LargestFirst,
+    ];
+}
+
+impl std::fmt::Display for ConversionOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ConversionOrder::AsListed => "As listed",
+                ConversionOrder::SmallestFirst => "Smallest first",
+                ConversionOrder::LargestFirst => "Largest first",
+            }
+        )
+    }
+}
+
+/// How the resize fields are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ResizeMode {
+    /// Width and height as typed, with a blank side following the aspect ratio.
+    #[default]
+    Exact,
+    /// Shrink so the longest side is at most `longest_side`, never enlarging.
+    FitWithin,
+}
+
+impl ResizeMode {
+    pub const ALL: [ResizeMode; 2] = [ResizeMode::Exact, ResizeMode::FitWithin];
+}
+
+impl std::fmt::Display for ResizeMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ResizeMode::Exact => "Width × height",
+                ResizeMode::FitWithin => "Fit within",
+            }
+        )
+    }
+}
+
+/// Which frame or page of a multi-frame GIF or TIFF becomes the output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FrameChoice {
+    #[default]
+    First,
+    Last,
+    Number,
+}
+
+impl FrameChoice {
+    pub const ALL: [FrameChoice; 3] = [FrameChoice::First, FrameChoice::Last, FrameChoice::Number];
+}
+
+impl std::fmt::Display for FrameChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                FrameChoice::First => "First frame",
+                FrameChoice::Last => "Last frame",
+                FrameChoice::Number => "Frame #",
+            }
+        )
+    }
+}
+
+/// Resize settings remembered separately for each output format.
+#[derive(Debug, Clone, Default)]
+pub struct ResizeSettings {
+    pub resize: bool,
+    pub target_width: String,
+    pub target_height: String,
+}
+
+/// Crop rectangle in coordinates normalized to the upright source, each from 0 to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CropRect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl CropRect {
+    /// Spans the rectangle between two normalized corner points, clamped to the image.
+    pub fn between(a: (f32, f32), b: (f32, f32)) -> Self {
+        let (x0, x1) = (a.0.min(b.0).clamp(0.0, 1.0), a.0.max(b.0).clamp(0.0, 1.0));
+        let (y0, y1) = (a.1.min(b.1).clamp(0.0, 1.0), a.1.max(b.1).clamp(0.0, 1.0));
+        Self {
+            x: x0,
+            y: y0,
+            width: x1 - x0,
+            height: y1 - y0,
+        }
+    }
+
+    /// Returns `(x, y, width, height)` in pixels of an image of the given size, at least 1×1.
+    pub fn pixels(&self, width: u32, height: u32) -> (u32, u32, u32, u32) {
+        let x = ((self.x * width as f32) as u32).min(width.saturating_sub(1));
+        let y = ((self.y * height as f32) as u32).min(height.saturating_sub(1));
+        let w = ((self.width * width as f32).round() as u32)
+            .max(1)
+            .min(width - x);
+        let h = ((self.height * height as f32).round() as u32)
+            .max(1)
+            .min(height - y);
+        (x, y, w, h)
+    }
+}
+
+/// Tonal adjustments applied after resizing, each from -100 to 100 with 0 meaning unchanged,
+/// then an optional grayscale conversion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Adjustments {
+    pub brightness: i32,
+    pub contrast: i32,
+    pub saturation: i32,
+    pub grayscale: bool,
+    /// Weight the channels in linear light rather than on gamma-encoded values.
+    pub linear_grayscale: bool,
+}
+
+impl Adjustments {
+    pub fn is_neutral(&self) -> bool {
+        // The linear sub-option changes nothing on its own.
+        Adjustments {
+            linear_grayscale: false,
+            ..*self
+        } == Adjustments::default()
+    }
+}
+
+/// Color space the output is converted to and tagged with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OutputColorSpace {
+    #[default]
+    Srgb,
+    DisplayP3,
+    AdobeRgb,
+}
+
+impl OutputColorSpace {
+    pub const ALL: [OutputColorSpace; 3] = [
+        OutputColorSpace::Srgb,
+        OutputColorSpace::DisplayP3,
+        OutputColorSpace::AdobeRgb,
+    ];
+}
+
+impl std::fmt::Display for OutputColorSpace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OutputColorSpace::Srgb => "sRGB",
+                OutputColorSpace::DisplayP3 => "Display P3",
+                OutputColorSpace::AdobeRgb => "Adobe RGB (1998)",
+            }
+        )
+    }
+}
+
+/// Color chunks an sRGB PNG is tagged with; the pixels themselves are not converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PngColorTag {
+    /// An `sRGB` chunk.
+    #[default]
+    Srgb,
+    /// `gAMA` 1.0 with sRGB primaries in `cHRM`, for linear data.
+    Linear,
+    /// No color chunks at all.
+    None,
+}
+
+impl PngColorTag {
+    pub const ALL: [PngColorTag; 3] = [PngColorTag::Srgb, PngColorTag::Linear, PngColorTag::None];
+}
+
+impl std::fmt::Display for PngColorTag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                PngColorTag::Srgb => "sRGB",
+                PngColorTag::Linear => "Linear (gAMA 1.0)",
+                PngColorTag::None => "No color chunks",
+            }
+        )
+    }
+}
+
+/// How embedded ICC profiles of inputs are handled when decoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColorCorrection {
+    /// Convert to sRGB unless the profile already is sRGB.
+    #[default]
+    SkipSrgb,
+    Always,
+    Off,
+}
+
+impl ColorCorrection {
+    pub const ALL: [ColorCorrection; 3] = [
+        ColorCorrection::SkipSrgb,
+        ColorCorrection::Always,
+        ColorCorrection::Off,
+    ];
+}
+
+impl std::fmt::Display for ColorCorrection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                ColorCorrection::SkipSrgb => "Convert non-sRGB profiles",
+                ColorCorrection::Always => "Convert all profiles",
+                ColorCorrection::Off => "Ignore profiles",
+            }
+        )
+    }
+}
+
+/// UI language; `System` follows the OS locale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    System,
+    English,
+    Indonesian,
+    Spanish,
+}
+
+impl Language {
+    pub const ALL: [Language; 4] = [
+        Language::System,
+        Language::English,
+        Language::Indonesian,
+        Language::Spanish,
+    ];
+}
+
+impl std::fmt::Display for Language {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                Language::System => "System",
+                Language::English => "English",
+                Language::Indonesian => "Bahasa Indonesia",
+                Language::Spanish => "Español",
+            }
+        )
+    }
+}
+
+/// Row density of the file list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UiDensity {
+    #[default]
+    Comfortable,
+    Compact,
+}
+
+/// A reversible change to the file list, kept for undo and redo.
+#[derive(Debug, Clone)]
+pub enum ListEdit {
+    /// Files inserted at these positions, in ascending order.
+    Added(Vec<(usize, FileItem)>),
+    /// Files taken out of these positions, in ascending order.
+    Removed(Vec<(usize, FileItem)>),
+    /// A row dragged from one position to another.
+    Moved { from: usize, to: usize },
+}
+
 /// Represents a file in the conversion queue.
 #[derive(Debug, Clone)]
 pub struct FileItem {
     pub id: uuid::Uuid,
     pub path: PathBuf,
     pub status: FileStatus,
+    pub verified: bool,
+    pub info: Option<FileInfo>,
+    /// Automatic encoder decision reported by the last conversion.
+    pub note: Option<String>,
+    /// What the last conversion wrote; its size and dimensions are compared with `info`.
+    pub report: Option<crate::convert::ConversionReport>,
+    /// Wall time of the last conversion, shown to spot slow files.
+    pub elapsed: Option<Duration>,
+    /// Region drawn over the preview to keep; `None` converts the whole image.
+    pub crop: Option<CropRect>,
+}
+
+/// Outcome of a finished batch, kept in the settings database across launches.
+#[derive(Debug, Clone, Copy)]
+pub struct LastRun {
+    pub files: usize,
+    pub failed: usize,
+    pub input_bytes: u64,
+    pub output_bytes: u64,
+}
+
+/// Dimensions and size read in the background after a file is added.
+#[derive(Debug, Clone, Copy)]
+pub struct FileInfo {
+    pub width: u32,
+    pub height: u32,
+    pub size_bytes: u64,
+    pub frames: u32,
 }
 
 impl FileItem {
@@ -50,6 +635,20 @@ impl FileItem {
             id: uuid::Uuid::new_v4(),
             path,
             status: FileStatus::Pending,
+            verified: false,
+            info: None,
+            note: None,
+            report: None,
+            elapsed: None,
+            crop: None,
+        }
+    }
+
+    /// Size of the source, from prefetched info when available.
+    pub fn input_bytes(&self) -> Option<u64> {
+        match self.info {
+            Some(info) => Some(info.size_bytes),
+            None => std::fs::metadata(&self.path).ok().map(|m| m.len()),
         }
     }
 }
@@ -63,48 +662,291 @@ pub enum FileStatus {
     Error(String),
 }
 
+/// Leaves one core free so the system stays responsive during large batches.
+pub fn default_thread_limit() -> usize {
+    std::thread::available_parallelism()
+        .map(|n| n.get().saturating_sub(1))
+        .unwrap_or(1)
+        .max(1)
+}
+
 /// User-configurable conversion options.
 #[derive(Debug, Clone)]
 pub struct ConversionOptions {
     pub format: ImageFormat,
+    /// Write JPEG, PNG, and WebP inputs back in their own format; `format` covers the rest.
+    pub keep_source_format: bool,
+    /// Further formats every input is also written in, e.g. a JPEG fallback beside WebP.
+    pub extra_formats: Vec<ImageFormat>,
     pub quality: u8,
+    pub custom_quality: u8,
+    /// Set once the user picks a quality; until then quality follows the format's default.
+    pub quality_customized: bool,
+    /// Drive every format from one perceptual slider instead of the raw encoder quality.
+    pub unified_quality: bool,
+    /// 0–100 perceptual quality used in unified mode.
+    pub perceptual_quality: u8,
     pub png_compressed: bool,
+    /// Write indexed PNG when the source PNG was indexed.
+    pub keep_png_palette: bool,
+    /// Decode 10/12-bit HEIC at full depth and write 16-bit PNG.
+    pub heic_high_bit_depth: bool,
+    pub color_space: OutputColorSpace,
+    pub color_correction: ColorCorrection,
+    /// Chunks marking the color space of PNGs written in sRGB.
+    pub png_color_tag: PngColorTag,
+    /// External sRGB profile embedded in JPEG/WebP output instead of the bundled one.
+    pub custom_srgb_icc: Option<PathBuf>,
+    /// Density written to JPEG/PNG headers; 0 keeps the encoder default.
+    pub dpi: u16,
+    pub webp_lossless: bool,
+    /// Encode every frame of animated GIF/WebP inputs when the output is WebP.
+    pub keep_animation: bool,
+    pub auto_lossless: bool,
     pub resize: bool,
+    pub resize_mode: ResizeMode,
     pub target_width: String,
     pub target_height: String,
+    /// Longest output side in pixels for `ResizeMode::FitWithin`; blank keeps the size.
+    pub longest_side: String,
+    /// Keep the source aspect ratio even with both sides set, fitting inside them; the UI
+    /// fills in the other side as one is typed.
+    pub lock_aspect: bool,
+    /// With both width and height set, fit the image inside them and fill the rest with
+    /// `pad_color` (`#rrggbb`) instead of stretching.
+    pub pad_to_fit: bool,
+    pub pad_color: String,
+    /// Pixel budget in megapixels that larger outputs are scaled down to fit; blank is off.
+    pub max_megapixels: String,
+    /// Crop of the file being converted, applied before resizing; set per file, never saved.
+    pub crop: Option<CropRect>,
+    /// Crop solid black bars off the edges before resizing.
+    pub remove_letterbox: bool,
+    /// Highest channel value, 0-255, still counted as black by letterbox removal.
+    pub letterbox_tolerance: u8,
+    /// Resize settings of the formats not currently selected.
+    pub format_resize: HashMap<ImageFormat, ResizeSettings>,
     pub prefix: String,
     pub find_pattern: String,
     pub replace_with: String,
     pub auto_suffix: bool,
+    /// Letter after the short side in the auto suffix (`-1080p`).
+    pub suffix_size_letter: String,
+    /// Letter after the quality in the auto suffix (`-85q`).
+    pub suffix_quality_letter: String,
+    /// Append the resize mode (`-fit`, `-exact`) to the auto suffix.
+    pub suffix_mode: bool,
+    pub extension_case: ExtensionCase,
+    pub output_timestamp: OutputTimestamp,
+    pub double_click_action: DoubleClickAction,
+    pub filename_template: String,
+    pub date_format: String,
     pub use_custom_output: bool,
+    /// Sort outputs into a subfolder named after their format (`jpg/`, `png/`, `webp/`).
+    pub format_subfolders: bool,
+    /// Write the batch into a single archive instead of loose files.
+    pub zip_output: bool,
+    /// Archive path; `None` uses `converted.zip` in the output folder.
+    pub zip_path: Option<PathBuf>,
+    /// Folder of the most recently added file; file dialogs open here.
+    pub last_input_dir: Option<PathBuf>,
     pub custom_output_path: Option<PathBuf>,
+    pub existing_file_policy: ExistingFilePolicy,
+    /// Overwrite existing outputs without the confirmation prompt.
+    pub overwrite_without_asking: bool,
+    /// The overwrite prompt only appears when more files than this already exist.
+    pub overwrite_prompt_threshold: usize,
+    pub recent_output_dirs: Vec<PathBuf>,
+    /// Naming rules of recent batches, newest first.
+    pub naming_history: Vec<NamingRule>,
     pub keep_metadata: bool,
+    /// Rebuild the EXIF thumbnail from the output when keeping metadata; off strips it.
+    pub regenerate_exif_thumbnail: bool,
+    /// Carry the source orientation tag over instead of resetting it to upright.
+    pub keep_exif_orientation: bool,
+    /// Leave pixels as stored instead of rotating them by the EXIF orientation tag.
+    pub ignore_exif_orientation: bool,
+    pub flatten_alpha: bool,
+    pub frame_choice: FrameChoice,
+    /// 1-based frame used when `frame_choice` is `Number`.
+    pub frame_number: u32,
+    pub adjustments: Adjustments,
+    pub verify_output: bool,
+    /// Re-decodes lossless outputs and fails them if the alpha channel changed.
+    pub verify_alpha: bool,
     pub generate_log: bool,
+    /// Write a `.txt` caption next to each output, filled from `caption_template`.
+    pub write_captions: bool,
+    /// Copy queued files that are not images (`.txt`, `.json` sidecars) to the output folder
+    /// unchanged instead of failing them.
+    pub copy_sidecars: bool,
+    pub caption_template: String,
+    /// Contact sheet grid: thumbnails per row, square cell size in pixels, `#rrggbb`
+    /// background, and whether file names are printed under the thumbnails.
+    pub contact_sheet_columns: u32,
+    pub contact_sheet_cell: u32,
+    pub contact_sheet_background: String,
+    pub contact_sheet_labels: bool,
+    /// Artist and copyright stamped into JPEG EXIF; blank fields are not written.
+    pub exif_artist: String,
+    pub exif_copyright: String,
+    pub failure_log: bool,
     pub add_numbering: bool,
     pub is_dark_mode: bool,
+    /// Show only format, quality, and the file list; new users start here.
+    pub simple_view: bool,
+    pub language: Language,
+    pub ui_density: UiDensity,
+    pub file_list_height: f32,
     pub max_batch_size: usize,
+    pub max_file_size_mb: u64,
+    pub thread_limit: usize,
+    pub conversion_order: ConversionOrder,
+    pub restore_queue: bool,
+    pub fail_fast: bool,
+    /// Remove each source after its output was written (and verified, when enabled).
+    pub delete_originals: bool,
+    /// Set once the user accepted the warning shown when first enabling `delete_originals`.
+    pub delete_originals_confirmed: bool,
+    /// Return freed allocator memory to the OS after each batch, at the cost of a short pause.
+    pub release_memory: bool,
+    /// Trades quality for speed while previewing a batch; never saved.
+    pub draft_mode: bool,
 }
 
 impl Default for ConversionOptions {
     fn default() -> Self {
         Self {
             format: ImageFormat::Jpeg,
+            keep_source_format: false,
+            extra_formats: Vec::new(),
             quality: 80,
+            custom_quality: 80,
+            quality_customized: false,
+            unified_quality: false,
+            perceptual_quality: 75,
             png_compressed: true,
+            keep_png_palette: true,
+            heic_high_bit_depth: false,
+            color_space: OutputColorSpace::Srgb,
+            color_correction: ColorCorrection::SkipSrgb,
+            png_color_tag: PngColorTag::Srgb,
+            custom_srgb_icc: None,
+            dpi: 0,
+            webp_lossless: false,
+            keep_animation: true,
+            auto_lossless: true,
             resize: false,
+            resize_mode: ResizeMode::Exact,
             target_width: String::new(),
             target_height: String::new(),
+            longest_side: String::new(),
+            max_megapixels: String::new(),
+            lock_aspect: false,
+            pad_to_fit: false,
+            pad_color: "#000000".to_string(),
+            crop: None,
+            remove_letterbox: false,
+            letterbox_tolerance: 16,
+            format_resize: HashMap::new(),
             prefix: String::new(),
             find_pattern: String::new(),
             replace_with: String::new(),
+            extension_case: ExtensionCase::Lower,
+            output_timestamp: OutputTimestamp::Conversion,
+            double_click_action: DoubleClickAction::Preview,
             auto_suffix: false,
+            suffix_size_letter: "p".to_string(),
+            suffix_quality_letter: "q".to_string(),
+            suffix_mode: false,
+            filename_template: String::new(),
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
             use_custom_output: false,
+            format_subfolders: false,
+            zip_output: false,
+            zip_path: None,
+            last_input_dir: None,
             custom_output_path: None,
+            existing_file_policy: ExistingFilePolicy::Overwrite,
+            overwrite_without_asking: false,
+            overwrite_prompt_threshold: 0,
+            recent_output_dirs: Vec::new(),
+            naming_history: Vec::new(),
             keep_metadata: false,
+            regenerate_exif_thumbnail: true,
+            keep_exif_orientation: false,
+            ignore_exif_orientation: false,
+            flatten_alpha: false,
+            frame_choice: FrameChoice::First,
+            frame_number: 1,
+            adjustments: Adjustments::default(),
+            verify_output: false,
+            verify_alpha: false,
             generate_log: false,
+            write_captions: false,
+            copy_sidecars: false,
+            contact_sheet_columns: 6,
+            contact_sheet_cell: 256,
+            contact_sheet_background: "#ffffff".to_string(),
+            contact_sheet_labels: true,
+            caption_template: String::new(),
+            exif_artist: String::new(),
+            exif_copyright: String::new(),
+            failure_log: false,
             add_numbering: false,
             is_dark_mode: false,
+            simple_view: true,
+            language: Language::System,
+            ui_density: UiDensity::Comfortable,
+            file_list_height: crate::theme::dimensions::FILE_LIST_HEIGHT,
             max_batch_size: 50,
+            max_file_size_mb: DEFAULT_MAX_FILE_SIZE_MB,
+            thread_limit: default_thread_limit(),
+            conversion_order: ConversionOrder::AsListed,
+            restore_queue: false,
+            fail_fast: false,
+            delete_originals: false,
+            delete_originals_confirmed: false,
+            release_memory: true,
+            draft_mode: false,
+        }
+    }
+}
+
+/// Red, green, and blue value counts of a source image.
+#[derive(Debug, Clone)]
+pub struct Histogram {
+    pub channels: Box<[[u32; 256]; 3]>,
+}
+
+/// Output size and fidelity of one encode in a quality sweep.
+#[derive(Debug, Clone, Copy)]
+pub struct SweepPoint {
+    pub quality: u8,
+    pub size: u64,
+    /// Structural similarity to a lossless encode; `None` when the sizes differ.
+    pub ssim: Option<f64>,
+}
+
+/// Rendered preview; the pixels are shared with the handle for drawing the crop overlay.
+#[derive(Debug, Clone)]
+pub struct Preview {
+    pub handle: iced::widget::image::Handle,
+    pub width: u32,
+    pub height: u32,
+    pub pixels: bytes::Bytes,
+}
+
+impl Preview {
+    /// Wraps rendered pixels in an image handle.
+    pub fn new(img: image::RgbaImage) -> Self {
+        let (width, height) = img.dimensions();
+        let pixels = bytes::Bytes::from(img.into_raw());
+        Self {
+            handle: iced::widget::image::Handle::from_pixels(width, height, pixels.clone()),
+            width,
+            height,
+            pixels,
         }
     }
 }
@@ -113,10 +955,80 @@ impl Default for ConversionOptions {
 pub struct AppState {
     pub files: Vec<FileItem>,
     pub selected_indices: HashSet<usize>,
+    /// Row a press-drag selection started on, and whether it selects or deselects.
+    pub band_anchor: Option<(usize, bool)>,
+    /// Selection before the press-drag started, restored outside the dragged range.
+    pub band_base: HashSet<usize>,
+    /// Row and time of the last click, to recognize a double-click.
+    pub last_click: Option<(usize, std::time::Instant)>,
+    /// List edits that Ctrl+Z steps back through, newest last.
+    pub undo_stack: Vec<ListEdit>,
+    pub redo_stack: Vec<ListEdit>,
+    /// The last added chunk has more following, so the next one joins its undo entry.
+    pub add_batch_open: bool,
+    pub preview_id: Option<uuid::Uuid>,
+    pub previews: HashMap<uuid::Uuid, Option<Preview>>,
+    /// Kept across preview refreshes since adjustments do not change the source.
+    pub histograms: HashMap<uuid::Uuid, Histogram>,
     pub is_processing: bool,
+    /// Sample files are being converted in memory to estimate savings.
+    pub preflight_running: bool,
+    /// Quality sweep of the previewed file, and whether one is being encoded.
+    pub quality_sweep: Option<(uuid::Uuid, Vec<SweepPoint>)>,
+    pub sweep_running: bool,
+    /// A contact sheet of the queue is being composited.
+    pub contact_sheet_running: bool,
+    /// Total input and output bytes of the last finished batch.
+    pub batch_sizes: Option<(u64, u64)>,
+    /// Summary of the previous session's last batch, shown until a new run starts.
+    pub last_run: Option<LastRun>,
+    /// Input bytes finished and in total for the running batch, weighting the progress bar.
+    pub batch_progress: (u64, u64),
+    /// Files of the running conversion not yet dispatched, in dispatch order.
+    pub conversion_queue: VecDeque<uuid::Uuid>,
+    /// Files the current run is limited to; `None` converts the whole queue.
+    pub batch_scope: Option<HashSet<uuid::Uuid>>,
+    /// Archive the running batch writes into when ZIP output is on.
+    pub zip_sink: Option<Arc<ZipSink>>,
+    /// Bumped to cancel queued conversion tasks; tasks skip work if it changed since spawn.
+    pub batch_generation: Arc<AtomicU64>,
     pub options: ConversionOptions,
-    pub dragging_index: Option<usize>,
-    pub hovered_index: Option<usize>,
+    pub dragging_id: Option<uuid::Uuid>,
+    pub hovered_id: Option<uuid::Uuid>,
+    /// Statuses of the files the list shows.
+    pub status_filter: StatusFilter,
+    pub cursor_y: f32,
+    pub list_resize_origin: Option<(f32, f32)>,
+    /// Vertical offset of the file list, pinned while a batch updates row statuses.
+    pub file_list_scroll_y: f32,
+    /// Furthest the file list can scroll, as last reported by the list itself.
+    pub file_list_scroll_max: Option<f32>,
+    /// Cursor height within the file list card while hovering it.
+    pub file_list_pointer_y: Option<f32>,
+    /// Last cursor position over the preview, normalized to its size.
+    pub crop_cursor: Option<(f32, f32)>,
+    /// Normalized corner where the crop drag in progress started.
+    pub crop_anchor: Option<(f32, f32)>,
+    /// Preview of `preview_id` with everything outside its crop dimmed.
+    pub crop_preview: Option<(uuid::Uuid, iced::widget::image::Handle)>,
+    /// Files opened in an external editor, with their modified time at launch, re-read when
+    /// the window regains focus.
+    pub external_edits: Vec<(uuid::Uuid, Option<std::time::SystemTime>)>,
+    pub show_advanced: bool,
+    pub show_log: bool,
+    pub show_about: bool,
+    /// Shown once per session when settings could not be saved where expected.
+    pub settings_warning: Option<String>,
+    pub settings_warned: bool,
+    /// Replaces the idle status text after Convert was clicked with an empty queue.
+    pub empty_queue_hint: bool,
+    pub url_input: String,
+    /// Max file size as typed, until Enter saves it; `None` shows the saved value.
+    pub max_file_size_input: Option<String>,
+    /// URLs that could not be downloaded, with the reason; shown until dismissed.
+    pub failed_downloads: Vec<(String, String)>,
+    /// Files dropped from the OS that are waiting to be added as one batch.
+    pub pending_drops: Vec<PathBuf>,
 }
 
 impl Default for AppState {
@@ -124,10 +1036,60 @@ impl Default for AppState {
         Self {
             files: Vec::new(),
             selected_indices: HashSet::new(),
+            band_anchor: None,
+            band_base: HashSet::new(),
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+            add_batch_open: false,
+            last_click: None,
+            preview_id: None,
+            previews: HashMap::new(),
+            histograms: HashMap::new(),
             is_processing: false,
+            preflight_running: false,
+            quality_sweep: None,
+            sweep_running: false,
+            contact_sheet_running: false,
+            batch_sizes: None,
+            last_run: None,
+            batch_progress: (0, 0),
+            conversion_queue: VecDeque::new(),
+            batch_scope: None,
+            zip_sink: None,
+            batch_generation: Arc::new(AtomicU64::new(0)),
             options: ConversionOptions::default(),
-            dragging_index: None,
-            hovered_index: None,
+            dragging_id: None,
+            hovered_id: None,
+            status_filter: StatusFilter::All,
+            cursor_y: 0.0,
+            list_resize_origin: None,
+            file_list_scroll_y: 0.0,
+            file_list_scroll_max: None,
+            file_list_pointer_y: None,
+            crop_cursor: None,
+            crop_anchor: None,
+            crop_preview: None,
+            external_edits: Vec::new(),
+            show_advanced: false,
+            show_log: false,
+            show_about: false,
+            settings_warning: None,
+            empty_queue_hint: false,
+            failed_downloads: Vec::new(),
+            settings_warned: false,
+            url_input: String::new(),
+            max_file_size_input: None,
+            pending_drops: Vec::new(),
+        }
+    }
+}
+
+impl AppState {
+    /// Whether a file belongs to the current (or most recent) conversion run.
+    pub fn in_batch(&self, file: &FileItem) -> bool {
+        match &self.batch_scope {
+            Some(ids) => ids.contains(&file.id),
+            None => true,
         }
     }
 }
@@ -54,6 +54,13 @@ pub mod spacing {
 /// Component dimensions.
 pub mod dimensions {
     pub const FILE_LIST_HEIGHT: f32 = 220.0;
+    pub const FILE_LIST_MIN_HEIGHT: f32 = 120.0;
+    pub const FILE_LIST_MAX_HEIGHT: f32 = 1200.0;
+    pub const SPLITTER_HEIGHT: f32 = 6.0;
+    pub const PREVIEW_HEIGHT: f32 = 240.0;
+    pub const HISTOGRAM_HEIGHT: f32 = 72.0;
+    pub const SWEEP_CHART_HEIGHT: f32 = 96.0;
+    pub const LOG_PANEL_HEIGHT: f32 = 180.0;
     pub const CARD_RADIUS: f32 = 12.0;
     pub const BUTTON_RADIUS: f32 = 8.0;
     pub const INPUT_RADIUS: f32 = 8.0;
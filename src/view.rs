@@ -1,72 +1,144 @@
 //! UI components and layout for the image converter application.
 
+use crate::convert::{
+    cropped_dimensions, encoder_quality, is_valid_date_format, parse_max_file_size, png_effort,
+    resized_dimensions, unknown_template_tokens, SourceFormat, TEMPLATE_TOKENS,
+};
+use crate::i18n::{strings, Strings};
 use crate::message::Message;
-use crate::state::{AppState, FileItem, FileStatus, ImageFormat};
+use crate::state::{
+    AppState, ColorCorrection, ConversionOrder, DoubleClickAction, ExistingFilePolicy,
+    ExtensionCase, FileInfo, FileItem, FileStatus, FrameChoice, Histogram, ImageFormat, Language,
+    NamingRule, OutputColorSpace, OutputTimestamp, PngColorTag, Preview, QualityPreset, ResizeMode,
+    StatusFilter, SweepPoint, UiDensity,
+};
 use crate::theme::{colors, dark, dimensions, spacing, typography};
 use iced::widget::{
-    button, checkbox, column, container, horizontal_space, mouse_area, pick_list, row, scrollable,
-    slider, text, text_input, vertical_space,
+    button, canvas, checkbox, column, container, horizontal_space, image, mouse_area, pick_list,
+    row, scrollable, slider, text, text_input, vertical_space,
 };
 use iced::Length::Fixed;
-use iced::{Background, Color, Element, Length, Theme};
+use iced::{Background, Color, Element, Length, Point, Rectangle, Theme};
+
+/// Text inputs without an input handler are drawn disabled by iced.
+trait OnInputIf {
+    fn on_input_if(self, enabled: bool, on_input: fn(String) -> Message) -> Self;
+}
+
+impl OnInputIf for iced::widget::TextInput<'_, Message> {
+    fn on_input_if(self, enabled: bool, on_input: fn(String) -> Message) -> Self {
+        if enabled {
+            self.on_input(on_input)
+        } else {
+            self
+        }
+    }
+}
 
 /// Main view function rendering the entire UI.
 pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
     let is_dark = state.options.is_dark_mode;
+    // Running tasks already hold a copy of the options, so edits wait until the batch ends.
+    let editable = !state.is_processing;
+    let t = strings(state.options.language);
 
-    let (bg, _surface, border, txt, txt_secondary, primary, success, error, processing) = if is_dark
-    {
-        (
-            dark::BACKGROUND,
-            dark::SURFACE,
-            dark::BORDER,
-            dark::TEXT,
-            dark::TEXT_SECONDARY,
-            dark::PRIMARY,
-            dark::SUCCESS,
-            dark::ERROR,
-            dark::PROCESSING,
-        )
-    } else {
-        (
-            colors::BACKGROUND,
-            colors::SURFACE,
-            colors::BORDER,
-            colors::TEXT,
-            colors::TEXT_SECONDARY,
-            colors::PRIMARY,
-            colors::SUCCESS,
-            colors::ERROR,
-            colors::PROCESSING,
-        )
-    };
+    let (bg, _surface, border, txt, txt_secondary, primary, success, error, warning, processing) =
+        if is_dark {
+            (
+                dark::BACKGROUND,
+                dark::SURFACE,
+                dark::BORDER,
+                dark::TEXT,
+                dark::TEXT_SECONDARY,
+                dark::PRIMARY,
+                dark::SUCCESS,
+                dark::ERROR,
+                dark::WARNING,
+                dark::PROCESSING,
+            )
+        } else {
+            (
+                colors::BACKGROUND,
+                colors::SURFACE,
+                colors::BORDER,
+                colors::TEXT,
+                colors::TEXT_SECONDARY,
+                colors::PRIMARY,
+                colors::SUCCESS,
+                colors::ERROR,
+                colors::WARNING,
+                colors::PROCESSING,
+            )
+        };
 
     // Header section
     let header = container(
         row![
-            text("Image Converter")
+            text(t.title)
                 .size(typography::TITLE)
                 .style(iced::theme::Text::Color(txt)),
             horizontal_space(),
-            checkbox("Dark Mode", state.options.is_dark_mode)
+            pick_list(
+                &Language::ALL[..],
+                Some(state.options.language),
+                Message::LanguageSelected,
+            )
+            .placeholder(t.language)
+            .text_size(typography::CAPTION)
+            .padding(spacing::XS),
+            checkbox(t.simple_view, state.options.simple_view)
+                .on_toggle(Message::SimpleViewToggled)
+                .text_size(typography::BODY),
+            checkbox(t.dark_mode, state.options.is_dark_mode)
                 .on_toggle(Message::DarkThemeToggled)
                 .text_size(typography::BODY)
         ]
+        .spacing(spacing::LG)
         .align_items(iced::Alignment::Center),
     )
     .padding([spacing::LG, spacing::XL])
     .width(Length::Fill);
 
     // Action bar with file selection and convert button
-    let add_files_btn = button(text("+ Select Files").size(typography::BODY))
+    let add_files_btn = button(text(t.select_files).size(typography::BODY))
         .on_press(Message::AddFilesClicked)
         .padding([spacing::SM, spacing::LG])
         .style(iced::theme::Button::Primary);
 
-    let convert_label = if state.is_processing {
-        "Processing..."
+    let url_row = row![
+        text_input(t.url_placeholder, &state.url_input)
+            .on_input(Message::UrlInputChanged)
+            .on_submit(Message::AddUrlClicked)
+            .padding(spacing::SM),
+        button(text(t.add_url).size(typography::CAPTION))
+            .on_press(Message::AddUrlClicked)
+            .padding([spacing::XS, spacing::SM])
+            .style(iced::theme::Button::Secondary),
+        button(text(t.import_url_list).size(typography::CAPTION))
+            .on_press(Message::ImportUrlListClicked)
+            .padding([spacing::XS, spacing::SM])
+            .style(iced::theme::Button::Secondary),
+        button(text(t.import_file_list).size(typography::CAPTION))
+            .on_press(Message::ImportFileListClicked)
+            .padding([spacing::XS, spacing::SM])
+            .style(iced::theme::Button::Secondary)
+    ]
+    .spacing(spacing::SM)
+    .align_items(iced::Alignment::Center);
+
+    // While a batch runs the convert button cancels it.
+    let (convert_label, convert_message, convert_style) = if state.is_processing {
+        (
+            t.cancel_conversion,
+            Some(Message::CancelConversion),
+            iced::theme::Button::Destructive,
+        )
     } else {
-        "Start Conversion"
+        (
+            t.start_conversion,
+            (!state.files.is_empty()).then_some(Message::ConvertClicked),
+            iced::theme::Button::Primary,
+        )
     };
 
     let convert_btn = button(
@@ -74,99 +146,414 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
             .width(Length::Fill)
             .center_x(),
     )
-    .on_press(Message::ConvertClicked)
+    .on_press_maybe(convert_message)
     .padding([spacing::MD, spacing::XL])
     .width(Length::FillPortion(2))
-    .style(iced::theme::Button::Primary);
+    .style(convert_style);
 
-    // Format and quality section
-    let format_pick = pick_list(
-        &ImageFormat::ALL[..],
-        Some(state.options.format),
-        Message::FormatSelected,
-    )
-    .placeholder("Format")
-    .padding(spacing::SM);
+    let preflight_btn = button(text(t.estimate_savings).size(typography::BODY))
+        .on_press_maybe(
+            (!state.is_processing && !state.preflight_running && !state.files.is_empty())
+                .then_some(Message::PreflightClicked),
+        )
+        .padding([spacing::MD, spacing::LG])
+        .style(iced::theme::Button::Secondary);
 
-    let quality_section: Element<'_, Message> = match state.options.format {
-        ImageFormat::Png => row![checkbox("Optimize PNG", state.options.png_compressed)
-            .on_toggle(Message::PngCompressionToggled)
-            .text_size(typography::BODY)]
+    let convert_selected_btn = button(text(t.convert_selected).size(typography::BODY))
+        .on_press_maybe(
+            (!state.is_processing && !state.selected_indices.is_empty())
+                .then_some(Message::ConvertSelectedClicked),
+        )
+        .padding([spacing::MD, spacing::LG])
+        .style(iced::theme::Button::Secondary);
+
+    // Format and quality section; every checked format is written, the first checked is main.
+    let format_checks = ImageFormat::ALL
+        .iter()
+        .fold(row![], |r, &format| {
+            let checked =
+                format == state.options.format || state.options.extra_formats.contains(&format);
+            r.push(
+                checkbox(format.to_string(), checked)
+                    .on_toggle_maybe(editable.then_some(move |v| Message::FormatToggled(format, v)))
+                    .text_size(typography::BODY),
+            )
+        })
+        .spacing(spacing::MD)
+        .padding([spacing::XS, 0]);
+
+    let raw_quality: Element<'_, Message> = match state.options.format {
+        ImageFormat::Png => row![
+            checkbox(t.optimize_png, state.options.png_compressed)
+                .on_toggle_maybe(editable.then_some(Message::PngCompressionToggled))
+                .text_size(typography::BODY),
+            checkbox(t.keep_palette, state.options.keep_png_palette)
+                .on_toggle_maybe(editable.then_some(Message::KeepPngPaletteToggled))
+                .text_size(typography::BODY),
+            checkbox(t.heic_high_bit_depth, state.options.heic_high_bit_depth)
+                .on_toggle_maybe(editable.then_some(Message::HeicHighBitDepthToggled))
+                .text_size(typography::BODY)
+        ]
+        .spacing(spacing::LG)
         .align_items(iced::Alignment::Center)
         .into(),
         _ => {
             let quality_str = state.options.quality.to_string();
-            row![
-                text("Quality")
-                    .size(typography::BODY)
-                    .style(iced::theme::Text::Color(txt_secondary)),
-                slider(1..=100, state.options.quality, Message::QualityChanged).width(Fixed(140.0)),
-                text_input("", &quality_str)
-                    .on_input(Message::QualityInputChanged)
-                    .width(Fixed(48.0))
-                    .padding(spacing::XS)
+            let presets =
+                QualityPreset::ALL
+                    .iter()
+                    .fold(row![].spacing(spacing::XXS), |r, &preset| {
+                        let active = preset
+                            .quality(state.options.format, state.options.custom_quality)
+                            == state.options.quality;
+                        r.push(
+                            button(text(preset.label()).size(typography::CAPTION))
+                                .on_press_maybe(
+                                    editable.then_some(Message::QualityPresetSelected(preset)),
+                                )
+                                .padding([spacing::XXS, spacing::XS])
+                                .style(if active {
+                                    iced::theme::Button::Primary
+                                } else {
+                                    iced::theme::Button::Secondary
+                                }),
+                        )
+                    });
+            column![
+                row![
+                    text(t.quality)
+                        .size(typography::BODY)
+                        .style(iced::theme::Text::Color(txt_secondary)),
+                    slider(1..=100, state.options.quality, Message::QualityChanged)
+                        .on_release(Message::SliderReleased)
+                        .width(Fixed(140.0)),
+                    text_input("", &quality_str)
+                        .on_input_if(editable, Message::QualityInputChanged)
+                        .width(Fixed(48.0))
+                        .padding(spacing::XS)
+                ]
+                .spacing(spacing::SM)
+                .align_items(iced::Alignment::Center),
+                presets,
+                text(
+                    if state.options.format == ImageFormat::Jpeg && state.options.quality >= 100 {
+                        t.near_lossless_jpeg
+                    } else {
+                        ""
+                    }
+                )
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary))
             ]
-            .spacing(spacing::SM)
-            .align_items(iced::Alignment::Center)
+            .spacing(spacing::XS)
             .into()
         }
     };
 
-    let metadata_row: Element<'_, Message> = if matches!(state.options.format, ImageFormat::Jpeg) {
-        checkbox("Keep Metadata", state.options.keep_metadata)
-            .on_toggle(Message::ToggleKeepMetadata)
-            .text_size(typography::BODY)
-            .into()
+    // Unified mode swaps the raw encoder value for one perceptual slider; PNG keeps its options.
+    let quality_section = if state.options.unified_quality {
+        let mapped = match state.options.format {
+            ImageFormat::Png => format!("→ {} {}/6", t.png_effort, png_effort(&state.options)),
+            format => format!("→ {} {}", format, encoder_quality(&state.options)),
+        };
+        column![]
+            .push_maybe((state.options.format == ImageFormat::Png).then_some(raw_quality))
+            .push(
+                row![
+                    text(t.visual_quality)
+                        .size(typography::BODY)
+                        .style(iced::theme::Text::Color(txt_secondary)),
+                    slider(
+                        0..=100,
+                        state.options.perceptual_quality,
+                        Message::PerceptualQualityChanged
+                    )
+                    .on_release(Message::SliderReleased)
+                    .width(Fixed(140.0)),
+                    text(mapped)
+                        .size(typography::CAPTION)
+                        .style(iced::theme::Text::Color(txt_secondary))
+                ]
+                .spacing(spacing::SM)
+                .align_items(iced::Alignment::Center),
+            )
+    } else {
+        column![raw_quality]
+    }
+    .push(
+        checkbox(t.unified_quality, state.options.unified_quality)
+            .on_toggle_maybe(editable.then_some(Message::ToggleUnifiedQuality))
+            .text_size(typography::CAPTION),
+    )
+    .spacing(spacing::XS);
+
+    // Credits apply to every JPEG written, including JPEG inputs kept in their own format.
+    let credits_row: Element<'_, Message> = if state.options.format == ImageFormat::Jpeg
+        || state.options.extra_formats.contains(&ImageFormat::Jpeg)
+        || state.options.keep_source_format
+    {
+        row![
+            text(t.artist)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("", &state.options.exif_artist)
+                .on_input_if(editable, Message::ExifArtistChanged)
+                .padding(spacing::XS)
+                .size(typography::CAPTION),
+            text(t.copyright)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("", &state.options.exif_copyright)
+                .on_input_if(editable, Message::ExifCopyrightChanged)
+                .padding(spacing::XS)
+                .size(typography::CAPTION),
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center)
+        .into()
+    } else {
+        horizontal_space().height(Fixed(0.0)).into()
+    };
+
+    let metadata_row = row![
+        checkbox(t.keep_metadata, state.options.keep_metadata)
+            .on_toggle_maybe(editable.then_some(Message::ToggleKeepMetadata))
+            .text_size(typography::BODY),
+        checkbox(t.exif_thumbnail, state.options.regenerate_exif_thumbnail)
+            .on_toggle_maybe(
+                (editable && state.options.keep_metadata)
+                    .then_some(Message::ToggleRegenerateExifThumbnail)
+            )
+            .text_size(typography::CAPTION),
+        checkbox(t.keep_orientation, state.options.keep_exif_orientation)
+            .on_toggle_maybe(
+                (editable && state.options.keep_metadata)
+                    .then_some(Message::ToggleKeepExifOrientation)
+            )
+            .text_size(typography::CAPTION),
+        checkbox(t.ignore_orientation, state.options.ignore_exif_orientation)
+            .on_toggle_maybe(editable.then_some(Message::ToggleIgnoreExifOrientation))
+            .text_size(typography::CAPTION)
+    ]
+    .spacing(spacing::LG);
+
+    let format_row: Element<'_, Message> = if matches!(state.options.format, ImageFormat::Jpeg) {
+        row![checkbox(t.flatten_alpha, state.options.flatten_alpha)
+            .on_toggle_maybe(editable.then_some(Message::ToggleFlattenAlpha))
+            .text_size(typography::BODY)]
+        .spacing(spacing::LG)
+        .into()
+    } else if matches!(state.options.format, ImageFormat::WebP) {
+        row![
+            checkbox(t.lossless, state.options.webp_lossless)
+                .on_toggle_maybe(editable.then_some(Message::WebpLosslessToggled))
+                .text_size(typography::BODY),
+            checkbox(t.auto_lossless, state.options.auto_lossless)
+                .on_toggle_maybe(editable.then_some(Message::AutoLosslessToggled))
+                .text_size(typography::BODY),
+            checkbox(t.keep_animation, state.options.keep_animation)
+                .on_toggle_maybe(editable.then_some(Message::KeepAnimationToggled))
+                .text_size(typography::BODY)
+        ]
+        .spacing(spacing::LG)
+        .into()
+    } else {
+        horizontal_space().height(Fixed(0.0)).into()
+    };
+
+    let dpi_str = if state.options.dpi > 0 {
+        state.options.dpi.to_string()
+    } else {
+        String::new()
+    };
+    let dpi_row: Element<'_, Message> = if state.options.format == ImageFormat::WebP {
+        horizontal_space().height(Fixed(0.0)).into()
+    } else {
+        row![
+            text(t.dpi)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input(t.dpi_default, &dpi_str)
+                .on_input_if(editable, Message::DpiChanged)
+                .width(Fixed(72.0))
+                .padding(spacing::XS)
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center)
+        .into()
+    };
+
+    let color_space_row = row![
+        text(t.color_space)
+            .size(typography::CAPTION)
+            .style(iced::theme::Text::Color(txt_secondary)),
+        pick_list(
+            &OutputColorSpace::ALL[..],
+            Some(state.options.color_space),
+            Message::ColorSpaceSelected,
+        )
+        .text_size(typography::CAPTION)
+        .padding(spacing::XS),
+        text(t.input_profiles)
+            .size(typography::CAPTION)
+            .style(iced::theme::Text::Color(txt_secondary)),
+        pick_list(
+            &ColorCorrection::ALL[..],
+            Some(state.options.color_correction),
+            Message::ColorCorrectionSelected,
+        )
+        .text_size(typography::CAPTION)
+        .padding(spacing::XS)
+    ]
+    .spacing(spacing::SM)
+    .align_items(iced::Alignment::Center);
+    // Other color spaces embed their ICC profile, which says it all.
+    let writes_png = state.options.format == ImageFormat::Png
+        || state.options.extra_formats.contains(&ImageFormat::Png)
+        || state.options.keep_source_format;
+    let color_space_row = if writes_png && state.options.color_space == OutputColorSpace::Srgb {
+        color_space_row
+            .push(
+                text(t.png_color_tag)
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary)),
+            )
+            .push(
+                pick_list(
+                    &PngColorTag::ALL[..],
+                    Some(state.options.png_color_tag),
+                    Message::PngColorTagSelected,
+                )
+                .text_size(typography::CAPTION)
+                .padding(spacing::XS),
+            )
+    } else {
+        color_space_row
+    };
+
+    let has_multi_frame = state
+        .files
+        .iter()
+        .any(|f| f.info.is_some_and(|i| i.frames > 1));
+    let frame_row: Element<'_, Message> = if has_multi_frame {
+        let mut r = row![
+            text(t.multi_frame)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            pick_list(
+                &FrameChoice::ALL[..],
+                Some(state.options.frame_choice),
+                Message::FrameChoiceSelected,
+            )
+            .text_size(typography::CAPTION)
+            .padding(spacing::XS)
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center);
+        if state.options.frame_choice == FrameChoice::Number {
+            r = r.push(
+                text_input("1", &state.options.frame_number.to_string())
+                    .on_input_if(editable, Message::FrameNumberChanged)
+                    .width(Fixed(48.0))
+                    .padding(spacing::XS),
+            );
+        }
+        r.into()
     } else {
         horizontal_space().height(Fixed(0.0)).into()
     };
 
     let format_card = card(
         column![
-            text("Output Settings")
+            text(t.output_settings)
                 .size(typography::HEADING)
                 .style(iced::theme::Text::Color(txt)),
             vertical_space().height(Fixed(spacing::SM as f32)),
             row![
                 column![
-                    text("Format")
+                    text(t.format)
                         .size(typography::CAPTION)
                         .style(iced::theme::Text::Color(txt_secondary)),
-                    format_pick
+                    format_checks,
+                    checkbox(t.keep_source_format, state.options.keep_source_format)
+                        .on_toggle_maybe(editable.then_some(Message::ToggleKeepSourceFormat))
+                        .text_size(typography::CAPTION)
                 ]
                 .spacing(spacing::XS),
                 horizontal_space().width(Fixed(spacing::XL as f32)),
                 quality_section
             ]
             .align_items(iced::Alignment::End),
-            metadata_row
+            metadata_row,
+            format_row,
+            credits_row,
+            dpi_row,
+            color_space_row,
+            frame_row
         ]
         .spacing(spacing::SM),
         is_dark,
     );
 
     // Filename options card
-    let prefix_input = text_input("e.g., converted_", &state.options.prefix)
-        .on_input(Message::PrefixChanged)
+    let prefix_input = text_input(t.prefix_placeholder, &state.options.prefix)
+        .on_input_if(editable, Message::PrefixChanged)
+        .padding(spacing::SM);
+
+    let find_input = text_input(t.find_placeholder, &state.options.find_pattern)
+        .on_input_if(editable, Message::FindPatternChanged)
         .padding(spacing::SM);
 
-    let find_input = text_input("Text to find...", &state.options.find_pattern)
-        .on_input(Message::FindPatternChanged)
+    let replace_input = text_input(t.replace_placeholder, &state.options.replace_with)
+        .on_input_if(editable, Message::ReplaceWithChanged)
         .padding(spacing::SM);
 
-    let replace_input = text_input("Replace with...", &state.options.replace_with)
-        .on_input(Message::ReplaceWithChanged)
+    let template_input = text_input(t.template_placeholder, &state.options.filename_template)
+        .on_input_if(editable, Message::FilenameTemplateChanged)
         .padding(spacing::SM);
 
+    let date_format_input = text_input("%Y%m%d", &state.options.date_format)
+        .on_input_if(editable, Message::DateFormatChanged)
+        .width(Fixed(120.0))
+        .padding(spacing::SM);
+
+    let unknown_tokens = unknown_template_tokens(&state.options.filename_template);
+    let date_format_ok = is_valid_date_format(&state.options.date_format);
+    let template_hint = if !unknown_tokens.is_empty() {
+        format!("{}: {{{}}}", t.unknown_tokens, unknown_tokens.join("}, {"))
+    } else if !date_format_ok {
+        t.invalid_date_format.to_string()
+    } else {
+        format!("{}: {{{}}}", t.tokens, TEMPLATE_TOKENS.join("}, {"))
+    };
+    let template_hint_color = if unknown_tokens.is_empty() && date_format_ok {
+        txt_secondary
+    } else {
+        error
+    };
+
     let filename_card = card(
         column![
-            text("Filename Options")
-                .size(typography::HEADING)
-                .style(iced::theme::Text::Color(txt)),
+            row![
+                text(t.filename_options)
+                    .size(typography::HEADING)
+                    .style(iced::theme::Text::Color(txt)),
+                horizontal_space(),
+                pick_list(
+                    state.options.naming_history.clone(),
+                    None::<NamingRule>,
+                    Message::NamingRuleSelected,
+                )
+                .placeholder(t.naming_history)
+                .text_size(typography::CAPTION)
+                .width(Fixed(180.0))
+                .padding(spacing::XS)
+            ]
+            .align_items(iced::Alignment::Center),
             vertical_space().height(Fixed(spacing::XS as f32)),
             row![
                 column![
-                    text("Prefix")
+                    text(t.prefix)
                         .size(typography::CAPTION)
                         .style(iced::theme::Text::Color(txt_secondary)),
                     prefix_input
@@ -174,7 +561,7 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
                 .spacing(spacing::XXS)
                 .width(Length::FillPortion(1)),
                 column![
-                    text("Find & Replace")
+                    text(t.find_replace)
                         .size(typography::CAPTION)
                         .style(iced::theme::Text::Color(txt_secondary)),
                     row![
@@ -189,12 +576,53 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
                 .width(Length::FillPortion(2))
             ]
             .spacing(spacing::LG),
-            checkbox(
-                "Auto Suffix (resolution + quality)",
-                state.options.auto_suffix
-            )
-            .on_toggle(Message::AutoSuffixToggled)
-            .text_size(typography::BODY)
+            row![
+                checkbox(t.auto_suffix, state.options.auto_suffix)
+                    .on_toggle_maybe(editable.then_some(Message::AutoSuffixToggled))
+                    .text_size(typography::BODY),
+                horizontal_space(),
+                text(t.extension)
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary)),
+                pick_list(
+                    &ExtensionCase::ALL[..],
+                    Some(state.options.extension_case),
+                    Message::ExtensionCaseSelected,
+                )
+                .text_size(typography::BODY)
+                .padding(spacing::XS)
+            ]
+            .spacing(spacing::SM)
+            .align_items(iced::Alignment::Center),
+            if state.options.auto_suffix {
+                row![
+                    text(t.size_letter)
+                        .size(typography::CAPTION)
+                        .style(iced::theme::Text::Color(txt_secondary)),
+                    text_input("p", &state.options.suffix_size_letter)
+                        .on_input_if(editable, Message::SuffixSizeLetterChanged)
+                        .width(Fixed(48.0))
+                        .padding(spacing::XS),
+                    text(t.quality_letter)
+                        .size(typography::CAPTION)
+                        .style(iced::theme::Text::Color(txt_secondary)),
+                    text_input("q", &state.options.suffix_quality_letter)
+                        .on_input_if(editable, Message::SuffixQualityLetterChanged)
+                        .width(Fixed(48.0))
+                        .padding(spacing::XS),
+                    checkbox(t.include_resize_mode, state.options.suffix_mode)
+                        .on_toggle_maybe(editable.then_some(Message::SuffixModeToggled))
+                        .text_size(typography::CAPTION)
+                ]
+                .spacing(spacing::SM)
+                .align_items(iced::Alignment::Center)
+            } else {
+                row![]
+            },
+            row![template_input, date_format_input].spacing(spacing::SM),
+            text(template_hint)
+                .size(typography::SMALL)
+                .style(iced::theme::Text::Color(template_hint_color))
         ]
         .spacing(spacing::SM),
         is_dark,
@@ -206,26 +634,45 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
         .custom_output_path
         .as_ref()
         .map(|p| p.to_string_lossy().to_string())
-        .unwrap_or_else(|| "Same as input folder".to_string());
+        .unwrap_or_else(|| t.same_as_input.to_string());
 
-    let browse_btn = button(text("Browse").size(typography::CAPTION))
-        .on_press(Message::BrowseOutputClicked)
+    let browse_btn = button(text(t.browse).size(typography::CAPTION))
+        .on_press_maybe(editable.then_some(Message::BrowseOutputClicked))
         .padding([spacing::XS, spacing::SM])
         .style(iced::theme::Button::Secondary);
 
+    let recent_dirs: Vec<String> = state
+        .options
+        .recent_output_dirs
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect();
+    let recent_selected = recent_dirs
+        .iter()
+        .find(|p| **p == output_path_display)
+        .cloned();
+    let recent_pick = pick_list(recent_dirs, recent_selected, |p| {
+        Message::RecentOutputSelected(std::path::PathBuf::from(p))
+    })
+    .placeholder(t.recent)
+    .text_size(typography::CAPTION)
+    .width(Fixed(120.0))
+    .padding(spacing::XS);
+
     let output_section = column![
         row![
-            text("Output")
+            text(t.output)
                 .size(typography::HEADING)
                 .style(iced::theme::Text::Color(txt)),
             horizontal_space(),
-            checkbox("Custom folder", state.options.use_custom_output)
-                .on_toggle(Message::ToggleCustomOutput)
+            checkbox(t.custom_folder, state.options.use_custom_output)
+                .on_toggle_maybe(editable.then_some(Message::ToggleCustomOutput))
                 .text_size(typography::CAPTION)
         ],
         if state.options.use_custom_output {
             row![
-                text_input("Select folder...", &output_path_display).padding(spacing::SM),
+                text_input(t.select_folder, &output_path_display).padding(spacing::SM),
+                recent_pick,
                 browse_btn
             ]
             .spacing(spacing::SM)
@@ -236,45 +683,240 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
                     .style(iced::theme::Text::Color(txt_secondary))
             )
             .padding(spacing::SM)]
-        }
+        },
+        checkbox(t.format_subfolders, state.options.format_subfolders)
+            .on_toggle_maybe(editable.then_some(Message::FormatSubfoldersToggled))
+            .text_size(typography::CAPTION),
+        row![
+            checkbox(t.output_to_zip, state.options.zip_output)
+                .on_toggle_maybe(editable.then_some(Message::ToggleZipOutput))
+                .text_size(typography::CAPTION),
+            text(
+                state
+                    .options
+                    .zip_path
+                    .as_ref()
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|| t.default_zip.to_string())
+            )
+            .size(typography::CAPTION)
+            .style(iced::theme::Text::Color(txt_secondary))
+            .width(Length::Fill),
+            button(text(t.choose).size(typography::CAPTION))
+                .on_press_maybe(editable.then_some(Message::BrowseZipClicked))
+                .padding([spacing::XS, spacing::SM])
+                .style(iced::theme::Button::Secondary)
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center),
+        row![
+            text(t.if_file_exists)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            pick_list(
+                &ExistingFilePolicy::ALL[..],
+                Some(state.options.existing_file_policy),
+                Message::ExistingFilePolicySelected,
+            )
+            .text_size(typography::CAPTION)
+            .padding(spacing::XS),
+            checkbox(t.dont_ask, state.options.overwrite_without_asking)
+                .on_toggle_maybe(editable.then_some(Message::OverwriteWithoutAskingToggled))
+                .text_size(typography::CAPTION),
+            text(t.ask_above)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("0", &state.options.overwrite_prompt_threshold.to_string())
+                .on_input_if(editable, Message::OverwriteThresholdChanged)
+                .width(Fixed(48.0))
+                .padding(spacing::XS)
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center),
+        row![
+            checkbox(t.verify_output, state.options.verify_output)
+                .on_toggle_maybe(editable.then_some(Message::ToggleVerifyOutput))
+                .text_size(typography::CAPTION),
+            checkbox(t.verify_alpha, state.options.verify_alpha)
+                .on_toggle_maybe(editable.then_some(Message::ToggleVerifyAlpha))
+                .text_size(typography::CAPTION),
+            checkbox(t.delete_originals, state.options.delete_originals)
+                .on_toggle_maybe(editable.then_some(Message::ToggleDeleteOriginals))
+                .text_size(typography::CAPTION)
+        ]
+        .spacing(spacing::LG)
     ]
     .spacing(spacing::SM);
 
     let width_input = text_input("W", &state.options.target_width)
-        .on_input(Message::WidthChanged)
+        .on_input_if(editable, Message::WidthChanged)
         .width(Fixed(60.0))
         .padding(spacing::XS);
     let height_input = text_input("H", &state.options.target_height)
-        .on_input(Message::HeightChanged)
+        .on_input_if(editable, Message::HeightChanged)
         .width(Fixed(60.0))
         .padding(spacing::XS);
 
     let resize_section = column![
         row![
-            text("Resize")
+            text(t.resize)
                 .size(typography::HEADING)
                 .style(iced::theme::Text::Color(txt)),
             horizontal_space(),
-            checkbox("Enable", state.options.resize)
-                .on_toggle(Message::ResizeToggled)
+            checkbox(t.enable, state.options.resize)
+                .on_toggle_maybe(editable.then_some(Message::ResizeToggled))
                 .text_size(typography::CAPTION)
         ],
         if state.options.resize {
+            row![pick_list(
+                &ResizeMode::ALL[..],
+                Some(state.options.resize_mode),
+                Message::ResizeModeSelected,
+            )
+            .text_size(typography::CAPTION)
+            .padding(spacing::XS)]
+        } else {
+            row![]
+        },
+        if state.options.resize && state.options.resize_mode == ResizeMode::FitWithin {
+            row![
+                text(t.longest_side)
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary)),
+                text_input("px", &state.options.longest_side)
+                    .on_input_if(editable, Message::LongestSideChanged)
+                    .width(Fixed(60.0))
+                    .padding(spacing::XS)
+            ]
+            .spacing(spacing::XS)
+            .align_items(iced::Alignment::Center)
+        } else if state.options.resize {
             row![
                 width_input,
                 text("x").style(iced::theme::Text::Color(txt_secondary)),
-                height_input
+                height_input,
+                checkbox(t.lock_aspect, state.options.lock_aspect)
+                    .on_toggle_maybe(editable.then_some(Message::LockAspectToggled))
+                    .text_size(typography::CAPTION),
+                checkbox(t.pad_to_fit, state.options.pad_to_fit)
+                    .on_toggle_maybe(editable.then_some(Message::PadToFitToggled))
+                    .text_size(typography::CAPTION),
+                if state.options.pad_to_fit {
+                    row![text_input("#000000", &state.options.pad_color)
+                        .on_input_if(editable, Message::PadColorChanged)
+                        .width(Fixed(72.0))
+                        .padding(spacing::XS)]
+                } else {
+                    row![]
+                }
             ]
             .spacing(spacing::XS)
             .align_items(iced::Alignment::Center)
         } else {
-            row![text("Original size")
+            row![text(t.original_size)
                 .size(typography::CAPTION)
                 .style(iced::theme::Text::Color(txt_secondary))]
+        },
+        row![
+            text(t.max_megapixels)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("MP", &state.options.max_megapixels)
+                .on_input_if(editable, Message::MaxMegapixelsChanged)
+                .width(Fixed(60.0))
+                .padding(spacing::XS)
+        ]
+        .spacing(spacing::XS)
+        .align_items(iced::Alignment::Center),
+        checkbox(t.remove_letterbox, state.options.remove_letterbox)
+            .on_toggle_maybe(editable.then_some(Message::RemoveLetterboxToggled))
+            .text_size(typography::CAPTION),
+        if state.options.remove_letterbox {
+            row![
+                text(t.letterbox_tolerance)
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary)),
+                slider(
+                    0..=64,
+                    state.options.letterbox_tolerance,
+                    Message::LetterboxToleranceChanged
+                )
+                .on_release(Message::SliderReleased)
+                .width(Fixed(100.0)),
+                text(state.options.letterbox_tolerance.to_string())
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary))
+            ]
+            .spacing(spacing::XS)
+            .align_items(iced::Alignment::Center)
+        } else {
+            row![]
         }
     ]
     .spacing(spacing::SM);
 
+    let adjustment_slider = |label: &'a str, value: i32, on_change: fn(i32) -> Message| {
+        row![
+            text(label)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary))
+                .width(Fixed(80.0)),
+            slider(-100..=100, value, on_change)
+                .on_release(Message::AdjustmentsReleased)
+                .width(Length::Fill),
+            text(format!("{:+}", value))
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary))
+                .width(Fixed(40.0))
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center)
+    };
+    let adjustments = state.options.adjustments;
+    let adjustments_card = card(
+        column![
+            row![
+                text(t.adjustments)
+                    .size(typography::HEADING)
+                    .style(iced::theme::Text::Color(txt)),
+                horizontal_space(),
+                button(text(t.reset).size(typography::CAPTION))
+                    .on_press_maybe(
+                        (editable && !adjustments.is_neutral())
+                            .then_some(Message::AdjustmentsReset)
+                    )
+                    .padding([spacing::XS, spacing::SM])
+                    .style(iced::theme::Button::Secondary)
+            ]
+            .align_items(iced::Alignment::Center),
+            adjustment_slider(
+                t.brightness,
+                adjustments.brightness,
+                Message::BrightnessChanged
+            ),
+            adjustment_slider(t.contrast, adjustments.contrast, Message::ContrastChanged),
+            adjustment_slider(
+                t.saturation,
+                adjustments.saturation,
+                Message::SaturationChanged
+            ),
+            row![
+                checkbox(t.grayscale, adjustments.grayscale)
+                    .on_toggle_maybe(editable.then_some(Message::GrayscaleToggled))
+                    .text_size(typography::CAPTION),
+                checkbox(t.linear_grayscale, adjustments.linear_grayscale)
+                    .on_toggle_maybe(
+                        (editable && adjustments.grayscale)
+                            .then_some(Message::LinearGrayscaleToggled)
+                    )
+                    .text_size(typography::CAPTION)
+            ]
+            .spacing(spacing::LG)
+        ]
+        .spacing(spacing::SM),
+        is_dark,
+    );
+
     let settings_row = row![
         card(output_section, is_dark).width(Length::FillPortion(3)),
         card(resize_section, is_dark).width(Length::FillPortion(2))
@@ -282,56 +924,295 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
     .spacing(spacing::LG);
 
     // Dataset options
-    let gen_txt_btn = button(text("Generate").size(typography::CAPTION))
+    let gen_txt_btn = button(text(t.generate).size(typography::CAPTION))
         .on_press(Message::ManualGenerateLogClicked)
         .padding([spacing::XS, spacing::SM])
         .style(iced::theme::Button::Secondary);
 
-    let dataset_section = row![
-        checkbox("Generate list file", state.options.generate_log)
-            .on_toggle(Message::ToggleGenerateLog)
+    let dataset_section = column![
+        row![
+            checkbox(t.generate_list, state.options.generate_log)
+                .on_toggle_maybe(editable.then_some(Message::ToggleGenerateLog))
+                .text_size(typography::BODY),
+            checkbox(t.numbering, state.options.add_numbering)
+                .on_toggle_maybe(editable.then_some(Message::AddNumberingToggled))
+                .text_size(typography::CAPTION),
+            gen_txt_btn
+        ]
+        .spacing(spacing::LG)
+        .align_items(iced::Alignment::Center),
+        row![
+            checkbox(t.caption_files, state.options.write_captions)
+                .on_toggle_maybe(editable.then_some(Message::ToggleWriteCaptions))
+                .text_size(typography::BODY),
+            if state.options.write_captions {
+                row![
+                    text_input(t.caption_placeholder, &state.options.caption_template)
+                        .on_input_if(editable, Message::CaptionTemplateChanged)
+                        .padding(spacing::XS)
+                ]
+            } else {
+                row![]
+            }
+        ]
+        .spacing(spacing::LG)
+        .align_items(iced::Alignment::Center),
+        checkbox(t.copy_sidecars, state.options.copy_sidecars)
+            .on_toggle_maybe(editable.then_some(Message::CopySidecarsToggled))
             .text_size(typography::BODY),
-        checkbox("# Numbering", state.options.add_numbering)
-            .on_toggle(Message::AddNumberingToggled)
-            .text_size(typography::CAPTION),
-        gen_txt_btn
+        row![
+            button(text(t.contact_sheet).size(typography::CAPTION))
+                .on_press_maybe(
+                    (editable && !state.contact_sheet_running && !state.files.is_empty())
+                        .then_some(Message::ContactSheetClicked)
+                )
+                .padding([spacing::XS, spacing::SM])
+                .style(iced::theme::Button::Secondary),
+            text(t.columns)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("6", &state.options.contact_sheet_columns.to_string())
+                .on_input_if(editable, Message::ContactSheetColumnsChanged)
+                .width(Fixed(40.0))
+                .padding(spacing::XS),
+            text(t.cell_size)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("256", &state.options.contact_sheet_cell.to_string())
+                .on_input_if(editable, Message::ContactSheetCellChanged)
+                .width(Fixed(52.0))
+                .padding(spacing::XS),
+            text(t.background)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+            text_input("#ffffff", &state.options.contact_sheet_background)
+                .on_input_if(editable, Message::ContactSheetBackgroundChanged)
+                .width(Fixed(72.0))
+                .padding(spacing::XS),
+            checkbox(t.labels, state.options.contact_sheet_labels)
+                .on_toggle_maybe(editable.then_some(Message::ContactSheetLabelsToggled))
+                .text_size(typography::CAPTION)
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center)
     ]
-    .spacing(spacing::LG)
-    .align_items(iced::Alignment::Center);
+    .spacing(spacing::SM);
+
+    // Advanced options
+    let max_size_str = state
+        .max_file_size_input
+        .clone()
+        .unwrap_or_else(|| state.options.max_file_size_mb.to_string());
+    let max_size_valid = parse_max_file_size(&max_size_str).is_some();
+    let thread_limit_str = state.options.thread_limit.to_string();
+    let advanced_card = card(
+        column![
+            row![
+                text(t.advanced)
+                    .size(typography::HEADING)
+                    .style(iced::theme::Text::Color(txt)),
+                horizontal_space(),
+                checkbox(t.show, state.show_advanced)
+                    .on_toggle(Message::AdvancedToggled)
+                    .text_size(typography::CAPTION)
+            ],
+            if state.show_advanced {
+                column![
+                    row![
+                        text(t.max_file_size)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary)),
+                        text_input("100", &max_size_str)
+                            .on_input_if(editable, Message::MaxFileSizeChanged)
+                            .on_submit(Message::MaxFileSizeSubmitted)
+                            .width(Fixed(60.0))
+                            .padding(spacing::XS),
+                        text(if max_size_valid {
+                            ""
+                        } else {
+                            t.invalid_max_file_size
+                        })
+                        .size(typography::CAPTION)
+                        .style(iced::theme::Text::Color(error))
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    row![
+                        text(t.threads)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary)),
+                        text_input("", &thread_limit_str)
+                            .on_input_if(editable, Message::ThreadLimitChanged)
+                            .width(Fixed(60.0))
+                            .padding(spacing::XS),
+                        text(t.threads_hint)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary))
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    row![
+                        text(t.output_timestamp)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary)),
+                        pick_list(
+                            &OutputTimestamp::ALL[..],
+                            Some(state.options.output_timestamp),
+                            Message::OutputTimestampSelected,
+                        )
+                        .text_size(typography::CAPTION)
+                        .padding(spacing::XS)
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    row![
+                        text(t.double_click)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary)),
+                        pick_list(
+                            &DoubleClickAction::ALL[..],
+                            Some(state.options.double_click_action),
+                            Message::DoubleClickActionSelected,
+                        )
+                        .text_size(typography::CAPTION)
+                        .padding(spacing::XS)
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    row![
+                        text(t.conversion_order)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary)),
+                        pick_list(
+                            &ConversionOrder::ALL[..],
+                            Some(state.options.conversion_order),
+                            Message::ConversionOrderSelected,
+                        )
+                        .text_size(typography::CAPTION)
+                        .padding(spacing::XS)
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    row![
+                        text(t.srgb_profile)
+                            .size(typography::CAPTION)
+                            .style(iced::theme::Text::Color(txt_secondary)),
+                        text(
+                            state
+                                .options
+                                .custom_srgb_icc
+                                .as_ref()
+                                .map(|p| p.to_string_lossy().to_string())
+                                .unwrap_or_else(|| t.bundled_profile.to_string())
+                        )
+                        .size(typography::CAPTION)
+                        .width(Length::Fill),
+                        button(text(t.choose).size(typography::CAPTION))
+                            .on_press_maybe(editable.then_some(Message::BrowseSrgbProfileClicked))
+                            .padding([spacing::XS, spacing::SM])
+                            .style(iced::theme::Button::Secondary),
+                        button(text(t.clear).size(typography::CAPTION))
+                            .on_press_maybe(
+                                (editable && state.options.custom_srgb_icc.is_some())
+                                    .then_some(Message::SrgbProfileCleared)
+                            )
+                            .padding([spacing::XS, spacing::SM])
+                            .style(iced::theme::Button::Secondary)
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    checkbox(t.restore_queue, state.options.restore_queue)
+                        .on_toggle(Message::ToggleRestoreQueue)
+                        .text_size(typography::CAPTION),
+                    checkbox(t.fail_fast, state.options.fail_fast)
+                        .on_toggle_maybe(editable.then_some(Message::ToggleFailFast))
+                        .text_size(typography::CAPTION),
+                    checkbox(t.release_memory, state.options.release_memory)
+                        .on_toggle(Message::ToggleReleaseMemory)
+                        .text_size(typography::CAPTION),
+                    checkbox(t.failure_log, state.options.failure_log)
+                        .on_toggle_maybe(editable.then_some(Message::ToggleFailureLog))
+                        .text_size(typography::CAPTION),
+                    button(text(t.copy_cli).size(typography::CAPTION))
+                        .on_press(Message::CopyCliClicked)
+                        .padding([spacing::XS, spacing::SM])
+                        .style(iced::theme::Button::Secondary),
+                    button(text(t.reset_defaults).size(typography::CAPTION))
+                        .on_press_maybe(editable.then_some(Message::ResetSettingsClicked))
+                        .padding([spacing::XS, spacing::SM])
+                        .style(iced::theme::Button::Destructive)
+                ]
+                .spacing(spacing::SM)
+            } else {
+                column![]
+            }
+        ]
+        .spacing(spacing::SM),
+        is_dark,
+    );
 
     // File list section
     let file_count = state.files.len();
     let selected_count = state.selected_indices.len();
     let list_title = if selected_count > 0 {
-        format!("Files ({} of {} selected)", selected_count, file_count)
+        (t.files_selected)(selected_count, file_count)
     } else {
-        format!("Files ({})", file_count)
+        (t.files)(file_count)
     };
 
-    let delete_btn = button(text("Delete").size(typography::CAPTION))
+    let delete_btn = button(text(t.delete).size(typography::CAPTION))
         .on_press(Message::DeleteSelected)
         .padding([spacing::XS, spacing::SM])
         .style(iced::theme::Button::Destructive);
 
-    let clear_btn = button(text("Clear All").size(typography::CAPTION))
+    let clear_btn = button(text(t.clear_all).size(typography::CAPTION))
         .on_press(Message::ClearList)
         .padding([spacing::XS, spacing::SM])
         .style(iced::theme::Button::Secondary);
 
+    let is_compact = state.options.ui_density == UiDensity::Compact;
+
     let list_header = row![
         text(&list_title)
             .size(typography::HEADING)
             .style(iced::theme::Text::Color(txt)),
         horizontal_space(),
+        checkbox(t.compact, is_compact)
+            .on_toggle(Message::CompactListToggled)
+            .text_size(typography::CAPTION),
         delete_btn,
         clear_btn
     ]
     .spacing(spacing::SM)
     .align_items(iced::Alignment::Center);
 
+    let filter_chips = StatusFilter::ALL
+        .iter()
+        .fold(row![].spacing(spacing::XXS), |r, &filter| {
+            let label = match filter {
+                StatusFilter::All => t.filter_all,
+                StatusFilter::Pending => t.filter_pending,
+                StatusFilter::Done => t.filter_done,
+                StatusFilter::Error => t.filter_error,
+                StatusFilter::Skipped => t.filter_skipped,
+            };
+            let count = state.files.iter().filter(|f| filter.matches(f)).count();
+            r.push(
+                button(text(format!("{} ({})", label, count)).size(typography::CAPTION))
+                    .on_press(Message::StatusFilterSelected(filter))
+                    .padding([spacing::XXS, spacing::XS])
+                    .style(if filter == state.status_filter {
+                        iced::theme::Button::Primary
+                    } else {
+                        iced::theme::Button::Secondary
+                    }),
+            )
+        });
+    let filter = state.status_filter;
+
     let file_list: Element<Message> = if state.files.is_empty() {
         container(
-            column![text("Drop files here or click Select Files")
+            column![text(t.drop_hint)
                 .size(typography::BODY)
                 .style(iced::theme::Text::Color(txt_secondary))]
             .spacing(spacing::SM)
@@ -342,58 +1223,221 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
         .center_x()
         .center_y()
         .into()
+    } else if !state.files.iter().any(|f| filter.matches(f)) {
+        container(
+            text(t.no_matching_files)
+                .size(typography::BODY)
+                .style(iced::theme::Text::Color(txt_secondary)),
+        )
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
     } else {
         let items: Vec<Element<Message>> = state
             .files
             .iter()
             .enumerate()
+            .filter(|(_, f)| filter.matches(f))
             .map(|(i, f)| {
+                let upscaled = f.info.is_some_and(|info| {
+                    let (cw, ch) = cropped_dimensions(f.crop, info.width, info.height);
+                    let (w, h) = resized_dimensions(&state.options, cw, ch);
+                    w > cw || h > ch
+                });
                 file_item_view(
                     i,
                     f,
-                    state.dragging_index,
-                    state.hovered_index,
+                    state.dragging_id,
+                    state.hovered_id,
                     state.selected_indices.contains(&i),
+                    is_compact,
+                    upscaled,
                     is_dark,
                     success,
                     error,
+                    warning,
                     processing,
                     txt,
                     txt_secondary,
+                    t,
                 )
             })
             .collect();
 
         scrollable(column(items).spacing(spacing::XXS))
+            .id(file_list_scroll_id())
+            .on_scroll(|viewport| {
+                let max = viewport.content_bounds().height - viewport.bounds().height;
+                Message::FileListScrolled(viewport.absolute_offset().y, max.max(0.0))
+            })
             .width(Length::Fill)
             .height(Length::Fill)
             .into()
     };
 
-    let list_card = container(file_list)
-        .padding(spacing::SM)
-        .width(Length::Fill)
-        .height(Fixed(dimensions::FILE_LIST_HEIGHT))
-        .style(card_style(is_dark));
+    let list_card = mouse_area(
+        container(file_list)
+            .padding(spacing::SM)
+            .width(Length::Fill)
+            .height(Fixed(state.options.file_list_height))
+            .style(card_style(is_dark)),
+    )
+    .on_move(|p| Message::FileListPointerMoved(Some(p.y)))
+    .on_exit(Message::FileListPointerMoved(None));
+
+    let list_splitter = mouse_area(
+        container(text(""))
+            .width(Length::Fill)
+            .height(Fixed(dimensions::SPLITTER_HEIGHT))
+            .style(move |_: &Theme| container::Appearance {
+                background: Some(Background::Color(border)),
+                border: iced::Border {
+                    radius: 3.0.into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+    )
+    .on_press(Message::ListResizeStarted)
+    .interaction(iced::mouse::Interaction::ResizingVertically);
+
+    // Preview of the most recently selected file
+    let preview_card: Element<Message> = match state
+        .preview_id
+        .and_then(|id| state.files.iter().find(|f| f.id == id))
+    {
+        Some(file) => {
+            let body: Element<Message> = match state.previews.get(&file.id) {
+                Some(Some(preview)) => {
+                    let shown = match &state.crop_preview {
+                        Some((id, dimmed)) if *id == file.id && file.crop.is_some() => {
+                            dimmed.clone()
+                        }
+                        _ => preview.handle.clone(),
+                    };
+                    // Sized explicitly so cursor positions map straight onto the preview.
+                    let (w, h) = preview_display_size(preview);
+                    mouse_area(
+                        image(shown)
+                            .width(Fixed(w))
+                            .height(Fixed(h))
+                            .content_fit(iced::ContentFit::Fill),
+                    )
+                    .on_move(move |p| Message::CropPointerMoved(p.x / w, p.y / h))
+                    .on_press(Message::CropDragStarted)
+                    .on_release(Message::CropDragEnded)
+                    .interaction(iced::mouse::Interaction::Crosshair)
+                    .into()
+                }
+                Some(None) => text(t.preview_unavailable)
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary))
+                    .into(),
+                None => text(t.loading_preview)
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(txt_secondary))
+                    .into(),
+            };
+            let histogram: Element<Message> = match state.histograms.get(&file.id) {
+                Some(histogram) => canvas(HistogramChart { histogram })
+                    .width(Length::Fill)
+                    .height(Fixed(dimensions::HISTOGRAM_HEIGHT))
+                    .into(),
+                None => column![].into(),
+            };
+            let sweep: Element<Message> = match &state.quality_sweep {
+                Some((id, points)) if *id == file.id && !points.is_empty() => {
+                    sweep_chart(points, t, primary, success, txt_secondary)
+                }
+                _ => column![].into(),
+            };
+            let sweep_btn: Element<Message> = if state.options.simple_view {
+                column![].into()
+            } else {
+                button(text(t.quality_sweep).size(typography::CAPTION))
+                    .on_press_maybe(
+                        (!state.is_processing
+                            && !state.sweep_running
+                            && state.options.format != ImageFormat::Png)
+                            .then_some(Message::QualitySweepClicked),
+                    )
+                    .padding([spacing::XS, spacing::SM])
+                    .style(iced::theme::Button::Secondary)
+                    .into()
+            };
+            card(
+                column![
+                    row![
+                        text(file.path.file_name().unwrap_or_default().to_string_lossy())
+                            .size(typography::HEADING)
+                            .style(iced::theme::Text::Color(txt))
+                            .width(Length::Fill),
+                        button(text(t.edit_externally).size(typography::CAPTION))
+                            .on_press_maybe(
+                                (!state.is_processing)
+                                    .then_some(Message::EditExternallyClicked(file.id)),
+                            )
+                            .padding([spacing::XS, spacing::SM])
+                            .style(iced::theme::Button::Secondary),
+                        sweep_btn,
+                    ]
+                    .spacing(spacing::SM)
+                    .align_items(iced::Alignment::Center),
+                    container(body).width(Length::Fill).center_x(),
+                    crop_row(state, file, t, txt_secondary),
+                    histogram,
+                    sweep
+                ]
+                .spacing(spacing::SM),
+                is_dark,
+            )
+            .into()
+        }
+        None => column![].into(),
+    };
 
     // Progress and status bar
+    let batch_count = state.files.iter().filter(|f| state.in_batch(f)).count();
     let done = state
         .files
         .iter()
+        .filter(|f| state.in_batch(f))
         .filter(|f| matches!(f.status, FileStatus::Done | FileStatus::Error(_)))
         .count();
-    let progress = if file_count > 0 {
-        done as f32 / file_count as f32
-    } else {
-        0.0
+    // Weighted by input bytes so one large file advances the bar more than a small icon.
+    let progress = match state.batch_progress {
+        (done_bytes, total_bytes) if total_bytes > 0 => {
+            (done_bytes as f64 / total_bytes as f64).min(1.0) as f32
+        }
+        _ if batch_count > 0 => done as f32 / batch_count as f32,
+        _ => 0.0,
     };
 
     let status_text = if state.is_processing {
-        format!("Processing {} of {}...", done, file_count)
+        (t.processing_progress)(done, batch_count)
+    } else if let Some((input, output)) = state.batch_sizes {
+        let change = 100.0 * (1.0 - output as f64 / input as f64);
+        format!(
+            "{} {:.0}% ({} → {})",
+            if change >= 0.0 { t.saved } else { t.grew },
+            change.abs(),
+            format_bytes(input),
+            format_bytes(output)
+        )
+    } else if file_count == 0 && state.empty_queue_hint {
+        t.add_files_first.to_string()
+    } else if let Some(run) = state.last_run {
+        (t.last_run)(
+            run.files,
+            run.failed,
+            &format_bytes(run.input_bytes.saturating_sub(run.output_bytes)),
+        )
     } else if file_count == 0 {
-        "Ready - add files to begin".to_string()
+        t.ready.to_string()
     } else {
-        format!("{} files ready", file_count)
+        (t.files_ready)(file_count)
     };
 
     let is_processing = state.is_processing;
@@ -434,23 +1478,177 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
     .align_items(iced::Alignment::Center)
     .padding([spacing::SM, 0]);
 
+    let settings_warning: Element<'_, Message> = match &state.settings_warning {
+        Some(warning_text) => row![
+            text(warning_text)
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(warning))
+                .width(Length::Fill),
+            button(text(t.dismiss).size(typography::CAPTION))
+                .on_press(Message::SettingsWarningDismissed)
+                .padding([spacing::XS, spacing::SM])
+                .style(iced::theme::Button::Secondary)
+        ]
+        .spacing(spacing::SM)
+        .align_items(iced::Alignment::Center)
+        .into(),
+        None => column![].into(),
+    };
+
+    let failed_downloads: Element<'_, Message> = if state.failed_downloads.is_empty() {
+        column![].into()
+    } else {
+        let failures = state.failed_downloads.iter().map(|(url, reason)| {
+            text(format!("{}: {}", url, reason))
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(warning))
+                .into()
+        });
+        column![
+            row![
+                text((t.downloads_failed)(state.failed_downloads.len()))
+                    .size(typography::CAPTION)
+                    .style(iced::theme::Text::Color(warning))
+                    .width(Length::Fill),
+                button(text(t.dismiss).size(typography::CAPTION))
+                    .on_press(Message::FailedDownloadsDismissed)
+                    .padding([spacing::XS, spacing::SM])
+                    .style(iced::theme::Button::Secondary)
+            ]
+            .spacing(spacing::SM)
+            .align_items(iced::Alignment::Center),
+            column(failures).spacing(spacing::XS)
+        ]
+        .spacing(spacing::XS)
+        .into()
+    };
+
+    // Log panel
+    let log_card = card(
+        column![
+            row![
+                text(t.log)
+                    .size(typography::HEADING)
+                    .style(iced::theme::Text::Color(txt)),
+                horizontal_space(),
+                button(text(t.copy_log).size(typography::CAPTION))
+                    .on_press(Message::CopyLogClicked)
+                    .padding([spacing::XS, spacing::SM])
+                    .style(iced::theme::Button::Secondary),
+                checkbox(t.show, state.show_log)
+                    .on_toggle(Message::LogToggled)
+                    .text_size(typography::CAPTION)
+            ]
+            .spacing(spacing::SM)
+            .align_items(iced::Alignment::Center),
+            if state.show_log {
+                let lines = crate::logging::lines();
+                let body = if lines.is_empty() {
+                    t.no_events.to_string()
+                } else {
+                    lines.join("\n")
+                };
+                column![scrollable(
+                    text(body)
+                        .size(typography::CAPTION)
+                        .font(iced::Font::MONOSPACE)
+                        .style(iced::theme::Text::Color(txt_secondary))
+                )
+                .height(Fixed(dimensions::LOG_PANEL_HEIGHT))]
+            } else {
+                column![]
+            }
+        ]
+        .spacing(spacing::SM),
+        is_dark,
+    );
+
+    // About panel with the codecs this build loaded
+    let about_card = card(
+        column![
+            row![
+                text(t.about)
+                    .size(typography::HEADING)
+                    .style(iced::theme::Text::Color(txt)),
+                horizontal_space(),
+                button(text(t.copy_diagnostics).size(typography::CAPTION))
+                    .on_press(Message::CopyDiagnosticsClicked)
+                    .padding([spacing::XS, spacing::SM])
+                    .style(iced::theme::Button::Secondary),
+                checkbox(t.show, state.show_about)
+                    .on_toggle(Message::AboutToggled)
+                    .text_size(typography::CAPTION)
+            ]
+            .spacing(spacing::SM)
+            .align_items(iced::Alignment::Center),
+            if state.show_about {
+                column![text(crate::diagnostics::report().join("\n"))
+                    .size(typography::CAPTION)
+                    .font(iced::Font::MONOSPACE)
+                    .style(iced::theme::Text::Color(txt_secondary))]
+            } else {
+                column![]
+            }
+        ]
+        .spacing(spacing::SM),
+        is_dark,
+    );
+
+    // The simple view keeps format, quality, and the file list; the rest is one click away.
+    let (filename_card, settings_row, adjustments_card, dataset_section, advanced_card) =
+        if state.options.simple_view {
+            (
+                column![].into(),
+                column![].into(),
+                column![].into(),
+                column![].into(),
+                column![].into(),
+            )
+        } else {
+            (
+                Element::from(filename_card),
+                Element::from(settings_row),
+                Element::from(adjustments_card),
+                Element::from(dataset_section),
+                Element::from(advanced_card),
+            )
+        };
+
     // Main layout
     let main_content = column![
         header,
         container(
             column![
-                row![add_files_btn, horizontal_space(), convert_btn]
-                    .spacing(spacing::LG)
-                    .align_items(iced::Alignment::Center),
+                row![
+                    add_files_btn,
+                    horizontal_space(),
+                    checkbox(t.draft_mode, state.options.draft_mode)
+                        .on_toggle_maybe(editable.then_some(Message::DraftModeToggled))
+                        .text_size(typography::CAPTION),
+                    preflight_btn,
+                    convert_selected_btn,
+                    convert_btn
+                ]
+                .spacing(spacing::LG)
+                .align_items(iced::Alignment::Center),
+                url_row,
+                failed_downloads,
+                settings_warning,
                 vertical_space().height(Fixed(spacing::LG as f32)),
                 format_card,
                 filename_card,
                 settings_row,
+                adjustments_card,
                 dataset_section,
+                advanced_card,
                 vertical_space().height(Fixed(spacing::SM as f32)),
                 list_header,
-                list_card,
-                status_bar
+                filter_chips,
+                column![list_card, list_splitter].spacing(spacing::XXS),
+                preview_card,
+                status_bar,
+                log_card,
+                about_card
             ]
             .spacing(spacing::MD)
         )
@@ -468,6 +1666,234 @@ pub fn view<'a>(state: &'a AppState) -> Element<'a, Message> {
         .into()
 }
 
+/// On-screen size of a preview scaled to the preview height.
+fn preview_display_size(preview: &Preview) -> (f32, f32) {
+    let height = dimensions::PREVIEW_HEIGHT;
+    (
+        preview.width as f32 * height / preview.height.max(1) as f32,
+        height,
+    )
+}
+
+/// Crop hint, or the cropped and final output size with a button to clear the crop.
+fn crop_row<'a>(
+    state: &'a AppState,
+    file: &'a FileItem,
+    t: &'static Strings,
+    txt_secondary: Color,
+) -> Element<'a, Message> {
+    let (Some(crop), Some(info)) = (file.crop, file.info) else {
+        return text(t.crop_hint)
+            .size(typography::CAPTION)
+            .style(iced::theme::Text::Color(txt_secondary))
+            .into();
+    };
+    // Previews are upright while the prefetched size is not, so follow the preview's shape.
+    let portrait = match state.previews.get(&file.id) {
+        Some(Some(preview)) => preview.height > preview.width,
+        _ => info.height > info.width,
+    };
+    let (w, h) = if portrait == (info.height > info.width) {
+        (info.width, info.height)
+    } else {
+        (info.height, info.width)
+    };
+    let (cw, ch) = cropped_dimensions(Some(crop), w, h);
+    let (ow, oh) = resized_dimensions(&state.options, cw, ch);
+    row![
+        text(format!("{} {}×{} → {}×{}", t.crop, cw, ch, ow, oh))
+            .size(typography::CAPTION)
+            .style(iced::theme::Text::Color(txt_secondary)),
+        horizontal_space(),
+        button(text(t.clear_crop).size(typography::CAPTION))
+            .on_press_maybe((!state.is_processing).then_some(Message::CropCleared))
+            .padding([spacing::XS, spacing::SM])
+            .style(iced::theme::Button::Secondary),
+    ]
+    .spacing(spacing::SM)
+    .align_items(iced::Alignment::Center)
+    .into()
+}
+
+/// Overlaid red, green, and blue histogram areas scaled to the tallest bin.
+struct HistogramChart<'a> {
+    histogram: &'a Histogram,
+}
+
+impl canvas::Program<Message> for HistogramChart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &iced::Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let (width, height) = (frame.width(), frame.height());
+        let peak = self
+            .histogram
+            .channels
+            .iter()
+            .flatten()
+            .copied()
+            .max()
+            .unwrap_or(0)
+            .max(1) as f32;
+        let colors = [
+            Color::from_rgba(0.91, 0.29, 0.33, 0.45),
+            Color::from_rgba(0.16, 0.71, 0.45, 0.45),
+            Color::from_rgba(0.35, 0.47, 0.98, 0.45),
+        ];
+        for (counts, color) in self.histogram.channels.iter().zip(colors) {
+            let area = canvas::Path::new(|path| {
+                path.move_to(Point::new(0.0, height));
+                for (value, &count) in counts.iter().enumerate() {
+                    path.line_to(Point::new(
+                        value as f32 * width / 255.0,
+                        height * (1.0 - count as f32 / peak),
+                    ));
+                }
+                path.line_to(Point::new(width, height));
+                path.close();
+            });
+            frame.fill(&area, color);
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Quality sweep chart with a caption giving the size and SSIM range.
+fn sweep_chart<'a>(
+    points: &'a [SweepPoint],
+    t: &'static Strings,
+    size_color: Color,
+    ssim_color: Color,
+    txt_secondary: Color,
+) -> Element<'a, Message> {
+    let (first, last) = (points[0], points[points.len() - 1]);
+    let ssim_range = match (first.ssim, last.ssim) {
+        (Some(low), Some(high)) => format!("  ·  SSIM {:.3} – {:.3}", low, high),
+        _ => String::new(),
+    };
+    column![
+        canvas(SweepChart {
+            points,
+            size_color,
+            ssim_color,
+            label_color: txt_secondary,
+        })
+        .width(Length::Fill)
+        .height(Fixed(dimensions::SWEEP_CHART_HEIGHT)),
+        text(format!(
+            "{}: {} (q{}) – {} (q{}){}",
+            t.sweep_size,
+            format_bytes(first.size),
+            first.quality,
+            format_bytes(last.size),
+            last.quality,
+            ssim_range
+        ))
+        .size(typography::CAPTION)
+        .style(iced::theme::Text::Color(txt_secondary))
+    ]
+    .spacing(spacing::XS)
+    .into()
+}
+
+/// Output size (scaled to the largest) and SSIM (scaled from the lowest to 1) per quality,
+/// with the qualities labelled along the bottom.
+struct SweepChart<'a> {
+    points: &'a [SweepPoint],
+    size_color: Color,
+    ssim_color: Color,
+    label_color: Color,
+}
+
+impl canvas::Program<Message> for SweepChart<'_> {
+    type State = ();
+
+    fn draw(
+        &self,
+        _state: &(),
+        renderer: &iced::Renderer,
+        _theme: &Theme,
+        bounds: Rectangle,
+        _cursor: iced::mouse::Cursor,
+    ) -> Vec<canvas::Geometry> {
+        const LABEL_SPACE: f32 = 14.0;
+        let mut frame = canvas::Frame::new(renderer, bounds.size());
+        let (width, height) = (frame.width(), frame.height() - LABEL_SPACE);
+        let step = width / self.points.len().max(2).saturating_sub(1) as f32;
+        let x = |i: usize| (i as f32 * step).clamp(4.0, width - 4.0);
+        let max_size = self.points.iter().map(|p| p.size).max().unwrap_or(0).max(1) as f32;
+        let min_ssim = self
+            .points
+            .iter()
+            .filter_map(|p| p.ssim)
+            .fold(1.0_f64, f64::min)
+            .min(0.999);
+        let series: [(Color, Vec<Option<f32>>); 2] = [
+            (
+                self.size_color,
+                self.points
+                    .iter()
+                    .map(|p| Some(p.size as f32 / max_size))
+                    .collect(),
+            ),
+            (
+                self.ssim_color,
+                self.points
+                    .iter()
+                    .map(|p| p.ssim.map(|s| ((s - min_ssim) / (1.0 - min_ssim)) as f32))
+                    .collect(),
+            ),
+        ];
+        for (color, values) in series {
+            let line = canvas::Path::new(|path| {
+                let mut started = false;
+                for (i, value) in values.iter().enumerate() {
+                    let Some(value) = value else {
+                        started = false;
+                        continue;
+                    };
+                    let point = Point::new(x(i), 2.0 + (height - 4.0) * (1.0 - value));
+                    if started {
+                        path.line_to(point);
+                    } else {
+                        path.move_to(point);
+                        started = true;
+                    }
+                    path.circle(point, 2.0);
+                    path.move_to(point);
+                }
+            });
+            frame.stroke(
+                &line,
+                canvas::Stroke::default().with_color(color).with_width(2.0),
+            );
+        }
+        for (i, point) in self.points.iter().enumerate() {
+            frame.fill_text(canvas::Text {
+                content: point.quality.to_string(),
+                position: Point::new(x(i), height + 2.0),
+                color: self.label_color,
+                size: 10.0.into(),
+                horizontal_alignment: iced::alignment::Horizontal::Center,
+                ..canvas::Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Fixed id of the file list scrollable, used to restore its offset while statuses change.
+pub fn file_list_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("file-list")
+}
+
 /// Creates a styled card container.
 fn card<'a>(
     content: impl Into<Element<'a, Message>>,
@@ -499,22 +1925,105 @@ fn card_style(is_dark: bool) -> impl Fn(&Theme) -> container::Appearance {
     }
 }
 
+/// Formats a byte count as KB or MB, e.g. "2.3 MB".
+pub fn format_bytes(bytes: u64) -> String {
+    let size = bytes as f64;
+    if size >= 1024.0 * 1024.0 {
+        format!("{:.1} MB", size / (1024.0 * 1024.0))
+    } else {
+        format!("{:.0} KB", (size / 1024.0).max(1.0))
+    }
+}
+
+/// Formats a conversion duration as e.g. "340 ms" or "2.4 s".
+fn format_elapsed(elapsed: std::time::Duration) -> String {
+    if elapsed.as_millis() < 1000 {
+        format!("{} ms", elapsed.as_millis())
+    } else {
+        format!("{:.1} s", elapsed.as_secs_f64())
+    }
+}
+
+/// Formats prefetched file info as e.g. "1920×1080 · 2.3 MB".
+fn format_file_info(info: FileInfo, t: &Strings) -> String {
+    let size = format_bytes(info.size_bytes);
+    if info.frames > 1 {
+        format!(
+            "{}×{} · {} · {} {}",
+            info.width, info.height, size, info.frames, t.frames
+        )
+    } else {
+        format!("{}×{} · {}", info.width, info.height, size)
+    }
+}
+
+/// Output size after conversion, or a note that the file was not resized.
+fn resize_badge(file: &FileItem, t: &Strings) -> String {
+    // Copied sidecars have no pixel size to report.
+    let output = file
+        .report
+        .as_ref()
+        .map(|r| r.dimensions)
+        .filter(|&(w, h)| w > 0 && h > 0);
+    match (file.info, output) {
+        (Some(info), Some((w, h))) if (info.width, info.height) == (w, h) => {
+            t.not_resized.to_string()
+        }
+        (_, Some((w, h))) => format!("→ {}×{}", w, h),
+        _ => String::new(),
+    }
+}
+
+/// Short source format label and its color, read from the file extension.
+fn format_tag(path: &std::path::Path, is_dark: bool) -> Option<(String, Color)> {
+    let format = SourceFormat::from_extension(&path.extension()?.to_string_lossy())?;
+    let (primary, success, warning, accent, muted) = if is_dark {
+        (
+            dark::PRIMARY,
+            dark::SUCCESS,
+            dark::WARNING,
+            dark::ACCENT,
+            dark::MUTED,
+        )
+    } else {
+        (
+            colors::PRIMARY,
+            colors::SUCCESS,
+            colors::WARNING,
+            colors::ACCENT,
+            colors::MUTED,
+        )
+    };
+    let color = match format {
+        SourceFormat::Jpeg => warning,
+        SourceFormat::Png => primary,
+        SourceFormat::WebP => success,
+        SourceFormat::Heif | SourceFormat::Avif => accent,
+        SourceFormat::Gif | SourceFormat::Tiff | SourceFormat::Svg => muted,
+    };
+    Some((format.extension().to_uppercase(), color))
+}
+
 /// Renders individual file item in list.
 fn file_item_view(
     index: usize,
     file: &FileItem,
-    dragging: Option<usize>,
-    hovered: Option<usize>,
+    dragging: Option<uuid::Uuid>,
+    hovered: Option<uuid::Uuid>,
     selected: bool,
+    compact: bool,
+    upscaled: bool,
     is_dark: bool,
     success: Color,
     error: Color,
+    warning: Color,
     processing: Color,
     txt: Color,
     txt_secondary: Color,
+    t: &'static Strings,
 ) -> Element<'static, Message> {
-    let is_dragging = dragging == Some(index);
-    let is_hovered = hovered == Some(index);
+    let is_dragging = dragging == Some(file.id);
+    let is_hovered = hovered == Some(file.id);
 
     let status_el: Element<'static, Message> = match &file.status {
         FileStatus::Pending => text("").into(),
@@ -522,14 +2031,30 @@ fn file_item_view(
             .size(typography::BODY)
             .style(iced::theme::Text::Color(processing))
             .into(),
-        FileStatus::Done => text("OK")
-            .size(typography::BODY)
-            .style(iced::theme::Text::Color(success))
-            .into(),
-        FileStatus::Error(e) => text(format!("ERR: {}", e.chars().take(20).collect::<String>()))
-            .size(typography::CAPTION)
-            .style(iced::theme::Text::Color(error))
-            .into(),
+        FileStatus::Done => text({
+            let mut status = t.ok.to_string();
+            if file.verified {
+                status.push_str(&format!(" ({})", t.verified));
+            }
+            if let Some(quality) = file.report.as_ref().and_then(|r| r.quality) {
+                status.push_str(&format!(" · q{}", quality));
+            }
+            if let Some(note) = &file.note {
+                status.push_str(&format!(" · {}", note));
+            }
+            status
+        })
+        .size(typography::BODY)
+        .style(iced::theme::Text::Color(success))
+        .into(),
+        FileStatus::Error(e) => text(format!(
+            "{}: {}",
+            t.error,
+            e.chars().take(20).collect::<String>()
+        ))
+        .size(typography::CAPTION)
+        .style(iced::theme::Text::Color(error))
+        .into(),
     };
 
     let file_name = file.path.file_name().unwrap_or_default().to_string_lossy();
@@ -556,34 +2081,103 @@ fn file_item_view(
         Color::TRANSPARENT
     };
 
-    let drag_handle = button(
-        text("::")
-            .size(typography::CAPTION)
-            .style(iced::theme::Text::Color(txt_secondary)),
+    let row_padding = if compact {
+        [spacing::XXS, spacing::SM]
+    } else {
+        [spacing::XS, spacing::SM]
+    };
+
+    // The handle tracks the whole press-drag-release gesture itself instead of relying on the
+    // window-level release, which touchpads deliver unreliably.
+    let drag_handle = mouse_area(
+        container(
+            text("::")
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+        )
+        .padding(row_padding),
     )
-    .on_press(Message::ItemDragStarted(index))
-    .padding([spacing::XS, spacing::SM])
-    .style(iced::theme::Button::Text);
+    .on_press(Message::ItemDragStarted(file.id))
+    .on_release(Message::ItemDropped(file.id))
+    .on_enter(Message::ItemHovered(Some(file.id)))
+    .interaction(if dragging.is_some() {
+        iced::mouse::Interaction::Grabbing
+    } else {
+        iced::mouse::Interaction::Grab
+    });
 
-    let content = row![
-        text(format!("{:02}", index + 1))
+    let mut content = row![].spacing(spacing::SM);
+    if !compact {
+        content = content.push(
+            text(format!("{:02}", index + 1))
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary))
+                .width(Fixed(24.0)),
+        );
+    }
+    let tag: Element<'static, Message> = match format_tag(&file.path, is_dark) {
+        Some((label, color)) => container(
+            text(label)
+                .size(typography::SMALL)
+                .style(iced::theme::Text::Color(color)),
+        )
+        .padding([0, spacing::XS])
+        .style(move |_: &Theme| container::Appearance {
+            background: Some(Background::Color(Color { a: 0.15, ..color })),
+            border: iced::Border {
+                radius: 4.0.into(),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .into(),
+        None => text("").into(),
+    };
+    let content = content
+        .push(container(tag).width(Fixed(40.0)))
+        .push(
+            text(file_name.to_string())
+                .size(if compact {
+                    typography::CAPTION
+                } else {
+                    typography::BODY
+                })
+                .style(iced::theme::Text::Color(txt))
+                .width(Length::Fill),
+        )
+        .push(
+            text(
+                file.info
+                    .map(|info| format_file_info(info, t))
+                    .unwrap_or_default(),
+            )
             .size(typography::CAPTION)
-            .style(iced::theme::Text::Color(txt_secondary))
-            .width(Fixed(24.0)),
-        text(file_name.to_string())
-            .size(typography::BODY)
-            .style(iced::theme::Text::Color(txt))
-            .width(Length::Fill),
-        status_el
-    ]
-    .spacing(spacing::SM)
-    .align_items(iced::Alignment::Center)
-    .padding([spacing::XS, spacing::SM]);
+            .style(iced::theme::Text::Color(txt_secondary)),
+        )
+        .push(
+            text(resize_badge(file, t))
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+        )
+        .push(
+            text(file.elapsed.map(format_elapsed).unwrap_or_default())
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(txt_secondary)),
+        )
+        .push(
+            text(if upscaled { t.upscale } else { "" })
+                .size(typography::CAPTION)
+                .style(iced::theme::Text::Color(warning)),
+        )
+        .push(status_el)
+        .align_items(iced::Alignment::Center)
+        .padding(row_padding);
 
     let item = container(
         mouse_area(content)
             .on_press(Message::ToggleSelection(index))
-            .on_enter(Message::ItemHovered(Some(index))),
+            .on_release(Message::ItemDropped(file.id))
+            .on_enter(Message::ItemHovered(Some(file.id))),
     )
     .style(move |_: &Theme| container::Appearance {
         background: Some(Background::Color(bg)),